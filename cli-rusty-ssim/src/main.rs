@@ -1,5 +1,17 @@
+use chrono::NaiveDate;
 use clap::{Args, Parser, Subcommand};
-use rusty_ssim_core::{ssim_to_csv, ssim_to_parquets};
+use rusty_ssim_core::{
+    AircraftVersions, CABIN_SEATS_COLUMN, ColumnSelection, Compression, CsvOptions, ParquetLayout,
+    ParseMode, PartitionKey, QuoteStyle, SEAT_INPUT_COLUMNS, SsimFilter, SsimOptions, WriteMode,
+    add_leg_times, add_seat_columns, parse_quote_style, parse_separator, ssim_to_csv_from_reader,
+    ssim_to_csv_with_options, ssim_to_dated_flights_from_reader,
+    ssim_to_dated_flights_with_options, ssim_to_ipc_from_reader, ssim_to_ipc_with_options,
+    ssim_to_ndjson_from_reader, ssim_to_ndjson_with_options, ssim_to_parquets_from_reader,
+    ssim_to_parquets_with_options, to_csv, to_parquet,
+};
+
+/// `--ssim-path` value that reads the SSIM data from stdin.
+const STDIN_PATH: &str = "-";
 
 #[derive(Parser)]
 #[command(name = "ssim")]
@@ -15,11 +27,17 @@ enum Commands {
     Parquet(SsimParquetOptions),
     /// Parse SSIM file to CSV.
     Csv(SsimCsvOptions),
+    /// Parse SSIM file to an Arrow IPC (Feather) file.
+    Ipc(SsimIpcOptions),
+    /// Parse SSIM file to newline-delimited JSON.
+    Ndjson(SsimNdjsonOptions),
+    /// Expand the flight legs of an SSIM file into one row per operating date.
+    Expand(SsimExpandOptions),
 }
 
 #[derive(Args)]
 struct SsimParquetOptions {
-    /// Path of the SSIM File
+    /// Path of the SSIM File, or "-" to read from stdin
     #[arg(short, long, required = true)]
     ssim_path: String,
 
@@ -31,13 +49,8 @@ struct SsimParquetOptions {
     #[arg(short, long, default_value = "uncompressed")]
     compression: String,
 
-    /// Batch size for streaming.
-    #[arg(long, default_value = "10000")]
-    batch_size: usize,
-
-    /// Buffer size for streaming.
-    #[arg(long, default_value = "8192")]
-    buffer_size: usize,
+    #[command(flatten)]
+    reader: ReaderArgs,
 
     /// Condense segments into a list of structs in a single column per flight (reduces file size)
     #[arg(long, default_value = "false")]
@@ -46,11 +59,151 @@ struct SsimParquetOptions {
     /// Serialize segments into a JSON column per flight (reduces file size)
     #[arg(long, default_value = "false")]
     serialize_segments: bool,
+
+    /// Write dates, times, UTC offsets and station codes as native Parquet types instead of SSIM text
+    #[arg(long, default_value = "false")]
+    native_dtypes: bool,
+
+    /// Rows per Parquet row group. Each carrier is streamed to its file one row group at a time.
+    #[arg(long, default_value = "262144")]
+    row_group_size: usize,
+
+    /// Write the whole input to a single ssim.parquet file instead of one file per carrier.
+    #[arg(long, default_value = "false", conflicts_with = "partition_by")]
+    single_file: bool,
+
+    /// Comma-separated Hive partition keys, e.g. "airline_designator,season" or "period_of_operation_from:month". Writes key=value directories instead of one file per carrier.
+    #[arg(long, value_delimiter = ',')]
+    partition_by: Vec<PartitionKey>,
+}
+
+impl SsimParquetOptions {
+    fn layout(&self) -> ParquetLayout {
+        if self.single_file {
+            ParquetLayout::SingleFile
+        } else if !self.partition_by.is_empty() {
+            ParquetLayout::Hive(self.partition_by.clone())
+        } else {
+            ParquetLayout::PerCarrier
+        }
+    }
 }
 
 #[derive(Args)]
 struct SsimCsvOptions {
-    /// Path of the SSIM File
+    /// Path of the SSIM File, or "-" to read from stdin
+    #[arg(short, long, required = true)]
+    ssim_path: String,
+
+    /// Output path / Directory + filename, or the output directory with --per-carrier
+    #[arg(short, long, required = true)]
+    output_path: String,
+
+    #[command(flatten)]
+    reader: ReaderArgs,
+
+    /// Condense segments into a single JSON column per flight (reduces file size)
+    #[arg(long, default_value = "false")]
+    condense_segments: bool,
+
+    /// What to do if the output file exists: "fail", "overwrite" or "append". Appending checks that the existing header matches.
+    #[arg(long, default_value = "overwrite")]
+    write_mode: WriteMode,
+
+    /// Field delimiter: a single ASCII character, or "tab".
+    #[arg(long, default_value = ",", value_parser = parse_separator)]
+    delimiter: u8,
+
+    /// When to quote fields: "necessary", "always", "non_numeric" or "never".
+    #[arg(long, default_value = "necessary", value_parser = parse_quote_style)]
+    quote_style: QuoteStyle,
+
+    /// Text written for null values.
+    #[arg(long, default_value = "")]
+    null_value: String,
+
+    /// chrono format of dates with --native-dtypes, e.g. "%d/%m/%Y". Default: YYYY-MM-DD.
+    #[arg(long)]
+    date_format: Option<String>,
+
+    /// CSV Compression Options are "gzip", "zstd" and "uncompressed"
+    #[arg(short, long, default_value = "uncompressed", value_parser = ["uncompressed", "gzip", "zstd"])]
+    compression: String,
+
+    /// Write one file per carrier into the output directory, e.g. ssim_XX_.csv, instead of a single file.
+    #[arg(long, default_value = "false")]
+    per_carrier: bool,
+
+    /// Write dates as YYYY-MM-DD, times as durations and days of operation as a bitmask instead of SSIM text
+    #[arg(long, default_value = "false")]
+    native_dtypes: bool,
+}
+
+impl SsimCsvOptions {
+    fn csv_options(&self) -> CsvOptions {
+        CsvOptions {
+            write_mode: self.write_mode,
+            separator: self.delimiter,
+            quote_style: self.quote_style,
+            null_value: self.null_value.clone(),
+            date_format: self.date_format.clone(),
+            compression: self.compression.parse().unwrap_or(Compression::None),
+            per_carrier: self.per_carrier,
+        }
+    }
+}
+
+#[derive(Args)]
+struct SsimIpcOptions {
+    /// Path of the SSIM File, or "-" to read from stdin
+    #[arg(short, long, required = true)]
+    ssim_path: String,
+
+    /// Output path / Directory + filename
+    #[arg(short, long, required = true)]
+    output_path: String,
+
+    /// IPC Compression Options are "lz4", "zstd" and "uncompressed"
+    #[arg(short, long, default_value = "uncompressed")]
+    compression: String,
+
+    #[command(flatten)]
+    reader: ReaderArgs,
+
+    /// Condense segments into a list of structs in a single column per flight (reduces file size)
+    #[arg(long, default_value = "false")]
+    condense_segments: bool,
+
+    /// Write dates, times, UTC offsets and station codes as native Arrow types instead of SSIM text
+    #[arg(long, default_value = "false")]
+    native_dtypes: bool,
+}
+
+#[derive(Args)]
+struct SsimNdjsonOptions {
+    /// Path of the SSIM File, or "-" to read from stdin
+    #[arg(short, long, required = true)]
+    ssim_path: String,
+
+    /// Output path / Directory + filename
+    #[arg(short, long, required = true)]
+    output_path: String,
+
+    #[command(flatten)]
+    reader: ReaderArgs,
+
+    /// Condense segments into a nested array of objects per flight (reduces file size)
+    #[arg(long, default_value = "false")]
+    condense_segments: bool,
+
+    /// Write dates and times as ISO 8601 strings and UTC offsets and days of operation as numbers instead of SSIM text
+    #[arg(long, default_value = "false")]
+    native_dtypes: bool,
+}
+
+#[derive(Args)]
+struct SsimExpandOptions {
+    /// Path of the SSIM File, or "-" to read from stdin
     #[arg(short, long, required = true)]
     ssim_path: String,
 
@@ -58,6 +211,62 @@ struct SsimCsvOptions {
     #[arg(short, long, required = true)]
     output_path: String,
 
+    /// Output format: "parquet" or "csv"
+    #[arg(short, long, default_value = "parquet", value_parser = ["parquet", "csv"])]
+    format: String,
+
+    /// Compression: "snappy", "gzip", "lz4", "zstd" or "uncompressed" for Parquet; "gzip", "zstd" or "uncompressed" for CSV
+    #[arg(short, long, default_value = "uncompressed", value_parser = ["uncompressed", "snappy", "gzip", "lz4", "zstd"])]
+    compression: String,
+
+    /// Reader settings. --date-from and --date-to also bound the flight dates written; periods without an end date need --date-to.
+    #[command(flatten)]
+    reader: ReaderArgs,
+
+    /// Write dates, times and days of operation as native types instead of SSIM text
+    #[arg(long, default_value = "false")]
+    native_dtypes: bool,
+
+    /// Add local and UTC departure and arrival timestamps and the block time of every leg
+    #[arg(long, default_value = "false")]
+    leg_times: bool,
+
+    /// Add the seats per cabin and in total of every leg, decoded from its aircraft configuration. CSV output only gets the per-cabin totals.
+    #[arg(long, default_value = "false")]
+    seats: bool,
+
+    /// CSV file of aircraft version codes and their seats (columns airline_designator, aircraft_version, aircraft_configuration), for legs without seats in their configuration
+    #[arg(long, requires = "seats")]
+    aircraft_versions: Option<String>,
+}
+
+/// The CSV settings of `ssim expand --format csv`, or `None` for Parquet.
+///
+/// `--compression` takes Parquet codecs too, so CSV output checks it has a CSV one.
+fn expand_csv_options(options: &SsimExpandOptions) -> Result<Option<CsvOptions>, String> {
+    if options.format != "csv" {
+        return Ok(None);
+    }
+    let compression = match options.compression.as_str() {
+        "uncompressed" => Compression::None,
+        "gzip" => Compression::Gzip,
+        "zstd" => Compression::Zstd,
+        other => {
+            return Err(format!(
+                "compression '{}' is not supported for CSV output, expected 'gzip', 'zstd' or 'uncompressed'",
+                other
+            ));
+        }
+    };
+    Ok(Some(CsvOptions {
+        compression,
+        ..Default::default()
+    }))
+}
+
+/// Reader settings shared by every subcommand.
+#[derive(Args)]
+struct ReaderArgs {
     /// Batch size for streaming.
     #[arg(long, default_value = "10000")]
     batch_size: usize,
@@ -66,37 +275,260 @@ struct SsimCsvOptions {
     #[arg(long, default_value = "8192")]
     buffer_size: usize,
 
-    /// Condense segments into a single JSON column per flight (reduces file size)
-    #[arg(long, default_value = "false")]
-    condense_segments: bool,
+    /// How to handle malformed lines: "strict" stops at the first one, "lenient" skips and reports them.
+    #[arg(long, default_value = "strict")]
+    mode: ParseMode,
+
+    /// Maximum number of malformed lines listed in the diagnostics report.
+    #[arg(long, default_value = "100")]
+    max_issues: usize,
+
+    /// Comma-separated list of output columns, e.g. "flight_designator,departure_station". Unselected fields are never parsed.
+    #[arg(long)]
+    columns: Option<ColumnSelection>,
+
+    /// Comma-separated airline designators to read. Other carrier blocks are skipped without being parsed.
+    #[arg(long, value_delimiter = ',')]
+    airlines: Vec<String>,
+
+    /// Comma-separated departure stations of the flight legs to keep.
+    #[arg(long, value_delimiter = ',')]
+    departure_stations: Vec<String>,
+
+    /// Comma-separated arrival stations of the flight legs to keep.
+    #[arg(long, value_delimiter = ',')]
+    arrival_stations: Vec<String>,
+
+    /// Keep flight legs whose period of operation ends on or after this date (YYYY-MM-DD).
+    #[arg(long)]
+    date_from: Option<NaiveDate>,
+
+    /// Keep flight legs whose period of operation starts on or before this date (YYYY-MM-DD).
+    #[arg(long)]
+    date_to: Option<NaiveDate>,
+
+    /// Comma-separated service types of the flight legs to keep, e.g. "J,C".
+    #[arg(long, value_delimiter = ',')]
+    service_types: Vec<String>,
+
+    /// Comma-separated aircraft types of the flight legs to keep, e.g. "73H,320".
+    #[arg(long, value_delimiter = ',')]
+    aircraft_types: Vec<String>,
+
+    /// Memory-map the input and parse chunks of about this many bytes on all cores. Compressed inputs and stdin are read sequentially.
+    #[arg(long)]
+    chunk_size: Option<usize>,
+}
+
+impl ReaderArgs {
+    fn to_options(&self) -> SsimOptions {
+        SsimOptions {
+            batch_size: Some(self.batch_size),
+            buffer_size: Some(self.buffer_size),
+            parse_mode: self.mode,
+            max_reported_issues: Some(self.max_issues),
+            columns: self.columns.clone().unwrap_or_default(),
+            filter: self.filter(),
+            chunk_size: self.chunk_size,
+            ..Default::default()
+        }
+    }
+
+    fn filter(&self) -> SsimFilter {
+        let mut filter = SsimFilter::new()
+            .with_airlines(&self.airlines)
+            .with_departure_stations(&self.departure_stations)
+            .with_arrival_stations(&self.arrival_stations)
+            .with_service_types(&self.service_types)
+            .with_aircraft_types(&self.aircraft_types);
+        if let Some(date) = self.date_from {
+            filter = filter.with_date_from(date);
+        }
+        if let Some(date) = self.date_to {
+            filter = filter.with_date_to(date);
+        }
+        filter
+    }
 }
 
 fn main() {
     let cli = Cli::parse();
 
-    match &cli.command {
+    let result = match &cli.command {
         Commands::Parquet(options) => {
-            ssim_to_parquets(
-                &options.ssim_path,
-                Some(options.output_path.as_str()),
-                Some(options.compression.as_str()),
-                Some(options.batch_size),
-                Some(options.buffer_size),
-                Some(options.condense_segments),
-                Some(options.serialize_segments)
-            )
-            .expect("Failed to parse SSIM File to Parquet's.");
+            let output_path = Some(options.output_path.as_str());
+            let compression = Some(options.compression.as_str());
+            let reader_options = SsimOptions {
+                native_dtypes: options.native_dtypes,
+                row_group_size: Some(options.row_group_size),
+                parquet_layout: options.layout(),
+                ..options.reader.to_options()
+            };
+
+            if options.ssim_path == STDIN_PATH {
+                ssim_to_parquets_from_reader(
+                    std::io::stdin().lock(),
+                    output_path,
+                    compression,
+                    Some(options.condense_segments),
+                    Some(options.serialize_segments),
+                    &reader_options,
+                )
+            } else {
+                ssim_to_parquets_with_options(
+                    &options.ssim_path,
+                    output_path,
+                    compression,
+                    Some(options.condense_segments),
+                    Some(options.serialize_segments),
+                    &reader_options,
+                )
+            }
+            .map_err(|e| format!("Failed to parse SSIM File to Parquet's: {}", e))
         }
 
         Commands::Csv(options) => {
-            ssim_to_csv(
-                &options.ssim_path,
-                &options.output_path,
-                Some(options.batch_size),
-                Some(options.buffer_size),
-                Some(options.condense_segments),
-            )
-            .expect("Failed to parse SSIM File to CSV.");
+            let reader_options = SsimOptions {
+                native_dtypes: options.native_dtypes,
+                csv: options.csv_options(),
+                ..options.reader.to_options()
+            };
+
+            if options.ssim_path == STDIN_PATH {
+                ssim_to_csv_from_reader(
+                    std::io::stdin().lock(),
+                    &options.output_path,
+                    Some(options.condense_segments),
+                    &reader_options,
+                )
+            } else {
+                ssim_to_csv_with_options(
+                    &options.ssim_path,
+                    &options.output_path,
+                    Some(options.condense_segments),
+                    &reader_options,
+                )
+            }
+            .map_err(|e| format!("Failed to parse SSIM File to CSV: {}", e))
+        }
+
+        Commands::Ipc(options) => {
+            let compression = Some(options.compression.as_str());
+            let reader_options = SsimOptions {
+                native_dtypes: options.native_dtypes,
+                ..options.reader.to_options()
+            };
+
+            if options.ssim_path == STDIN_PATH {
+                ssim_to_ipc_from_reader(
+                    std::io::stdin().lock(),
+                    &options.output_path,
+                    compression,
+                    Some(options.condense_segments),
+                    &reader_options,
+                )
+            } else {
+                ssim_to_ipc_with_options(
+                    &options.ssim_path,
+                    &options.output_path,
+                    compression,
+                    Some(options.condense_segments),
+                    &reader_options,
+                )
+            }
+            .map_err(|e| format!("Failed to parse SSIM File to IPC: {}", e))
+        }
+
+        Commands::Ndjson(options) => {
+            let reader_options = SsimOptions {
+                native_dtypes: options.native_dtypes,
+                ..options.reader.to_options()
+            };
+
+            if options.ssim_path == STDIN_PATH {
+                ssim_to_ndjson_from_reader(
+                    std::io::stdin().lock(),
+                    &options.output_path,
+                    Some(options.condense_segments),
+                    &reader_options,
+                )
+            } else {
+                ssim_to_ndjson_with_options(
+                    &options.ssim_path,
+                    &options.output_path,
+                    Some(options.condense_segments),
+                    &reader_options,
+                )
+            }
+            .map_err(|e| format!("Failed to parse SSIM File to NDJSON: {}", e))
+        }
+
+        Commands::Expand(options) => match expand_csv_options(options) {
+            Err(message) => Err(message),
+            Ok(csv) => {
+                let columns = options.reader.columns.clone().unwrap_or_default();
+                let reader_options = SsimOptions {
+                    native_dtypes: options.native_dtypes,
+                    columns: if options.seats {
+                        columns.with_inputs(SEAT_INPUT_COLUMNS)
+                    } else {
+                        columns.clone()
+                    },
+                    ..options.reader.to_options()
+                };
+                let (date_from, date_to) = (options.reader.date_from, options.reader.date_to);
+
+                if options.ssim_path == STDIN_PATH {
+                    ssim_to_dated_flights_from_reader(
+                        std::io::stdin().lock(),
+                        date_from,
+                        date_to,
+                        &reader_options,
+                    )
+                } else {
+                    ssim_to_dated_flights_with_options(
+                        &options.ssim_path,
+                        date_from,
+                        date_to,
+                        &reader_options,
+                    )
+                }
+                .and_then(|(mut dated, diagnostics)| {
+                    if options.leg_times {
+                        dated = add_leg_times(&dated)?;
+                    }
+                    dated = columns.drop_inputs(dated, &["time_mode"]);
+                    if options.seats {
+                        let versions = match &options.aircraft_versions {
+                            Some(path) => Some(AircraftVersions::from_csv(path)?),
+                            None => None,
+                        };
+                        dated = add_seat_columns(&dated, versions.as_ref())?;
+                        dated = columns.drop_inputs(dated, SEAT_INPUT_COLUMNS);
+                    }
+                    if let Some(csv) = &csv {
+                        if options.seats {
+                            dated = dated.drop(CABIN_SEATS_COLUMN)?;
+                        }
+                        to_csv(&dated, &options.output_path, csv)?;
+                    } else {
+                        to_parquet(&mut dated, &options.output_path, &options.compression)?;
+                    }
+                    Ok(diagnostics)
+                })
+                .map_err(|e| format!("Failed to expand SSIM File to dated flights: {}", e))
+            }
+        },
+    };
+
+    match result {
+        Ok(diagnostics) if !diagnostics.is_clean() => {
+            eprintln!("warning: SSIM input issues: {}", diagnostics);
+        }
+        Ok(_) => {}
+        Err(message) => {
+            eprintln!("error: {}", message);
+            std::process::exit(1);
         }
     }
 }
@@ -1,5 +1,5 @@
-use std::io::Write;
-use std::process::Command;
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
 use tempfile::{NamedTempFile, TempDir};
 
 pub const CLI_APP: &str = "cli-rusty-ssim";
@@ -150,7 +150,7 @@ mod cli_tests {
         let output_path = temp_dir.path().join("output.csv");
 
         let output = Command::new(CLI_APP)
-            .args(&[
+            .args([
                 "csv",
                 "-s",
                 temp_file.path().to_str().unwrap(),
@@ -185,6 +185,403 @@ mod cli_tests {
         );
     }
 
+    #[test]
+    fn test_cli_csv_write_modes_and_formats() {
+        let temp_file = create_temp_ssim_file(SAMPLE_SSIM_DATA);
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let output_path = temp_dir.path().join("output.csv");
+        let run_csv = |output_path: &std::path::Path, extra: &[&str]| {
+            Command::new(CLI_APP)
+                .args([
+                    "csv",
+                    "-s",
+                    temp_file.path().to_str().unwrap(),
+                    "-o",
+                    output_path.to_str().unwrap(),
+                ])
+                .args(extra)
+                .output()
+                .expect("Failed to execute CLI command")
+        };
+
+        assert!(run_csv(&output_path, &[]).status.success());
+        let rows = fs::read_to_string(&output_path).unwrap().lines().count();
+
+        let output = run_csv(&output_path, &["--write-mode", "fail"]);
+        assert!(
+            !output.status.success(),
+            "An existing file should not be replaced"
+        );
+        assert!(run_csv(&output_path, &[]).status.success());
+        assert_eq!(
+            fs::read_to_string(&output_path).unwrap().lines().count(),
+            rows
+        );
+
+        let output = run_csv(&output_path, &["--write-mode", "append"]);
+        assert!(
+            output.status.success(),
+            "CLI append failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert_eq!(
+            fs::read_to_string(&output_path).unwrap().lines().count(),
+            2 * rows - 1
+        );
+        let output = run_csv(
+            &output_path,
+            &["--write-mode", "append", "--delimiter", ";"],
+        );
+        assert!(
+            !output.status.success(),
+            "A different header should not be appended to"
+        );
+
+        let carrier_dir = temp_dir.path().join("carriers");
+        let output = run_csv(
+            &carrier_dir,
+            &[
+                "--per-carrier",
+                "--delimiter",
+                "tab",
+                "-c",
+                "gzip",
+                "--quote-style",
+                "always",
+            ],
+        );
+        assert!(
+            output.status.success(),
+            "CLI per-carrier CSV failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let mut text = String::new();
+        flate2::read::GzDecoder::new(fs::File::open(carrier_dir.join("ssim_XX_.csv.gz")).unwrap())
+            .read_to_string(&mut text)
+            .unwrap();
+        assert_eq!(text.lines().count(), rows);
+        assert!(text.starts_with("\""));
+        assert!(text.contains("\"\t\""));
+    }
+
+    #[test]
+    fn test_cli_ipc_and_ndjson_commands() {
+        let temp_file = create_temp_ssim_file(SAMPLE_SSIM_DATA);
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+
+        for (command, file_name, extra) in [
+            ("ipc", "output.arrow", ["-c", "zstd"]),
+            (
+                "ndjson",
+                "output.ndjson",
+                ["--condense-segments", "--batch-size=5"],
+            ),
+        ] {
+            let output_path = temp_dir.path().join(file_name);
+            let output = Command::new(CLI_APP)
+                .args([
+                    command,
+                    "-s",
+                    temp_file.path().to_str().unwrap(),
+                    "-o",
+                    output_path.to_str().unwrap(),
+                ])
+                .args(extra)
+                .output()
+                .expect("Failed to execute CLI command");
+            assert!(
+                output.status.success(),
+                "CLI {} command failed: {}",
+                command,
+                String::from_utf8_lossy(&output.stderr)
+            );
+            let bytes = fs::read(&output_path).expect("Failed to read output file");
+            assert!(!bytes.is_empty(), "{} file should not be empty", command);
+            if command == "ipc" {
+                assert!(bytes.starts_with(b"ARROW1"), "Should be an Arrow IPC file");
+            } else {
+                let text = String::from_utf8(bytes).unwrap();
+                assert!(text.lines().all(|line| line.starts_with('{')));
+                assert!(text.contains("\"segment_data\":["));
+            }
+        }
+
+        let output_path = temp_dir.path().join("native.ndjson");
+        let output = Command::new(CLI_APP)
+            .args([
+                "ndjson",
+                "-s",
+                temp_file.path().to_str().unwrap(),
+                "-o",
+                output_path.to_str().unwrap(),
+                "--native-dtypes",
+            ])
+            .output()
+            .expect("Failed to execute CLI command");
+        assert!(
+            output.status.success(),
+            "CLI ndjson command failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let text = fs::read_to_string(&output_path).expect("Failed to read output file");
+        assert!(text.contains("\"period_of_operation_from\":\"2018-"));
+    }
+
+    #[test]
+    fn test_cli_expand_command() {
+        let temp_file = create_temp_ssim_file(SAMPLE_SSIM_DATA);
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let window = ["--date-from", "2018-04-01", "--date-to", "2018-04-07"];
+
+        let csv_path = temp_dir.path().join("dated.csv");
+        let output = Command::new(CLI_APP)
+            .args([
+                "expand",
+                "-s",
+                temp_file.path().to_str().unwrap(),
+                "-o",
+                csv_path.to_str().unwrap(),
+                "--format",
+                "csv",
+            ])
+            .args(window)
+            .output()
+            .expect("Failed to execute CLI command");
+        assert!(
+            output.status.success(),
+            "CLI expand command failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let text = fs::read_to_string(&csv_path).expect("Failed to read output file");
+        assert!(
+            text.lines()
+                .next()
+                .unwrap()
+                .contains("flight_date,departure_date,arrival_date")
+        );
+        // Nine legs operate in the first week of April.
+        assert_eq!(text.lines().count(), 10);
+        assert!(text.lines().skip(1).all(|line| line.contains(",2018-04-0")));
+
+        let parquet_path = temp_dir.path().join("dated.parquet");
+        let output = Command::new(CLI_APP)
+            .args([
+                "expand",
+                "-s",
+                temp_file.path().to_str().unwrap(),
+                "-o",
+                parquet_path.to_str().unwrap(),
+                "-c",
+                "zstd",
+                "--native-dtypes",
+            ])
+            .args(window)
+            .output()
+            .expect("Failed to execute CLI command");
+        assert!(
+            output.status.success(),
+            "CLI expand command failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert!(fs::read(&parquet_path).unwrap().starts_with(b"PAR1"));
+
+        // The sample has no open-ended periods, but one needs --date-to.
+        let open_ended = SAMPLE_SSIM_DATA.replacen("01APR1827APR18", "01APR1800XXX00", 1);
+        let open_file = create_temp_ssim_file(&open_ended);
+        let output = Command::new(CLI_APP)
+            .args([
+                "expand",
+                "-s",
+                open_file.path().to_str().unwrap(),
+                "-o",
+                csv_path.to_str().unwrap(),
+                "--format",
+                "csv",
+            ])
+            .output()
+            .expect("Failed to execute CLI command");
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("period_of_operation_to"));
+
+        // A Parquet codec or a typo is not silently written uncompressed as CSV.
+        for compression in ["snappy", "zstdd"] {
+            let output = Command::new(CLI_APP)
+                .args([
+                    "expand",
+                    "-s",
+                    temp_file.path().to_str().unwrap(),
+                    "-o",
+                    csv_path.to_str().unwrap(),
+                    "--format",
+                    "csv",
+                    "-c",
+                    compression,
+                ])
+                .output()
+                .expect("Failed to execute CLI command");
+            assert!(!output.status.success(), "-c {} should fail", compression);
+            assert!(String::from_utf8_lossy(&output.stderr).contains(compression));
+        }
+    }
+
+    #[test]
+    fn test_cli_expand_leg_times() {
+        let temp_file = create_temp_ssim_file(SAMPLE_SSIM_DATA);
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let csv_path = temp_dir.path().join("timed.csv");
+
+        let output = Command::new(CLI_APP)
+            .args([
+                "expand",
+                "-s",
+                temp_file.path().to_str().unwrap(),
+                "-o",
+                csv_path.to_str().unwrap(),
+                "--format",
+                "csv",
+                "--date-from",
+                "2018-04-03",
+                "--date-to",
+                "2018-04-03",
+                "--leg-times",
+            ])
+            .output()
+            .expect("Failed to execute CLI command");
+        assert!(
+            output.status.success(),
+            "CLI expand command failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let text = fs::read_to_string(&csv_path).expect("Failed to read output file");
+        assert!(text.lines().next().unwrap().ends_with(
+            "departure_local,departure_utc,arrival_local,arrival_utc,block_time_minutes,block_time_suspect"
+        ));
+        // The sample carrier is in UTC time mode: KEF 0510 +0000 to AMS 0800 +0200.
+        assert_eq!(text.lines().count(), 6);
+        assert!(text.lines().skip(1).all(|line| line.ends_with(
+            "2018-04-03T05:10:00.000,2018-04-03T05:10:00.000,2018-04-03T10:00:00.000,2018-04-03T08:00:00.000,170,false"
+        )));
+
+        // The time mode is read even if the column selection leaves it out.
+        let output = Command::new(CLI_APP)
+            .args([
+                "expand",
+                "-s",
+                temp_file.path().to_str().unwrap(),
+                "-o",
+                csv_path.to_str().unwrap(),
+                "--format",
+                "csv",
+                "--date-from",
+                "2018-04-03",
+                "--date-to",
+                "2018-04-03",
+                "--columns",
+                "flight_designator,period_of_operation_from,period_of_operation_to,days_of_operation,scheduled_time_of_aircraft_departure,time_variation_departure,scheduled_time_of_aircraft_arrival,time_variation_arrival",
+                "--leg-times",
+            ])
+            .output()
+            .expect("Failed to execute CLI command");
+        assert!(
+            output.status.success(),
+            "CLI expand command failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let text = fs::read_to_string(&csv_path).expect("Failed to read output file");
+        assert!(!text.lines().next().unwrap().contains("time_mode"));
+        assert_eq!(text.lines().count(), 6);
+        assert!(text.lines().skip(1).all(|line| line.ends_with(
+            "2018-04-03T05:10:00.000,2018-04-03T05:10:00.000,2018-04-03T10:00:00.000,2018-04-03T08:00:00.000,170,false"
+        )));
+    }
+
+    #[test]
+    fn test_cli_expand_seats() {
+        let temp_file = create_temp_ssim_file(SAMPLE_SSIM_DATA);
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let csv_path = temp_dir.path().join("seats.csv");
+        let versions_path = temp_dir.path().join("versions.csv");
+        fs::write(
+            &versions_path,
+            "aircraft_version,aircraft_configuration\n738H189,J12Y177\n",
+        )
+        .expect("Failed to write versions file");
+
+        let output = Command::new(CLI_APP)
+            .args([
+                "expand",
+                "-s",
+                temp_file.path().to_str().unwrap(),
+                "-o",
+                csv_path.to_str().unwrap(),
+                "--format",
+                "csv",
+                "--date-from",
+                "2018-04-03",
+                "--date-to",
+                "2018-04-03",
+                "--seats",
+                "--aircraft-versions",
+                versions_path.to_str().unwrap(),
+            ])
+            .output()
+            .expect("Failed to execute CLI command");
+        assert!(
+            output.status.success(),
+            "CLI expand command failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let text = fs::read_to_string(&csv_path).expect("Failed to read output file");
+        let header = text.lines().next().unwrap();
+        assert!(header.ends_with("seats_f,seats_c,seats_w,seats_y,total_seats"));
+        assert!(!header.contains("cabin_seats"));
+        // The sample legs list their seats (Y189), so the version table is not used.
+        assert!(
+            text.lines()
+                .skip(1)
+                .all(|line| line.ends_with("0,0,0,189,189"))
+        );
+
+        // The seats are decoded even if the aircraft configuration is not selected.
+        let output = Command::new(CLI_APP)
+            .args([
+                "expand",
+                "-s",
+                temp_file.path().to_str().unwrap(),
+                "-o",
+                csv_path.to_str().unwrap(),
+                "--format",
+                "csv",
+                "--date-from",
+                "2018-04-03",
+                "--date-to",
+                "2018-04-03",
+                "--columns",
+                "flight_designator,period_of_operation_from,period_of_operation_to,days_of_operation",
+                "--seats",
+            ])
+            .output()
+            .expect("Failed to execute CLI command");
+        assert!(
+            output.status.success(),
+            "CLI expand command failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let text = fs::read_to_string(&csv_path).expect("Failed to read output file");
+        let header = text.lines().next().unwrap();
+        assert!(header.ends_with("seats_f,seats_c,seats_w,seats_y,total_seats"));
+        assert!(!header.contains("aircraft_configuration"));
+        assert!(
+            text.lines()
+                .skip(1)
+                .all(|line| line.ends_with("0,0,0,189,189"))
+        );
+    }
+
     #[test]
     fn test_cli_small_ssim_csv_command() {
         let temp_file = create_temp_ssim_file(MINIMAL_SSIM_DATA);
@@ -192,7 +589,7 @@ mod cli_tests {
         let output_path = temp_dir.path().join("output.csv");
 
         let output = Command::new(CLI_APP)
-            .args(&[
+            .args([
                 "csv",
                 "-s",
                 temp_file.path().to_str().unwrap(),
@@ -234,7 +631,7 @@ mod cli_tests {
         let output_path = temp_dir.path();
 
         let output = Command::new(CLI_APP)
-            .args(&[
+            .args([
                 "parquet",
                 "--ssim-path",
                 temp_file.path().to_str().unwrap(),
@@ -268,7 +665,7 @@ mod cli_tests {
                     .path()
                     .extension()
                     .and_then(|ext| ext.to_str())
-                    .map_or(false, |ext| ext == "parquet")
+                    .is_some_and(|ext| ext == "parquet")
             })
             .collect();
 
@@ -300,7 +697,7 @@ mod cli_tests {
         let output_path = temp_dir.path();
 
         let output = Command::new(CLI_APP)
-            .args(&[
+            .args([
                 "parquet",
                 "--ssim-path",
                 temp_file.path().to_str().unwrap(),
@@ -334,7 +731,7 @@ mod cli_tests {
                     .path()
                     .extension()
                     .and_then(|ext| ext.to_str())
-                    .map_or(false, |ext| ext == "parquet")
+                    .is_some_and(|ext| ext == "parquet")
             })
             .collect();
 
@@ -359,13 +756,168 @@ mod cli_tests {
         );
     }
 
+    #[test]
+    fn test_cli_filter_skips_other_carriers() {
+        let temp_file = create_temp_ssim_file(SAMPLE_MULTI_SSIM_DATA);
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+
+        let output = Command::new(CLI_APP)
+            .args([
+                "parquet",
+                "--ssim-path",
+                temp_file.path().to_str().unwrap(),
+                "--output-path",
+                temp_dir.path().to_str().unwrap(),
+                "--airlines",
+                "YY",
+                "--departure-stations",
+                "AMS,KEF",
+                "--date-from",
+                "2018-04-01",
+            ])
+            .output()
+            .expect("Failed to execute CLI command");
+        assert!(
+            output.status.success(),
+            "CLI command failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let file_names: Vec<String> = fs::read_dir(temp_dir.path())
+            .expect("Failed to read output directory")
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .collect();
+        assert!(!file_names.is_empty(), "Should have written the YY carrier");
+        assert!(
+            file_names.iter().all(|name| name.starts_with("ssim_YY")),
+            "Other carriers should be skipped: {:?}",
+            file_names
+        );
+
+        let output = Command::new(CLI_APP)
+            .args([
+                "parquet",
+                "--ssim-path",
+                temp_file.path().to_str().unwrap(),
+                "--date-from",
+                "01APR18",
+            ])
+            .output()
+            .expect("Failed to execute CLI command");
+        assert!(
+            !output.status.success(),
+            "A date that is not YYYY-MM-DD should be rejected"
+        );
+    }
+
+    #[test]
+    fn test_cli_parquet_layouts() {
+        let temp_file = create_temp_ssim_file(SAMPLE_MULTI_SSIM_DATA);
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+
+        let output = Command::new(CLI_APP)
+            .args([
+                "parquet",
+                "--ssim-path",
+                temp_file.path().to_str().unwrap(),
+                "--output-path",
+                temp_dir.path().to_str().unwrap(),
+                "--partition-by",
+                "airline_designator,period_of_operation_from:month",
+            ])
+            .output()
+            .expect("Failed to execute CLI command");
+        assert!(
+            output.status.success(),
+            "CLI command failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let partition = temp_dir
+            .path()
+            .join("airline_designator=XX")
+            .join("period_of_operation_from_month=2018-03")
+            .join("ssim.parquet");
+        assert!(partition.exists(), "Missing partition {:?}", partition);
+
+        let output = Command::new(CLI_APP)
+            .args([
+                "parquet",
+                "--ssim-path",
+                temp_file.path().to_str().unwrap(),
+                "--output-path",
+                temp_dir.path().to_str().unwrap(),
+                "--single-file",
+            ])
+            .output()
+            .expect("Failed to execute CLI command");
+        assert!(
+            output.status.success(),
+            "CLI command failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert!(temp_dir.path().join("ssim.parquet").exists());
+
+        let output = Command::new(CLI_APP)
+            .args([
+                "parquet",
+                "--ssim-path",
+                temp_file.path().to_str().unwrap(),
+                "--partition-by",
+                "departure_station:month",
+            ])
+            .output()
+            .expect("Failed to execute CLI command");
+        assert!(
+            !output.status.success(),
+            "Only date columns can be partitioned by month"
+        );
+    }
+
+    #[test]
+    fn test_cli_chunk_size_matches_sequential() {
+        let temp_file = create_temp_ssim_file(SAMPLE_MULTI_SSIM_DATA);
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+
+        let mut outputs = Vec::new();
+        for (name, extra_args) in [
+            ("sequential.csv", vec![]),
+            ("chunked.csv", vec!["--chunk-size", "512"]),
+        ] {
+            let output_path = temp_dir.path().join(name);
+            let output = Command::new(CLI_APP)
+                .args([
+                    "csv",
+                    "--ssim-path",
+                    temp_file.path().to_str().unwrap(),
+                    "--output-path",
+                    output_path.to_str().unwrap(),
+                ])
+                .args(extra_args)
+                .output()
+                .expect("Failed to execute CLI command");
+            assert!(
+                output.status.success(),
+                "CLI command failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+            outputs.push(fs::read_to_string(&output_path).expect("Failed to read output file"));
+        }
+
+        assert!(!outputs[0].is_empty());
+        assert_eq!(
+            outputs[0], outputs[1],
+            "Chunked output should match the sequential output"
+        );
+    }
+
     #[test]
     fn test_cli_csv_invalid_arguments() {
         let temp_file = create_temp_ssim_file(SAMPLE_SSIM_DATA);
 
         // Test missing required arguments
         let output = Command::new(CLI_APP)
-            .args(&["csv", "--ssim-path", temp_file.path().to_str().unwrap()])
+            .args(["csv", "--ssim-path", temp_file.path().to_str().unwrap()])
             .output()
             .expect("Failed to execute CLI command");
 
@@ -387,7 +939,7 @@ mod cli_tests {
     fn test_cli_parquet_invalid_arguments() {
         // Test missing required arguments
         let output = Command::new(CLI_APP)
-            .args(&["parquet"])
+            .args(["parquet"])
             .output()
             .expect("Failed to execute CLI command");
 
@@ -408,7 +960,7 @@ mod cli_tests {
     #[test]
     fn test_cli_help_command() {
         let output = Command::new(CLI_APP)
-            .args(&["--help"])
+            .args(["--help"])
             .output()
             .expect("Failed to execute CLI command");
 
@@ -442,7 +994,7 @@ mod cli_tests {
             let temp_dir = TempDir::new().expect("Failed to create temp directory");
 
             let output = Command::new(CLI_APP)
-                .args(&[
+                .args([
                     "parquet",
                     "--ssim-path",
                     temp_file.path().to_str().unwrap(),
@@ -480,7 +1032,7 @@ mod cli_tests {
                         .path()
                         .extension()
                         .and_then(|ext| ext.to_str())
-                        .map_or(false, |ext| ext == expected_ext)
+                        .is_some_and(|ext| ext == expected_ext)
                 })
                 .count();
 
@@ -501,8 +1053,8 @@ mod cli_tests {
         let temp_dir = TempDir::new().expect("Failed to create temp directory");
         let output_file = temp_dir.path().join("output.csv");
 
-        let output = Command::new(&CLI_APP)
-            .args(&[
+        let output = Command::new(CLI_APP)
+            .args([
                 "csv",
                 "--ssim-path",
                 "nonexistent_file.ssim",
@@ -527,4 +1079,130 @@ mod cli_tests {
 
         println!("CLI nonexistent file test passed");
     }
+
+    #[test]
+    fn test_cli_lenient_mode_reports_malformed_lines() {
+        let malformed = SAMPLE_SSIM_DATA.replacen("\n3 XX", "\n9 XX", 1);
+        let temp_file = create_temp_ssim_file(&malformed);
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let output_file = temp_dir.path().join("output.csv");
+
+        let strict = Command::new(CLI_APP)
+            .args([
+                "csv",
+                "--ssim-path",
+                temp_file.path().to_str().unwrap(),
+                "--output-path",
+                output_file.to_str().unwrap(),
+            ])
+            .output()
+            .expect("Failed to execute CLI command");
+        assert!(
+            !strict.status.success(),
+            "Strict mode should fail on an invalid record type"
+        );
+
+        let lenient = Command::new(CLI_APP)
+            .args([
+                "csv",
+                "--ssim-path",
+                temp_file.path().to_str().unwrap(),
+                "--output-path",
+                output_file.to_str().unwrap(),
+                "--mode",
+                "lenient",
+            ])
+            .output()
+            .expect("Failed to execute CLI command");
+        assert!(
+            lenient.status.success(),
+            "Lenient mode should skip malformed lines. Stderr: {}",
+            String::from_utf8_lossy(&lenient.stderr)
+        );
+
+        let stderr = String::from_utf8_lossy(&lenient.stderr);
+        assert!(
+            stderr.contains("invalid_record_type: 1"),
+            "Diagnostics should be reported on stderr. Stderr: {}",
+            stderr
+        );
+        assert!(output_file.exists(), "Output CSV file should be created");
+    }
+
+    #[test]
+    fn test_cli_csv_from_stdin() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let output_file = temp_dir.path().join("output.csv");
+
+        let mut child = Command::new(CLI_APP)
+            .args([
+                "csv",
+                "--ssim-path",
+                "-",
+                "--output-path",
+                output_file.to_str().unwrap(),
+            ])
+            .stdin(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Failed to execute CLI command");
+
+        child
+            .stdin
+            .take()
+            .expect("Failed to open stdin")
+            .write_all(SAMPLE_SSIM_DATA.as_bytes())
+            .expect("Failed to write to stdin");
+        let output = child.wait_with_output().expect("Failed to wait for CLI");
+
+        assert!(
+            output.status.success(),
+            "CLI should read SSIM from stdin. Stderr: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let content = fs::read_to_string(&output_file).expect("Failed to read output file");
+        assert!(
+            content.lines().count() > 1,
+            "CSV should contain a header and data rows"
+        );
+    }
+
+    #[test]
+    fn test_cli_gzip_input() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let input_file = temp_dir.path().join("schedule.ssim.gz");
+        let output_dir = temp_dir.path().join("parquet_output");
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder
+            .write_all(SAMPLE_SSIM_DATA.as_bytes())
+            .expect("Failed to compress SSIM data");
+        fs::write(
+            &input_file,
+            encoder.finish().expect("Failed to finish gzip stream"),
+        )
+        .expect("Failed to write gzip file");
+
+        let output = Command::new(CLI_APP)
+            .args([
+                "parquet",
+                "--ssim-path",
+                input_file.to_str().unwrap(),
+                "--output-path",
+                output_dir.to_str().unwrap(),
+            ])
+            .output()
+            .expect("Failed to execute CLI command");
+
+        assert!(
+            output.status.success(),
+            "CLI should read gzip input. Stderr: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert!(
+            output_dir.join("ssim_XX_.parquet").exists(),
+            "Parquet file for carrier XX should be created"
+        );
+    }
 }
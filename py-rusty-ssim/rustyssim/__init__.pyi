@@ -1,19 +1,111 @@
+import datetime
 import polars as pl
-from typing import Optional
+import os
+from typing import BinaryIO, Literal, Optional, TextIO, TypedDict, Union
+
+SsimSource = Union[str, os.PathLike[str], bytes, bytearray, BinaryIO, TextIO]
+
+ParseMode = Literal["strict", "lenient"]
+
+class SsimIssue(TypedDict):
+    """A malformed line skipped in lenient mode."""
+    kind: str
+    file_path: Optional[str]
+    line_number: int
+    byte_offset: int
+    record_serial_number: Optional[str]
+    reason: str
+
+class SsimDiagnostics(TypedDict):
+    """Feed-quality report returned when ``return_diagnostics=True``."""
+    lines_read: int
+    counts: dict[str, int]
+    issues: list[SsimIssue]
+
+class SsimFilter(TypedDict, total=False):
+    """Carrier blocks and flight legs to read, matched before parsing. Every key is optional."""
+    airlines: list[str]
+    departure_stations: list[str]
+    arrival_stations: list[str]
+    date_from: Union[datetime.date, str]
+    date_to: Union[datetime.date, str]
+    service_types: list[str]
+    aircraft_types: list[str]
+
+class SsimParquetMetadata(TypedDict):
+    """Provenance stored in the key-value metadata of the Parquet files written by rustyssim."""
+    rusty_ssim_version: str
+    schema_version: int
+    source_file: Optional[str]
+    header: dict[str, str]
+    carrier: dict[str, str]
+
+class SsimParseError(ValueError):
+    """Base class for malformed SSIM input. Carries the location of the offending line."""
+    file_path: Optional[str]
+    line_number: int
+    byte_offset: int
+    record_serial_number: Optional[str]
+
+class TruncatedRecordError(SsimParseError):
+    """A record is shorter than 200 bytes."""
+
+class InvalidRecordTypeError(SsimParseError):
+    """A line starts with an unknown record type."""
+
+class InvalidUtf8Error(SsimParseError):
+    """A line is not valid UTF-8 or splits a character across fields."""
+
+class MissingCarrierError(SsimParseError):
+    """A flight or segment record appears before any carrier record."""
+
+class SsimIOError(OSError):
+    """The SSIM input could not be opened or read."""
 
 def split_ssim_to_dataframes(
-        file_path: str,
+        file_path: SsimSource,
         batch_size: int = 10000,
-        buffer_size: int = 8192
-) -> tuple[pl.DataFrame, pl.DataFrame, pl.DataFrame]:
+        buffer_size: int = 8192,
+        native_dtypes: bool = False,
+        columns: Optional[list[str]] = None,
+        filters: Optional[SsimFilter] = None,
+        chunk_size: Optional[int] = None,
+        mode: ParseMode = "strict",
+        max_issues: int = 100,
+        return_diagnostics: bool = False,
+        decode_deis: bool = False
+) -> Union[
+    tuple[pl.DataFrame, pl.DataFrame, pl.DataFrame],
+    tuple[tuple[pl.DataFrame, pl.DataFrame, pl.DataFrame], SsimDiagnostics],
+]:
     """
     Parse SSIM file into Polars DataFrames (types 2, 3, 4).
 
     Args:
-        file_path (str): Path to the SSIM file.
+        file_path (str | os.PathLike | bytes | file-like): Path to the SSIM file, the raw SSIM bytes,
+            or any object with a ``read()`` method (e.g. ``sys.stdin.buffer``, ``io.BytesIO``, an HTTP response).
         batch_size (int, optional): Batch size for streaming. Defaults to 10000.
         buffer_size (int, optional): Buffer size in bytes for file reading. Defaults to 8192 (8KB).
             For larger files, consider using 131072 (128KB) for better performance.
+        native_dtypes (bool, optional): If True, return periods and dates as ``Date``, scheduled times as ``Duration``
+            since local midnight, UTC time variations as ``Int16`` minutes, ``leg_sequence_number`` as ``UInt8``,
+            ``days_of_operation`` as a ``UInt8`` bitmask (bit 0 = Monday) and stations, aircraft and service types
+            as ``Categorical``. Defaults to False, which keeps the raw SSIM text.
+        columns (list[str], optional): Output columns, in order. Flight leg and segment fields that are not
+            selected are never parsed. Defaults to None (every column).
+        filters (SsimFilter, optional): Only read the carrier blocks of ``airlines`` and the flight legs matching
+            ``departure_stations``, ``arrival_stations``, ``service_types``, ``aircraft_types`` and whose period
+            of operation overlaps ``date_from`` to ``date_to`` (``datetime.date`` or ``"YYYY-MM-DD"``). Other lines
+            are skipped before parsing; segments are kept with their flight legs. Defaults to None (every record).
+        chunk_size (int, optional): Memory-map an uncompressed file and parse chunks of about this many bytes
+            on all cores. The output is the same as the sequential reader's. Compressed files and streams are
+            read sequentially. Defaults to None (sequential).
+        mode (str, optional): "strict" raises on the first malformed line, "lenient" skips malformed lines
+            and reports them in the diagnostics. Defaults to "strict".
+        max_issues (int, optional): Maximum number of malformed lines listed in the diagnostics. Defaults to 100.
+        return_diagnostics (bool, optional): If True, return a ``(result, diagnostics)`` tuple. Defaults to False.
+        decode_deis (bool, optional): If True, add the ``dei_name``, ``dei_level`` and ``dei_values`` of each
+            segment's Data Element Identifier to record_type4, see ``add_dei_columns``. Defaults to False.
 
     Returns:
         Tuple of DataFrames (record_type2, record_type3, record_type4).
@@ -26,17 +118,25 @@ def split_ssim_to_dataframes(
     ...
 
 def parse_ssim_to_dataframe(
-        file_path: str,
+        file_path: SsimSource,
         batch_size: int = 10000,
         buffer_size: int = 8192,
         condense_segments: bool = False,
-        serialize_segments: bool = False
-) -> pl.DataFrame:
+        serialize_segments: bool = False,
+        native_dtypes: bool = False,
+        columns: Optional[list[str]] = None,
+        filters: Optional[SsimFilter] = None,
+        chunk_size: Optional[int] = None,
+        mode: ParseMode = "strict",
+        max_issues: int = 100,
+        return_diagnostics: bool = False
+) -> Union[pl.DataFrame, tuple[pl.DataFrame, SsimDiagnostics]]:
     """
     Parse SSIM file into a single Polars DataFrame containing Record Type 3 and 4.
 
     Args:
-        file_path (str): Path to the SSIM file.
+        file_path (str | os.PathLike | bytes | file-like): Path to the SSIM file, the raw SSIM bytes,
+            or any object with a ``read()`` method (e.g. ``sys.stdin.buffer``, ``io.BytesIO``, an HTTP response).
         batch_size (int, optional): Batch size for streaming. Defaults to 10000.
         buffer_size (int, optional): Buffer size in bytes for file reading. Defaults to 8192 (8KB).
             For larger files, consider using 131072 (128KB) for better performance.
@@ -44,6 +144,23 @@ def parse_ssim_to_dataframe(
             Defaults to False. This can reduce number of rows and improve performance as well file size.
         serialize_segments (bool, optional): If True, serialize segments into a JSON column per flight.
                 Defaults to False. This can reduce file size.
+        native_dtypes (bool, optional): If True, return periods and dates as ``Date``, scheduled times as ``Duration``
+            since local midnight, UTC time variations as ``Int16`` minutes, ``leg_sequence_number`` as ``UInt8``,
+            ``days_of_operation`` as a ``UInt8`` bitmask (bit 0 = Monday) and stations, aircraft and service types
+            as ``Categorical``. Defaults to False, which keeps the raw SSIM text.
+        columns (list[str], optional): Output columns, in order. Flight leg and segment fields that are not
+            selected are never parsed. Defaults to None (every column).
+        filters (SsimFilter, optional): Only read the carrier blocks of ``airlines`` and the flight legs matching
+            ``departure_stations``, ``arrival_stations``, ``service_types``, ``aircraft_types`` and whose period
+            of operation overlaps ``date_from`` to ``date_to`` (``datetime.date`` or ``"YYYY-MM-DD"``). Other lines
+            are skipped before parsing; segments are kept with their flight legs. Defaults to None (every record).
+        chunk_size (int, optional): Memory-map an uncompressed file and parse chunks of about this many bytes
+            on all cores. The output is the same as the sequential reader's. Compressed files and streams are
+            read sequentially. Defaults to None (sequential).
+        mode (str, optional): "strict" raises on the first malformed line, "lenient" skips malformed lines
+            and reports them in the diagnostics. Defaults to "strict".
+        max_issues (int, optional): Maximum number of malformed lines listed in the diagnostics. Defaults to 100.
+        return_diagnostics (bool, optional): If True, return a ``(result, diagnostics)`` tuple. Defaults to False.
 
     Returns:
         polars.DataFrame: DataFrame containing Record Type 3 and 4 info.
@@ -54,27 +171,74 @@ def parse_ssim_to_dataframe(
         >>> ssim_dataframe = parse_ssim_to_dataframe("path/to/ssim_file.ssim", buffer_size=128 * 1024)
         >>> ssim_dataframe = parse_ssim_to_dataframe("path/to/ssim_file.ssim", condense_segments=True)
         >>> ssim_dataframe = parse_ssim_to_dataframe("path/to/ssim_file.ssim", condense_segments=True, serialize_segments=True)
+        >>> ssim_dataframe = parse_ssim_to_dataframe("path/to/ssim_file.ssim", native_dtypes=True)
+        >>> ssim_dataframe = parse_ssim_to_dataframe("path/to/ssim_file.ssim", columns=["flight_designator", "departure_station"])
+        >>> ssim_dataframe = parse_ssim_to_dataframe("path/to/ssim_file.ssim", filters={"airlines": ["XX"], "departure_stations": ["KEF"]})
+        >>> ssim_dataframe = parse_ssim_to_dataframe(response.content)
+        >>> ssim_dataframe = parse_ssim_to_dataframe(io.BytesIO(raw_bytes))
     """
     ...
 
 def parse_ssim_to_csv(
-        file_path: str,
+        file_path: SsimSource,
         output_path: str,
         batch_size: int = 10000,
         buffer_size: int = 8192,
-        condense_segments: bool = False
-) -> None:
+        condense_segments: bool = False,
+        columns: Optional[list[str]] = None,
+        filters: Optional[SsimFilter] = None,
+        chunk_size: Optional[int] = None,
+        mode: ParseMode = "strict",
+        max_issues: int = 100,
+        return_diagnostics: bool = False,
+        write_mode: Literal["fail", "overwrite", "append"] = "overwrite",
+        delimiter: str = ",",
+        quote_style: Literal["necessary", "always", "non_numeric", "never"] = "necessary",
+        null_value: str = "",
+        date_format: Optional[str] = None,
+        compression: Literal["uncompressed", "gzip", "zstd"] = "uncompressed",
+        per_carrier: bool = False,
+        native_dtypes: bool = False
+) -> Optional[tuple[None, SsimDiagnostics]]:
     """
     Parse SSIM file and write directly to CSV file.
 
     Args:
-        file_path (str): Path to the SSIM file.
-        output_path (str): Output Path for the CSV file.
+        file_path (str | os.PathLike | bytes | file-like): Path to the SSIM file, the raw SSIM bytes,
+            or any object with a ``read()`` method (e.g. ``sys.stdin.buffer``, ``io.BytesIO``, an HTTP response).
+        output_path (str): Output Path for the CSV file, or the output directory with ``per_carrier``.
         batch_size (int, optional): Batch size for streaming. Defaults to 10000.
         buffer_size (int, optional): Buffer size in bytes for file reading. Defaults to 8192 (8KB).
             For larger files, consider using 131072 (128KB) for better performance.
         condense_segments (bool, optional): If True, condense multiple segments for the same flight into a single column/row in list of json format.
             Defaults to False. This can reduce number of rows and improve performance as well file size.
+        columns (list[str], optional): Output columns, in order. Flight leg and segment fields that are not
+            selected are never parsed. Defaults to None (every column).
+        filters (SsimFilter, optional): Only read the carrier blocks of ``airlines`` and the flight legs matching
+            ``departure_stations``, ``arrival_stations``, ``service_types``, ``aircraft_types`` and whose period
+            of operation overlaps ``date_from`` to ``date_to`` (``datetime.date`` or ``"YYYY-MM-DD"``). Other lines
+            are skipped before parsing; segments are kept with their flight legs. Defaults to None (every record).
+        chunk_size (int, optional): Memory-map an uncompressed file and parse chunks of about this many bytes
+            on all cores. The output is the same as the sequential reader's. Compressed files and streams are
+            read sequentially. Defaults to None (sequential).
+        mode (str, optional): "strict" raises on the first malformed line, "lenient" skips malformed lines
+            and reports them in the diagnostics. Defaults to "strict".
+        max_issues (int, optional): Maximum number of malformed lines listed in the diagnostics. Defaults to 100.
+        return_diagnostics (bool, optional): If True, return a ``(result, diagnostics)`` tuple. Defaults to False.
+        write_mode (str, optional): What to do if the output file exists: "fail" raises, "overwrite" replaces it
+            and "append" adds the rows after checking that its header matches the output columns.
+            Defaults to "overwrite".
+        delimiter (str, optional): Field delimiter, a single ASCII character or "tab". Defaults to ",".
+        quote_style (str, optional): When to quote fields: "necessary", "always", "non_numeric" or "never".
+            Defaults to "necessary".
+        null_value (str, optional): Text written for null values. Defaults to "".
+        date_format (str, optional): chrono format of dates with ``native_dtypes``, e.g. "%d/%m/%Y".
+            Defaults to None (YYYY-MM-DD).
+        compression (str, optional): "gzip", "zstd" or "uncompressed". Defaults to "uncompressed".
+        per_carrier (bool, optional): Write one file per carrier into ``output_path``, e.g. ``ssim_XX_.csv``,
+            instead of a single file. Defaults to False.
+        native_dtypes (bool, optional): Write periods as dates, scheduled times as ``HH:MM``, UTC variations in
+            minutes and days of operation as a bitmask instead of the raw SSIM text. Defaults to False.
 
     Returns:
         None: File is written to disk.
@@ -84,23 +248,139 @@ def parse_ssim_to_csv(
         >>> parse_ssim_to_csv("path/to/ssim_file.ssim", "output/path/output_file.csv", batch_size=5000)
         >>> parse_ssim_to_csv("path/to/ssim_file.ssim", "output/path/output_file.csv", buffer_size=128 * 1024)
         >>> parse_ssim_to_csv("path/to/ssim_file.ssim", "output/path/output_file.csv", condense_segments=True)
+        >>> parse_ssim_to_csv("path/to/ssim_file.ssim", "output/path/output_file.csv.gz", compression="gzip", delimiter=";")
+        >>> parse_ssim_to_csv("path/to/ssim_file.ssim", "output/path/carriers", per_carrier=True, write_mode="fail")
+    """
+    ...
+
+def parse_ssim_to_ipc(
+        file_path: SsimSource,
+        output_path: str,
+        compression: Optional[str] = "uncompressed",
+        batch_size: int = 10000,
+        buffer_size: int = 8192,
+        condense_segments: bool = False,
+        native_dtypes: bool = False,
+        columns: Optional[list[str]] = None,
+        filters: Optional[SsimFilter] = None,
+        chunk_size: Optional[int] = None,
+        mode: ParseMode = "strict",
+        max_issues: int = 100,
+        return_diagnostics: bool = False
+) -> Optional[tuple[None, SsimDiagnostics]]:
+    """
+    Parse SSIM file and stream it into an Arrow IPC (Feather v2) file, readable with ``pl.read_ipc``.
+
+    Args:
+        file_path (str | os.PathLike | bytes | file-like): Path to the SSIM file, the raw SSIM bytes,
+            or any object with a ``read()`` method (e.g. ``sys.stdin.buffer``, ``io.BytesIO``, an HTTP response).
+        output_path (str): Output Path for the IPC file. An existing file is replaced.
+        compression (str, optional): IPC Compression Options are "lz4", "zstd", or "uncompressed".
+            Defaults to "uncompressed".
+        batch_size (int, optional): Batch size for streaming. Defaults to 10000.
+        buffer_size (int, optional): Buffer size in bytes for file reading. Defaults to 8192 (8KB).
+        condense_segments (bool, optional): If True, nest the segments of each flight leg in a ``segment_data``
+            ``List<Struct>`` column. Defaults to False.
+        native_dtypes (bool, optional): If True, write dates, times, UTC offsets and codes as native Arrow types
+            (see ``parse_ssim_to_parquets``). Defaults to False, which writes the raw SSIM text.
+        columns (list[str], optional): Output columns, in order. Flight leg and segment fields that are not
+            selected are never parsed. Defaults to None (every column).
+        filters (SsimFilter, optional): Only read the carrier blocks of ``airlines`` and the flight legs matching
+            ``departure_stations``, ``arrival_stations``, ``service_types``, ``aircraft_types`` and whose period
+            of operation overlaps ``date_from`` to ``date_to`` (``datetime.date`` or ``"YYYY-MM-DD"``). Other lines
+            are skipped before parsing; segments are kept with their flight legs. Defaults to None (every record).
+        chunk_size (int, optional): Memory-map an uncompressed file and parse chunks of about this many bytes
+            on all cores. The output is the same as the sequential reader's. Compressed files and streams are
+            read sequentially. Defaults to None (sequential).
+        mode (str, optional): "strict" raises on the first malformed line, "lenient" skips malformed lines
+            and reports them in the diagnostics. Defaults to "strict".
+        max_issues (int, optional): Maximum number of malformed lines listed in the diagnostics. Defaults to 100.
+        return_diagnostics (bool, optional): If True, return a ``(result, diagnostics)`` tuple. Defaults to False.
+
+    Returns:
+        None: File is written to disk.
+
+    Example:
+        >>> parse_ssim_to_ipc("path/to/ssim_file.ssim", "output/path/schedule.arrow", compression="zstd")
+        >>> parse_ssim_to_ipc("path/to/ssim_file.ssim", "output/path/schedule.arrow", condense_segments=True)
+    """
+    ...
+
+def parse_ssim_to_ndjson(
+        file_path: SsimSource,
+        output_path: str,
+        batch_size: int = 10000,
+        buffer_size: int = 8192,
+        condense_segments: bool = False,
+        native_dtypes: bool = False,
+        columns: Optional[list[str]] = None,
+        filters: Optional[SsimFilter] = None,
+        chunk_size: Optional[int] = None,
+        mode: ParseMode = "strict",
+        max_issues: int = 100,
+        return_diagnostics: bool = False
+) -> Optional[tuple[None, SsimDiagnostics]]:
+    """
+    Parse SSIM file and write it to a newline-delimited JSON file, one object per row.
+
+    Args:
+        file_path (str | os.PathLike | bytes | file-like): Path to the SSIM file, the raw SSIM bytes,
+            or any object with a ``read()`` method (e.g. ``sys.stdin.buffer``, ``io.BytesIO``, an HTTP response).
+        output_path (str): Output Path for the NDJSON file. An existing file is replaced.
+        batch_size (int, optional): Batch size for streaming. Defaults to 10000.
+        buffer_size (int, optional): Buffer size in bytes for file reading. Defaults to 8192 (8KB).
+        condense_segments (bool, optional): If True, nest the segments of each flight leg in a ``segment_data``
+            array of objects. Defaults to False.
+        native_dtypes (bool, optional): If True, write dates and scheduled times as ISO 8601 strings and UTC
+            offsets and days of operation as numbers instead of SSIM text. Defaults to False.
+        columns (list[str], optional): Output columns, in order. Flight leg and segment fields that are not
+            selected are never parsed. Defaults to None (every column).
+        filters (SsimFilter, optional): Only read the carrier blocks of ``airlines`` and the flight legs matching
+            ``departure_stations``, ``arrival_stations``, ``service_types``, ``aircraft_types`` and whose period
+            of operation overlaps ``date_from`` to ``date_to`` (``datetime.date`` or ``"YYYY-MM-DD"``). Other lines
+            are skipped before parsing; segments are kept with their flight legs. Defaults to None (every record).
+        chunk_size (int, optional): Memory-map an uncompressed file and parse chunks of about this many bytes
+            on all cores. The output is the same as the sequential reader's. Compressed files and streams are
+            read sequentially. Defaults to None (sequential).
+        mode (str, optional): "strict" raises on the first malformed line, "lenient" skips malformed lines
+            and reports them in the diagnostics. Defaults to "strict".
+        max_issues (int, optional): Maximum number of malformed lines listed in the diagnostics. Defaults to 100.
+        return_diagnostics (bool, optional): If True, return a ``(result, diagnostics)`` tuple. Defaults to False.
+
+    Returns:
+        None: File is written to disk.
+
+    Example:
+        >>> parse_ssim_to_ndjson("path/to/ssim_file.ssim", "output/path/schedule.ndjson")
+        >>> parse_ssim_to_ndjson("path/to/ssim_file.ssim", "output/path/schedule.ndjson", condense_segments=True)
     """
     ...
 
 def parse_ssim_to_parquets(
-        file_path: str,
+        file_path: SsimSource,
         output_path: Optional[str] = ".",
         compression: Optional[str] = "uncompressed",
         batch_size: int = 10000,
         buffer_size: int = 8192,
         condense_segments: bool = False,
-        serialize_segments: bool = False
-) -> None:
+        serialize_segments: bool = False,
+        native_dtypes: bool = False,
+        columns: Optional[list[str]] = None,
+        filters: Optional[SsimFilter] = None,
+        chunk_size: Optional[int] = None,
+        row_group_size: Optional[int] = None,
+        single_file: bool = False,
+        partition_by: Optional[list[str]] = None,
+        mode: ParseMode = "strict",
+        max_issues: int = 100,
+        return_diagnostics: bool = False
+) -> Optional[tuple[None, SsimDiagnostics]]:
     """
     Parse SSIM file and write contents to parquet files.
 
     Args:
-        file_path (str): Path to the SSIM file.
+        file_path (str | os.PathLike | bytes | file-like): Path to the SSIM file, the raw SSIM bytes,
+            or any object with a ``read()`` method (e.g. ``sys.stdin.buffer``, ``io.BytesIO``, an HTTP response).
         output_path (str): Output Path for the parquet files. Defaults to current directory.
         compression (str, optional): Parquet Compression Options are "snappy", "gzip", "lz4", "zstd", or "uncompressed".
                                    Defaults to "uncompressed".
@@ -111,6 +391,30 @@ def parse_ssim_to_parquets(
             Defaults to False. This can reduce number of rows and improve performance as well file size.
         serialize_segments (bool, optional): If True, serialize segments into a JSON column per flight.
             Defaults to False. This can reduce file size.
+        native_dtypes (bool, optional): If True, write periods and dates as ``Date``, scheduled times as ``Duration``
+            since local midnight, UTC time variations as ``Int16`` minutes, ``leg_sequence_number`` as ``UInt8``,
+            ``days_of_operation`` as a ``UInt8`` bitmask (bit 0 = Monday) and stations, aircraft and service types
+            as ``Categorical``. Defaults to False, which writes the raw SSIM text.
+        columns (list[str], optional): Output columns, in order. Flight leg and segment fields that are not
+            selected are never parsed. Defaults to None (every column).
+        filters (SsimFilter, optional): Only read the carrier blocks of ``airlines`` and the flight legs matching
+            ``departure_stations``, ``arrival_stations``, ``service_types``, ``aircraft_types`` and whose period
+            of operation overlaps ``date_from`` to ``date_to`` (``datetime.date`` or ``"YYYY-MM-DD"``). Other lines
+            are skipped before parsing; segments are kept with their flight legs. Defaults to None (every record).
+        chunk_size (int, optional): Memory-map an uncompressed file and parse chunks of about this many bytes
+            on all cores. The output is the same as the sequential reader's. Compressed files and streams are
+            read sequentially. Defaults to None (sequential).
+        row_group_size (int, optional): Rows per Parquet row group. Each carrier is streamed to its file one
+            row group at a time, so memory does not grow with the size of a carrier. Defaults to None (262,144).
+        single_file (bool, optional): If True, write the whole input to one ``ssim.parquet`` file instead of
+            one file per carrier. Defaults to False.
+        partition_by (list[str], optional): Columns to partition the output by, in Hive-style ``key=value``
+            directories. ``"column:month"`` partitions by the ``YYYY-MM`` month of a date column. Cannot be
+            combined with ``single_file``. Defaults to None (one file per carrier).
+        mode (str, optional): "strict" raises on the first malformed line, "lenient" skips malformed lines
+            and reports them in the diagnostics. Defaults to "strict".
+        max_issues (int, optional): Maximum number of malformed lines listed in the diagnostics. Defaults to 100.
+        return_diagnostics (bool, optional): If True, return a ``(result, diagnostics)`` tuple. Defaults to False.
 
     Returns:
         None: Files are written to disk.
@@ -123,4 +427,208 @@ def parse_ssim_to_parquets(
         >>> parse_ssim_to_parquets("path/to/ssim_file.ssim", buffer_size=128 * 1024, condense_segments=True)
         >>> parse_ssim_to_parquets("path/to/ssim_file.ssim", buffer_size=128 * 1024, condense_segments=True, serialize_segments=True)
     """
-    ...
\ No newline at end of file
+    ...
+
+def expand_to_dated_flights(
+        flights: pl.DataFrame,
+        date_from: Optional[Union[datetime.date, str]] = None,
+        date_to: Optional[Union[datetime.date, str]] = None
+) -> pl.DataFrame:
+    """
+    Expand flight legs into one row per date they operate on.
+
+    Args:
+        flights (pl.DataFrame): Flight legs, such as the record_type3 DataFrame of ``split_ssim_to_dataframes``,
+            as SSIM text or with ``native_dtypes``. Needs ``period_of_operation_from``, ``period_of_operation_to``
+            and ``days_of_operation``; ``frequency_rate`` ``"2"`` keeps every other week, counted from the
+            week of the period start.
+        date_from (datetime.date | str, optional): First flight date to keep. Defaults to None (the period start).
+        date_to (datetime.date | str, optional): Last flight date to keep. Needed if a period has no end
+            date (``00XXX00``). Defaults to None (the period end).
+
+    Returns:
+        pl.DataFrame: The rows of ``flights``, repeated per date, with ``flight_date`` (the date of the period
+            of operation) and the leg's ``departure_date`` and ``arrival_date`` after its ``date_variation``.
+
+    Raises:
+        RuntimeError: If a required column is missing, a value is invalid, or an open-ended period has no ``date_to``.
+
+    Example:
+        >>> _, flights, _ = split_ssim_to_dataframes("path/to/ssim_file.ssim")
+        >>> dated = expand_to_dated_flights(flights, date_from="2025-03-30", date_to="2025-04-05")
+    """
+    ...
+
+def add_leg_times(legs: pl.DataFrame) -> pl.DataFrame:
+    """
+    Add the local and UTC departure and arrival times and the block time of every flight leg.
+
+    Args:
+        legs (pl.DataFrame): Flight legs with the scheduled aircraft departure and arrival times and their
+            UTC time variations, as SSIM text or with ``native_dtypes``. ``date_variation`` shifts the departure
+            and arrival days. A ``time_mode`` of ``"U"`` (in ``parse_ssim_to_dataframe`` output, not in the
+            flights of ``split_ssim_to_dataframes``) marks the scheduled times as UTC; they are local otherwise.
+
+    Returns:
+        pl.DataFrame: ``legs`` with ``departure_local``, ``departure_utc``, ``arrival_local`` and ``arrival_utc``,
+            as ``Datetime`` if ``legs`` has the ``flight_date`` of ``expand_to_dated_flights`` and as ``Duration``
+            since midnight of the flight date otherwise, ``block_time_minutes`` (``Int64``) and
+            ``block_time_suspect``, True for negative block times and those over 20 hours.
+
+    Raises:
+        RuntimeError: If a required column is missing or a time is invalid.
+
+    Example:
+        >>> legs = parse_ssim_to_dataframe("path/to/ssim_file.ssim", condense_segments=True)
+        >>> timed = add_leg_times(expand_to_dated_flights(legs, date_from="2025-03-30", date_to="2025-04-05"))
+        >>> timed.filter(pl.col("block_time_suspect"))
+    """
+    ...
+
+def add_dei_columns(segments: pl.DataFrame) -> pl.DataFrame:
+    """
+    Add the registry name and level and the decoded data of each segment's Data Element Identifier (DEI).
+
+    Decoded DEIs: 10 (codeshare marketing flights), 50 (operating flight of a duplicate leg), 113/114
+    (departure and arrival terminals), 127 (operating airline disclosure), 170-173 (traffic restrictions),
+    501 (meal service per booking designator), 503 (in-flight services) and 505 (electronic ticketing).
+
+    Args:
+        segments (pl.DataFrame): Rows with ``data_element_identifier`` and ``data`` columns, such as the
+            record_type4 DataFrame of ``split_ssim_to_dataframes`` or ``parse_ssim_to_dataframe`` output.
+
+    Returns:
+        pl.DataFrame: ``segments`` with ``dei_name``, ``dei_level`` (``"flight"``, ``"leg"`` or ``"segment"``)
+            and ``dei_values``, a list of strings such as ``["AF 1234", "KQ 5678"]`` for DEI 10 or
+            ``["Y:B", "J:M"]`` for DEI 501. All three are null for unregistered DEIs, and ``dei_values``
+            is null for data that does not decode.
+
+    Raises:
+        RuntimeError: If a required column is missing.
+
+    Example:
+        >>> _, _, segments = split_ssim_to_dataframes("path/to/ssim_file.ssim")
+        >>> codeshares = add_dei_columns(segments).filter(pl.col("data_element_identifier") == "010")
+    """
+    ...
+
+def codeshare_table(segments: pl.DataFrame) -> pl.DataFrame:
+    """
+    Pair operating and marketing flights from the DEI 10 and DEI 50 records of a segments DataFrame.
+
+    DEI 10 (Code Share - Commercial Duplicate) records make their own flight the operating flight of each
+    marketing flight they list; DEI 50 (Duplicate Leg Cross Reference) records make their own flight a
+    marketing flight of the operating flight they name. A pair filed by both carriers is listed once.
+
+    Args:
+        segments (pl.DataFrame): The record_type4 DataFrame of ``split_ssim_to_dataframes``.
+
+    Returns:
+        pl.DataFrame: ``operating_airline``, ``operating_flight_number``, ``operating_suffix``,
+            ``marketing_airline``, ``marketing_flight_number``, ``marketing_suffix``, the ``board_point``
+            and ``off_point`` of the leg covered and the ``data_element_identifier`` (10 or 50).
+
+    Raises:
+        RuntimeError: If a required column is missing.
+
+    Example:
+        >>> _, _, segments = split_ssim_to_dataframes("path/to/ssim_file.ssim")
+        >>> partners = codeshare_table(segments).group_by("operating_airline").agg(pl.col("marketing_airline").unique())
+    """
+    ...
+
+def add_operating_flights(
+        flights: pl.DataFrame,
+        segments: pl.DataFrame,
+        emit_marketing_legs: bool = False
+) -> pl.DataFrame:
+    """
+    Mark the marketing duplicate legs of a flights DataFrame with their operating flight.
+
+    Args:
+        flights (pl.DataFrame): The record_type3 DataFrame of ``split_ssim_to_dataframes``.
+        segments (pl.DataFrame): The record_type4 DataFrame of the same file.
+        emit_marketing_legs (bool, optional): If True, append a copy of the operating leg for every marketing
+            flight its DEI 10 records list that ``flights`` does not have, with the marketing airline, flight
+            number and operational suffix. Defaults to False.
+
+    Returns:
+        pl.DataFrame: ``flights`` with ``operating_flight``, the operating flight of duplicate legs
+            (e.g. ``"KL 2562"``) and null for operating legs.
+
+    Raises:
+        RuntimeError: If a required column is missing.
+    """
+    ...
+
+def collapse_codeshares(flights: pl.DataFrame, segments: pl.DataFrame) -> pl.DataFrame:
+    """
+    Keep the operating legs of a flights DataFrame, dropping the marketing duplicates found by
+    ``add_operating_flights``, so each flown leg is counted once, e.g. when summing capacity.
+
+    Args:
+        flights (pl.DataFrame): The record_type3 DataFrame of ``split_ssim_to_dataframes``.
+        segments (pl.DataFrame): The record_type4 DataFrame of the same file.
+
+    Returns:
+        pl.DataFrame: The operating legs of ``flights``, with its columns.
+
+    Raises:
+        RuntimeError: If a required column is missing.
+
+    Example:
+        >>> _, flights, segments = split_ssim_to_dataframes("path/to/ssim_file.ssim")
+        >>> flown = collapse_codeshares(flights, segments)
+    """
+    ...
+
+def add_seat_columns(
+    legs: pl.DataFrame,
+    aircraft_versions: Optional[Union[str, os.PathLike[str]]] = None,
+) -> pl.DataFrame:
+    """
+    Decode the aircraft configuration of each flight leg, e.g. ``J30W24Y250``, into seats per cabin.
+
+    Legs whose configuration only names an aircraft version, e.g. ``VV738H189``, get the seats of
+    that version from the ``aircraft_versions`` table.
+
+    Args:
+        legs (pl.DataFrame): Flight legs with an ``aircraft_configuration`` column and, optionally,
+            ``airline_designator`` to pick airline-specific versions.
+        aircraft_versions (str | os.PathLike, optional): CSV file with a header row and the columns
+            ``aircraft_version`` and ``aircraft_configuration``, and optionally
+            ``airline_designator`` (blank for every airline).
+
+    Returns:
+        pl.DataFrame: ``legs`` with ``cabin_seats`` (``List[Struct{cabin, seats}]``), ``seats_f``,
+        ``seats_c``, ``seats_w``, ``seats_y`` and ``total_seats`` (``UInt32``), null where the seats
+        are unknown.
+
+    Raises:
+        RuntimeError: If ``aircraft_configuration`` is missing or the versions file cannot be read.
+
+    Example:
+        >>> _, flights, _ = split_ssim_to_dataframes("path/to/ssim_file.ssim")
+        >>> seats = add_seat_columns(flights, aircraft_versions="versions.csv")
+    """
+    ...
+
+def read_parquet_metadata(file_path: Union[str, os.PathLike[str]]) -> Optional[SsimParquetMetadata]:
+    """
+    Read the rustyssim metadata from the footer of a Parquet file, without reading its rows.
+
+    Args:
+        file_path (str | os.PathLike): Path to a Parquet file written by ``parse_ssim_to_parquets``.
+
+    Returns:
+        dict | None: The rusty-ssim version and schema version that wrote the file, the source file name,
+            and the header (``header``) and carrier (``carrier``) record fields by column name. Files holding
+            several carriers (``single_file`` and ``partition_by``) have no carrier fields. None if the file
+            was not written by rustyssim.
+
+    Example:
+        >>> meta = read_parquet_metadata("./output_path/ssim_XX_.parquet")
+        >>> meta["carrier"]["season"]
+        'S18'
+    """
+    ...
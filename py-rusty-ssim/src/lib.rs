@@ -1,8 +1,32 @@
-use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use chrono::NaiveDate;
+use pyo3::IntoPyObjectExt;
+use pyo3::create_exception;
+use pyo3::exceptions::{PyIOError, PyRuntimeError, PyTypeError, PyValueError};
 use pyo3::prelude::*;
+use pyo3::types::{PyByteArray, PyBytes, PyDict, PyList, PyString};
 use pyo3_polars::PyDataFrame;
+use std::io::{self, Cursor, Read};
+use std::path::PathBuf;
 
-pub use rusty_ssim_core::{ssim_to_csv, ssim_to_dataframe, ssim_to_dataframes, ssim_to_parquets};
+pub use rusty_ssim_core::{
+    AircraftVersions, ColumnSelection, Compression, CsvOptions, Diagnostics, ParquetLayout,
+    ParseMode, SsimError, SsimFilter, SsimOptions, WriteMode, add_dei_columns as add_deis,
+    add_leg_times as add_times, add_operating_flights as add_operating,
+    add_seat_columns as add_seats, codeshare_table as codeshares, collapse_codeshares as collapse,
+    expand_to_dated_flights as expand_dated_flights, parse_quote_style, parse_separator,
+    read_parquet_metadata as read_metadata, ssim_to_csv_from_reader, ssim_to_csv_with_options,
+    ssim_to_dataframe_from_reader, ssim_to_dataframe_with_options, ssim_to_dataframes_from_reader,
+    ssim_to_dataframes_with_options, ssim_to_ipc_from_reader, ssim_to_ipc_with_options,
+    ssim_to_ndjson_from_reader, ssim_to_ndjson_with_options, ssim_to_parquets_from_reader,
+    ssim_to_parquets_with_options,
+};
+
+create_exception!(rustyssim, SsimParseError, PyValueError, "Base class for malformed SSIM input.");
+create_exception!(rustyssim, TruncatedRecordError, SsimParseError, "A record is shorter than 200 bytes.");
+create_exception!(rustyssim, InvalidRecordTypeError, SsimParseError, "A line starts with an unknown record type.");
+create_exception!(rustyssim, InvalidUtf8Error, SsimParseError, "A line is not valid UTF-8 or splits a character across fields.");
+create_exception!(rustyssim, MissingCarrierError, SsimParseError, "A flight or segment record appears before any carrier record.");
+create_exception!(rustyssim, SsimIOError, PyIOError, "The SSIM input could not be opened or read.");
 
 #[inline(always)]
 fn runtime_error(msg: String) -> PyErr {
@@ -14,88 +38,635 @@ fn value_error(msg: String) -> PyErr {
     PyValueError::new_err(msg)
 }
 
+/// Build reader options from the keyword arguments shared by every parse function.
+fn reader_options(
+    batch_size: Option<usize>,
+    buffer_size: Option<usize>,
+    mode: &str,
+    max_issues: Option<usize>,
+    columns: Option<Vec<String>>,
+    filters: Option<&Bound<'_, PyDict>>,
+) -> PyResult<SsimOptions> {
+    let columns = match columns {
+        Some(columns) => ColumnSelection::new(columns).map_err(value_error)?,
+        None => ColumnSelection::all(),
+    };
+    Ok(SsimOptions {
+        batch_size,
+        buffer_size,
+        parse_mode: mode.parse::<ParseMode>().map_err(value_error)?,
+        max_reported_issues: max_issues,
+        columns,
+        filter: ssim_filter(filters)?,
+        ..Default::default()
+    })
+}
+
+/// The Parquet layout of the `single_file` and `partition_by` arguments.
+fn parquet_layout(single_file: bool, partition_by: Option<Vec<String>>) -> PyResult<ParquetLayout> {
+    match (single_file, partition_by) {
+        (true, Some(_)) => Err(value_error(
+            "single_file and partition_by cannot be combined".to_string(),
+        )),
+        (true, None) => Ok(ParquetLayout::SingleFile),
+        (false, Some(keys)) => ParquetLayout::hive(keys).map_err(value_error),
+        (false, None) => Ok(ParquetLayout::PerCarrier),
+    }
+}
+
+/// The CSV options of the `parse_ssim_to_csv` arguments.
+fn csv_options(
+    write_mode: &str,
+    delimiter: &str,
+    quote_style: &str,
+    null_value: &str,
+    date_format: Option<String>,
+    compression: &str,
+    per_carrier: bool,
+) -> PyResult<CsvOptions> {
+    let compression = match compression.parse().map_err(value_error)? {
+        compression @ (Compression::None | Compression::Gzip | Compression::Zstd) => compression,
+        _ => {
+            return Err(value_error(format!(
+                "invalid CSV compression '{}', expected 'uncompressed', 'gzip' or 'zstd'",
+                compression
+            )));
+        }
+    };
+    Ok(CsvOptions {
+        write_mode: write_mode.parse::<WriteMode>().map_err(value_error)?,
+        separator: parse_separator(delimiter).map_err(value_error)?,
+        quote_style: parse_quote_style(quote_style).map_err(value_error)?,
+        null_value: null_value.to_string(),
+        date_format,
+        compression,
+        per_carrier,
+    })
+}
+
+/// Build an `SsimFilter` from the `filters` dict, rejecting unknown keys.
+fn ssim_filter(filters: Option<&Bound<'_, PyDict>>) -> PyResult<SsimFilter> {
+    let mut filter = SsimFilter::new();
+    let Some(filters) = filters else {
+        return Ok(filter);
+    };
+
+    for (key, value) in filters.iter() {
+        let key: String = key.extract()?;
+        filter = match key.as_str() {
+            "airlines" => filter.with_airlines(value.extract::<Vec<String>>()?),
+            "departure_stations" => filter.with_departure_stations(value.extract::<Vec<String>>()?),
+            "arrival_stations" => filter.with_arrival_stations(value.extract::<Vec<String>>()?),
+            "date_from" => filter.with_date_from(filter_date(&value)?),
+            "date_to" => filter.with_date_to(filter_date(&value)?),
+            "service_types" => filter.with_service_types(value.extract::<Vec<String>>()?),
+            "aircraft_types" => filter.with_aircraft_types(value.extract::<Vec<String>>()?),
+            other => return Err(value_error(format!("unknown filter '{}'", other))),
+        };
+    }
+    Ok(filter)
+}
+
+/// Accept a `datetime.date` or an ISO `YYYY-MM-DD` string.
+fn filter_date(value: &Bound<'_, PyAny>) -> PyResult<NaiveDate> {
+    let text = value.str()?.to_string();
+    text.parse::<NaiveDate>()
+        .map_err(|e| value_error(format!("invalid date '{}': {}", text, e)))
+}
+
+/// Convert `Diagnostics` into a plain dict of `lines_read`, `counts` and `issues`.
+fn diagnostics_to_py<'py>(
+    py: Python<'py>,
+    diagnostics: &Diagnostics,
+) -> PyResult<Bound<'py, PyDict>> {
+    let counts = PyDict::new(py);
+    for (kind, count) in &diagnostics.counts {
+        counts.set_item(kind.as_str(), count)?;
+    }
+
+    let issues = PyList::empty(py);
+    for issue in &diagnostics.issues {
+        let item = PyDict::new(py);
+        item.set_item("kind", issue.kind.as_str())?;
+        item.set_item("file_path", issue.location.file_path.clone())?;
+        item.set_item("line_number", issue.location.line_number)?;
+        item.set_item("byte_offset", issue.location.byte_offset)?;
+        item.set_item(
+            "record_serial_number",
+            issue.location.record_serial_number.clone(),
+        )?;
+        item.set_item("reason", issue.reason.clone())?;
+        issues.append(item)?;
+    }
+
+    let result = PyDict::new(py);
+    result.set_item("lines_read", diagnostics.lines_read)?;
+    result.set_item("counts", counts)?;
+    result.set_item("issues", issues)?;
+    Ok(result)
+}
+
+/// Return `value` on its own, or as `(value, diagnostics)` when diagnostics were requested.
+fn with_diagnostics<'py, T>(
+    py: Python<'py>,
+    value: T,
+    diagnostics: &Diagnostics,
+    return_diagnostics: bool,
+) -> PyResult<Bound<'py, PyAny>>
+where
+    T: IntoPyObject<'py>,
+{
+    if return_diagnostics {
+        let diagnostics = diagnostics_to_py(py, diagnostics)?;
+        (value, diagnostics).into_bound_py_any(py)
+    } else {
+        value.into_bound_py_any(py)
+    }
+}
+
+/// Where the SSIM data comes from: a path on disk or an in-memory / file-like object.
+enum SsimSource {
+    Path(String),
+    Reader(Box<dyn Read + Send>),
+}
+
+impl SsimSource {
+    /// Accept a `str` / `os.PathLike` path, `bytes` / `bytearray`, or any object with a `read()` method.
+    fn extract(obj: &Bound<'_, PyAny>) -> PyResult<Self> {
+        if let Ok(bytes) = obj.cast::<PyBytes>() {
+            return Ok(SsimSource::Reader(Box::new(Cursor::new(
+                bytes.as_bytes().to_vec(),
+            ))));
+        }
+        if let Ok(bytes) = obj.cast::<PyByteArray>() {
+            return Ok(SsimSource::Reader(Box::new(Cursor::new(bytes.to_vec()))));
+        }
+        if obj.hasattr("read")? {
+            return Ok(SsimSource::Reader(Box::new(PyFileReader::new(
+                obj.clone().unbind(),
+            ))));
+        }
+        if let Ok(path) = obj.cast::<PyString>() {
+            return Ok(SsimSource::Path(path.to_str()?.to_string()));
+        }
+        if let Ok(path) = obj.extract::<PathBuf>() {
+            return Ok(SsimSource::Path(path.to_string_lossy().into_owned()));
+        }
+        Err(PyTypeError::new_err(
+            "file_path must be a path, bytes, or a file-like object with a read() method",
+        ))
+    }
+}
+
+/// Adapts a Python file-like object to `std::io::Read`, re-acquiring the GIL for each `read()` call.
+struct PyFileReader {
+    file: Py<PyAny>,
+    pending: Cursor<Vec<u8>>,
+}
+
+impl PyFileReader {
+    fn new(file: Py<PyAny>) -> Self {
+        Self {
+            file,
+            pending: Cursor::new(Vec::new()),
+        }
+    }
+
+    fn read_chunk(&self, size: usize) -> io::Result<Vec<u8>> {
+        Python::attach(|py| {
+            let chunk = self.file.call_method1(py, "read", (size,))?;
+            let chunk = chunk.bind(py);
+            if let Ok(bytes) = chunk.cast::<PyBytes>() {
+                Ok(bytes.as_bytes().to_vec())
+            } else if let Ok(text) = chunk.cast::<PyString>() {
+                // Text-mode files return `str`; re-encode it so the reader sees UTF-8 bytes.
+                Ok(text.to_str()?.as_bytes().to_vec())
+            } else {
+                chunk.extract::<Vec<u8>>()
+            }
+        })
+        .map_err(io::Error::other)
+    }
+}
+
+impl Read for PyFileReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.pending.read(buf)?;
+        if read > 0 || buf.is_empty() {
+            return Ok(read);
+        }
+        self.pending = Cursor::new(self.read_chunk(buf.len())?);
+        self.pending.read(buf)
+    }
+}
+
+/// Map an `SsimError` to its Python exception class, attaching the record
+/// location as `file_path`, `line_number`, `byte_offset` and `record_serial_number` attributes.
+fn to_py_err(py: Python<'_>, e: SsimError, context: &str) -> PyErr {
+    let msg = format!("{}: {}", context, e);
+    let err = match &e {
+        SsimError::TruncatedRecord { .. } => TruncatedRecordError::new_err(msg),
+        SsimError::InvalidRecordType { .. } => InvalidRecordTypeError::new_err(msg),
+        SsimError::InvalidUtf8 { .. } => InvalidUtf8Error::new_err(msg),
+        SsimError::MissingCarrierContext { .. } => MissingCarrierError::new_err(msg),
+        SsimError::Io { .. } => SsimIOError::new_err(msg),
+        SsimError::Polars(_) => return runtime_error(msg),
+    };
+
+    if let Some(location) = e.location() {
+        let value = err.value(py);
+        let _ = value.setattr("file_path", location.file_path.clone());
+        let _ = value.setattr("line_number", location.line_number);
+        let _ = value.setattr("byte_offset", location.byte_offset);
+        let _ = value.setattr("record_serial_number", location.record_serial_number.clone());
+    }
+    err
+}
+
 #[pyfunction]
-#[pyo3(signature = (file_path, output_path, batch_size=10000, buffer_size=8192, condense_segments=false))]
-fn parse_ssim_to_csv(
-    py: Python<'_>,
-    file_path: &str,
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (file_path, output_path, batch_size=10000, buffer_size=8192, condense_segments=false, columns=None, filters=None, chunk_size=None, mode="strict", max_issues=100, return_diagnostics=false, write_mode="overwrite", delimiter=",", quote_style="necessary", null_value="", date_format=None, compression="uncompressed", per_carrier=false, native_dtypes=false))]
+fn parse_ssim_to_csv<'py>(
+    py: Python<'py>,
+    file_path: &Bound<'py, PyAny>,
     output_path: &str,
     batch_size: Option<usize>,
     buffer_size: Option<usize>,
     condense_segments: Option<bool>,
-) -> PyResult<()> {
-    py.detach(|| {
-        ssim_to_csv(
-            file_path,
-            output_path,
-            batch_size,
-            buffer_size,
-            condense_segments,
-        )
-    })
-    .map_err(|e| runtime_error(format!("Failed to process SSIM file: {}", e)))
+    columns: Option<Vec<String>>,
+    filters: Option<&Bound<'py, PyDict>>,
+    chunk_size: Option<usize>,
+    mode: &str,
+    max_issues: Option<usize>,
+    return_diagnostics: bool,
+    write_mode: &str,
+    delimiter: &str,
+    quote_style: &str,
+    null_value: &str,
+    date_format: Option<String>,
+    compression: &str,
+    per_carrier: bool,
+    native_dtypes: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    let source = SsimSource::extract(file_path)?;
+    let options = SsimOptions {
+        chunk_size,
+        native_dtypes,
+        csv: csv_options(
+            write_mode,
+            delimiter,
+            quote_style,
+            null_value,
+            date_format,
+            compression,
+            per_carrier,
+        )?,
+        ..reader_options(batch_size, buffer_size, mode, max_issues, columns, filters)?
+    };
+    let diagnostics = py
+        .detach(|| match source {
+            SsimSource::Path(path) => {
+                ssim_to_csv_with_options(&path, output_path, condense_segments, &options)
+            }
+            SsimSource::Reader(reader) => {
+                ssim_to_csv_from_reader(reader, output_path, condense_segments, &options)
+            }
+        })
+        .map_err(|e| to_py_err(py, e, "Failed to process SSIM file"))?;
+
+    with_diagnostics(py, py.None(), &diagnostics, return_diagnostics)
 }
 
 #[pyfunction]
-#[pyo3(signature = (file_path, output_path=".", compression="uncompressed", batch_size=10000, buffer_size=8192, condense_segments=false, serialize_segments=false))]
-fn parse_ssim_to_parquets(
-    py: Python<'_>,
-    file_path: &str,
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (file_path, output_path, compression="uncompressed", batch_size=10000, buffer_size=8192, condense_segments=false, native_dtypes=false, columns=None, filters=None, chunk_size=None, mode="strict", max_issues=100, return_diagnostics=false))]
+fn parse_ssim_to_ipc<'py>(
+    py: Python<'py>,
+    file_path: &Bound<'py, PyAny>,
+    output_path: &str,
+    compression: Option<&str>,
+    batch_size: Option<usize>,
+    buffer_size: Option<usize>,
+    condense_segments: Option<bool>,
+    native_dtypes: bool,
+    columns: Option<Vec<String>>,
+    filters: Option<&Bound<'py, PyDict>>,
+    chunk_size: Option<usize>,
+    mode: &str,
+    max_issues: Option<usize>,
+    return_diagnostics: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    let source = SsimSource::extract(file_path)?;
+    let options = SsimOptions {
+        native_dtypes,
+        chunk_size,
+        ..reader_options(batch_size, buffer_size, mode, max_issues, columns, filters)?
+    };
+    let diagnostics = py
+        .detach(|| match source {
+            SsimSource::Path(path) => ssim_to_ipc_with_options(
+                &path,
+                output_path,
+                compression,
+                condense_segments,
+                &options,
+            ),
+            SsimSource::Reader(reader) => ssim_to_ipc_from_reader(
+                reader,
+                output_path,
+                compression,
+                condense_segments,
+                &options,
+            ),
+        })
+        .map_err(|e| to_py_err(py, e, "Failed to process SSIM file"))?;
+
+    with_diagnostics(py, py.None(), &diagnostics, return_diagnostics)
+}
+
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (file_path, output_path, batch_size=10000, buffer_size=8192, condense_segments=false, native_dtypes=false, columns=None, filters=None, chunk_size=None, mode="strict", max_issues=100, return_diagnostics=false))]
+fn parse_ssim_to_ndjson<'py>(
+    py: Python<'py>,
+    file_path: &Bound<'py, PyAny>,
+    output_path: &str,
+    batch_size: Option<usize>,
+    buffer_size: Option<usize>,
+    condense_segments: Option<bool>,
+    native_dtypes: bool,
+    columns: Option<Vec<String>>,
+    filters: Option<&Bound<'py, PyDict>>,
+    chunk_size: Option<usize>,
+    mode: &str,
+    max_issues: Option<usize>,
+    return_diagnostics: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    let source = SsimSource::extract(file_path)?;
+    let options = SsimOptions {
+        native_dtypes,
+        chunk_size,
+        ..reader_options(batch_size, buffer_size, mode, max_issues, columns, filters)?
+    };
+    let diagnostics = py
+        .detach(|| match source {
+            SsimSource::Path(path) => {
+                ssim_to_ndjson_with_options(&path, output_path, condense_segments, &options)
+            }
+            SsimSource::Reader(reader) => {
+                ssim_to_ndjson_from_reader(reader, output_path, condense_segments, &options)
+            }
+        })
+        .map_err(|e| to_py_err(py, e, "Failed to process SSIM file"))?;
+
+    with_diagnostics(py, py.None(), &diagnostics, return_diagnostics)
+}
+
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (file_path, output_path=".", compression="uncompressed", batch_size=10000, buffer_size=8192, condense_segments=false, serialize_segments=false, native_dtypes=false, columns=None, filters=None, chunk_size=None, row_group_size=None, single_file=false, partition_by=None, mode="strict", max_issues=100, return_diagnostics=false))]
+fn parse_ssim_to_parquets<'py>(
+    py: Python<'py>,
+    file_path: &Bound<'py, PyAny>,
     output_path: Option<&str>,
     compression: Option<&str>,
     batch_size: Option<usize>,
     buffer_size: Option<usize>,
     condense_segments: Option<bool>,
     serialize_segments: Option<bool>,
-) -> PyResult<()> {
-    py.detach(|| {
-        ssim_to_parquets(
-            file_path,
-            output_path,
-            compression,
-            batch_size,
-            buffer_size,
-            condense_segments,
-            serialize_segments
-        )
-    })
-    .map_err(|e| runtime_error(format!("Failed to process SSIM file: {}", e)))
+    native_dtypes: bool,
+    columns: Option<Vec<String>>,
+    filters: Option<&Bound<'py, PyDict>>,
+    chunk_size: Option<usize>,
+    row_group_size: Option<usize>,
+    single_file: bool,
+    partition_by: Option<Vec<String>>,
+    mode: &str,
+    max_issues: Option<usize>,
+    return_diagnostics: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    let source = SsimSource::extract(file_path)?;
+    let options = SsimOptions {
+        native_dtypes,
+        chunk_size,
+        row_group_size,
+        parquet_layout: parquet_layout(single_file, partition_by)?,
+        ..reader_options(batch_size, buffer_size, mode, max_issues, columns, filters)?
+    };
+    let diagnostics = py
+        .detach(|| match source {
+            SsimSource::Path(path) => ssim_to_parquets_with_options(
+                &path,
+                output_path,
+                compression,
+                condense_segments,
+                serialize_segments,
+                &options,
+            ),
+            SsimSource::Reader(reader) => ssim_to_parquets_from_reader(
+                reader,
+                output_path,
+                compression,
+                condense_segments,
+                serialize_segments,
+                &options,
+            ),
+        })
+        .map_err(|e| to_py_err(py, e, "Failed to process SSIM file"))?;
+
+    with_diagnostics(py, py.None(), &diagnostics, return_diagnostics)
 }
 
 #[pyfunction]
-#[pyo3(signature = (file_path, batch_size=10000, buffer_size=8192))]
-fn split_ssim_to_dataframes(
-    py: Python<'_>,
-    file_path: &str,
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (file_path, batch_size=10000, buffer_size=8192, native_dtypes=false, columns=None, filters=None, chunk_size=None, mode="strict", max_issues=100, return_diagnostics=false, decode_deis=false))]
+fn split_ssim_to_dataframes<'py>(
+    py: Python<'py>,
+    file_path: &Bound<'py, PyAny>,
     batch_size: Option<usize>,
     buffer_size: Option<usize>,
-) -> PyResult<(PyDataFrame, PyDataFrame, PyDataFrame)> {
-    let (carrier_df, flights_df, segments_df) = py
-        .detach(|| ssim_to_dataframes(file_path, batch_size, buffer_size))
-        .map_err(|e| value_error(e.to_string()))?;
+    native_dtypes: bool,
+    columns: Option<Vec<String>>,
+    filters: Option<&Bound<'py, PyDict>>,
+    chunk_size: Option<usize>,
+    mode: &str,
+    max_issues: Option<usize>,
+    return_diagnostics: bool,
+    decode_deis: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    let source = SsimSource::extract(file_path)?;
+    let options = SsimOptions {
+        native_dtypes,
+        chunk_size,
+        decode_deis,
+        ..reader_options(batch_size, buffer_size, mode, max_issues, columns, filters)?
+    };
+    let ((carrier_df, flights_df, segments_df), diagnostics) = py
+        .detach(|| match source {
+            SsimSource::Path(path) => ssim_to_dataframes_with_options(&path, &options),
+            SsimSource::Reader(reader) => ssim_to_dataframes_from_reader(reader, &options),
+        })
+        .map_err(|e| to_py_err(py, e, "Failed to parse SSIM file"))?;
 
-    Ok((
+    let dataframes = (
         PyDataFrame(carrier_df),
         PyDataFrame(flights_df),
         PyDataFrame(segments_df),
-    ))
+    );
+    with_diagnostics(py, dataframes, &diagnostics, return_diagnostics)
 }
 
 #[pyfunction]
-#[pyo3(signature = (file_path, batch_size=10000, buffer_size=8192, condense_segments=false, serialize_segments=false))]
-fn parse_ssim_to_dataframe(
-    py: Python<'_>,
-    file_path: &str,
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (file_path, batch_size=10000, buffer_size=8192, condense_segments=false, serialize_segments=false, native_dtypes=false, columns=None, filters=None, chunk_size=None, mode="strict", max_issues=100, return_diagnostics=false))]
+fn parse_ssim_to_dataframe<'py>(
+    py: Python<'py>,
+    file_path: &Bound<'py, PyAny>,
     batch_size: Option<usize>,
     buffer_size: Option<usize>,
     condense_segments: Option<bool>,
-    serialize_segments: Option<bool>
+    serialize_segments: Option<bool>,
+    native_dtypes: bool,
+    columns: Option<Vec<String>>,
+    filters: Option<&Bound<'py, PyDict>>,
+    chunk_size: Option<usize>,
+    mode: &str,
+    max_issues: Option<usize>,
+    return_diagnostics: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    let source = SsimSource::extract(file_path)?;
+    let options = SsimOptions {
+        native_dtypes,
+        chunk_size,
+        ..reader_options(batch_size, buffer_size, mode, max_issues, columns, filters)?
+    };
+    let (ssim_dataframe, diagnostics) = py
+        .detach(|| match source {
+            SsimSource::Path(path) => ssim_to_dataframe_with_options(
+                &path,
+                condense_segments,
+                serialize_segments,
+                &options,
+            ),
+            SsimSource::Reader(reader) => ssim_to_dataframe_from_reader(
+                reader,
+                condense_segments,
+                serialize_segments,
+                &options,
+            ),
+        })
+        .map_err(|e| to_py_err(py, e, "Failed to parse SSIM file"))?;
+
+    with_diagnostics(
+        py,
+        PyDataFrame(ssim_dataframe),
+        &diagnostics,
+        return_diagnostics,
+    )
+}
+
+#[pyfunction]
+#[pyo3(signature = (flights, date_from=None, date_to=None))]
+fn expand_to_dated_flights(
+    py: Python<'_>,
+    flights: PyDataFrame,
+    date_from: Option<&Bound<'_, PyAny>>,
+    date_to: Option<&Bound<'_, PyAny>>,
 ) -> PyResult<PyDataFrame> {
-    let ssim_dataframe = py
-        .detach(|| ssim_to_dataframe(file_path, batch_size, buffer_size, condense_segments, serialize_segments))
-        .map_err(|e| value_error(e.to_string()))?;
+    let date_from = date_from.map(filter_date).transpose()?;
+    let date_to = date_to.map(filter_date).transpose()?;
+    let dated = py
+        .detach(|| expand_dated_flights(&flights.0, date_from, date_to))
+        .map_err(|e| runtime_error(format!("Failed to expand flights: {}", e)))?;
+    Ok(PyDataFrame(dated))
+}
 
-    Ok(PyDataFrame(ssim_dataframe))
+#[pyfunction]
+fn add_leg_times(py: Python<'_>, legs: PyDataFrame) -> PyResult<PyDataFrame> {
+    let timed = py
+        .detach(|| add_times(&legs.0))
+        .map_err(|e| runtime_error(format!("Failed to add leg times: {}", e)))?;
+    Ok(PyDataFrame(timed))
+}
+
+#[pyfunction]
+fn add_dei_columns(py: Python<'_>, segments: PyDataFrame) -> PyResult<PyDataFrame> {
+    let decoded = py
+        .detach(|| add_deis(&segments.0))
+        .map_err(|e| runtime_error(format!("Failed to decode DEIs: {}", e)))?;
+    Ok(PyDataFrame(decoded))
+}
+
+#[pyfunction]
+fn codeshare_table(py: Python<'_>, segments: PyDataFrame) -> PyResult<PyDataFrame> {
+    let table = py
+        .detach(|| codeshares(&segments.0))
+        .map_err(|e| runtime_error(format!("Failed to find codeshares: {}", e)))?;
+    Ok(PyDataFrame(table))
+}
+
+#[pyfunction]
+#[pyo3(signature = (flights, segments, emit_marketing_legs=false))]
+fn add_operating_flights(
+    py: Python<'_>,
+    flights: PyDataFrame,
+    segments: PyDataFrame,
+    emit_marketing_legs: bool,
+) -> PyResult<PyDataFrame> {
+    let marked = py
+        .detach(|| add_operating(&flights.0, &segments.0, emit_marketing_legs))
+        .map_err(|e| runtime_error(format!("Failed to find codeshares: {}", e)))?;
+    Ok(PyDataFrame(marked))
+}
+
+#[pyfunction]
+fn collapse_codeshares(
+    py: Python<'_>,
+    flights: PyDataFrame,
+    segments: PyDataFrame,
+) -> PyResult<PyDataFrame> {
+    let operating = py
+        .detach(|| collapse(&flights.0, &segments.0))
+        .map_err(|e| runtime_error(format!("Failed to find codeshares: {}", e)))?;
+    Ok(PyDataFrame(operating))
+}
+
+#[pyfunction]
+#[pyo3(signature = (legs, aircraft_versions=None))]
+fn add_seat_columns(
+    py: Python<'_>,
+    legs: PyDataFrame,
+    aircraft_versions: Option<PathBuf>,
+) -> PyResult<PyDataFrame> {
+    let seated = py
+        .detach(|| {
+            let versions = aircraft_versions
+                .map(|path| AircraftVersions::from_csv(&path.to_string_lossy()))
+                .transpose()?;
+            add_seats(&legs.0, versions.as_ref())
+        })
+        .map_err(|e| runtime_error(format!("Failed to add seat columns: {}", e)))?;
+    Ok(PyDataFrame(seated))
+}
+
+#[pyfunction]
+fn read_parquet_metadata<'py>(
+    py: Python<'py>,
+    file_path: PathBuf,
+) -> PyResult<Option<Bound<'py, PyDict>>> {
+    let file_path = file_path.to_string_lossy().into_owned();
+    let Some(metadata) = read_metadata(&file_path)
+        .map_err(|e| to_py_err(py, e, "Failed to read Parquet metadata"))?
+    else {
+        return Ok(None);
+    };
+    let result = PyDict::new(py);
+    result.set_item("rusty_ssim_version", metadata.rusty_ssim_version)?;
+    result.set_item("schema_version", metadata.schema_version)?;
+    result.set_item("source_file", metadata.source_file)?;
+    result.set_item("header", metadata.header)?;
+    result.set_item("carrier", metadata.carrier)?;
+    Ok(Some(result))
 }
 
 #[pymodule]
@@ -104,5 +675,21 @@ fn rustyssim(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(split_ssim_to_dataframes, m)?)?;
     m.add_function(wrap_pyfunction!(parse_ssim_to_csv, m)?)?;
     m.add_function(wrap_pyfunction!(parse_ssim_to_parquets, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_ssim_to_ipc, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_ssim_to_ndjson, m)?)?;
+    m.add_function(wrap_pyfunction!(expand_to_dated_flights, m)?)?;
+    m.add_function(wrap_pyfunction!(add_leg_times, m)?)?;
+    m.add_function(wrap_pyfunction!(add_dei_columns, m)?)?;
+    m.add_function(wrap_pyfunction!(codeshare_table, m)?)?;
+    m.add_function(wrap_pyfunction!(add_operating_flights, m)?)?;
+    m.add_function(wrap_pyfunction!(collapse_codeshares, m)?)?;
+    m.add_function(wrap_pyfunction!(add_seat_columns, m)?)?;
+    m.add_function(wrap_pyfunction!(read_parquet_metadata, m)?)?;
+    m.add("SsimParseError", m.py().get_type::<SsimParseError>())?;
+    m.add("TruncatedRecordError", m.py().get_type::<TruncatedRecordError>())?;
+    m.add("InvalidRecordTypeError", m.py().get_type::<InvalidRecordTypeError>())?;
+    m.add("InvalidUtf8Error", m.py().get_type::<InvalidUtf8Error>())?;
+    m.add("MissingCarrierError", m.py().get_type::<MissingCarrierError>())?;
+    m.add("SsimIOError", m.py().get_type::<SsimIOError>())?;
     Ok(())
 }
@@ -2,6 +2,7 @@ import pytest
 import polars as pl
 import tempfile
 import os
+import datetime
 from pathlib import Path
 
 
@@ -180,6 +181,274 @@ def test_error_handling_invalid_file():
         rustyssim.parse_ssim_to_dataframe("non_existent_file.ssim")
 
 
+def test_lenient_mode_returns_diagnostics(tmp_path, typical_ssim_content):
+    """Test that lenient mode skips malformed lines and reports them"""
+    lines = typical_ssim_content.splitlines()
+    flight_index = next(i for i, line in enumerate(lines) if line.startswith("3"))
+    lines[flight_index] = "9" + lines[flight_index][1:]
+    ssim_path = tmp_path / "malformed.ssim"
+    ssim_path.write_text("\n".join(lines))
+
+    with pytest.raises(rustyssim.InvalidRecordTypeError) as excinfo:
+        rustyssim.parse_ssim_to_dataframe(str(ssim_path))
+    assert excinfo.value.line_number == flight_index + 1
+
+    df, diagnostics = rustyssim.parse_ssim_to_dataframe(
+        str(ssim_path), mode="lenient", return_diagnostics=True
+    )
+    assert isinstance(df, pl.DataFrame)
+    # The fixture trailer's check reference is a placeholder, so it is reported too.
+    assert diagnostics["counts"]["invalid_record_type"] == 1
+    assert diagnostics["issues"][0]["line_number"] == flight_index + 1
+
+
+def test_split_carriers_include_header_and_trailer(temp_ssim_file):
+    """Test that header and trailer fields are joined onto the carriers DataFrame"""
+    carriers, _, _ = rustyssim.split_ssim_to_dataframes(temp_ssim_file)
+
+    assert carriers["title_of_contents"].to_list() == ["AIRLINE STANDARD SCHEDULE DATA SET"]
+    assert carriers["data_set_serial_number"].to_list() == ["001"]
+    assert carriers["serial_number_check_reference"].to_list() == ["000011"]
+    assert carriers["continuation_end_code"].to_list() == ["E"]
+
+
+def test_native_dtypes(temp_ssim_file):
+    """Test that native_dtypes converts SSIM text columns to Polars types"""
+    df = rustyssim.parse_ssim_to_dataframe(temp_ssim_file, native_dtypes=True)
+
+    assert df["period_of_operation_from"].dtype == pl.Date
+    assert df["scheduled_time_of_aircraft_departure"].dtype == pl.Duration("ms")
+    assert df["time_variation_departure"].dtype == pl.Int16
+    assert df["leg_sequence_number"].dtype == pl.UInt8
+    assert df["days_of_operation"].dtype == pl.UInt8
+    assert df["departure_station"].dtype == pl.Categorical
+
+    _, flights, _ = rustyssim.split_ssim_to_dataframes(temp_ssim_file, native_dtypes=True)
+    assert flights["period_of_operation_to"].dtype == pl.Date
+
+
+def test_expand_to_dated_flights(temp_ssim_file):
+    """Test expanding flight legs into one row per operating date"""
+    flights = pl.DataFrame(
+        {
+            "flight_designator": ["XX 1", "XX 2"],
+            "period_of_operation_from": ["25MAR18", "26MAR18"],
+            "period_of_operation_to": ["22APR18", "01APR18"],
+            "days_of_operation": ["1 3    ", "      7"],
+            "frequency_rate": ["2", " "],
+            "date_variation": ["01", "  "],
+        }
+    )
+
+    dated = rustyssim.expand_to_dated_flights(flights)
+    assert dated["flight_date"].dtype == pl.Date
+    assert dated["flight_date"].cast(pl.Utf8).to_list() == [
+        "2018-04-02",
+        "2018-04-04",
+        "2018-04-16",
+        "2018-04-18",
+        "2018-04-01",
+    ]
+    assert dated["arrival_date"].cast(pl.Utf8).to_list()[0] == "2018-04-03"
+
+    windowed = rustyssim.expand_to_dated_flights(
+        flights, date_from=datetime.date(2018, 4, 3), date_to="2018-04-16"
+    )
+    assert windowed["flight_designator"].to_list() == ["XX 1", "XX 1"]
+
+    _, text_flights, _ = rustyssim.split_ssim_to_dataframes(temp_ssim_file)
+    _, native_flights, _ = rustyssim.split_ssim_to_dataframes(temp_ssim_file, native_dtypes=True)
+    assert (
+        rustyssim.expand_to_dated_flights(text_flights).height
+        == rustyssim.expand_to_dated_flights(native_flights).height
+    )
+
+    with pytest.raises(RuntimeError, match="period_of_operation_to"):
+        rustyssim.expand_to_dated_flights(
+            flights.with_columns(period_of_operation_to=pl.lit("00XXX00"))
+        )
+
+
+def test_add_leg_times():
+    """Test local and UTC leg times and block times"""
+    legs = pl.DataFrame(
+        {
+            "flight_date": [datetime.date(2018, 4, 3)] * 3,
+            "scheduled_time_of_aircraft_departure": ["0510", "2300", "0510"],
+            "time_variation_departure": ["+0000", "+0000", "+0000"],
+            "scheduled_time_of_aircraft_arrival": ["0800", "0130", "0130"],
+            "time_variation_arrival": ["+0200", "+0200", "+0200"],
+            "date_variation": ["  ", "01", "  "],
+            "time_mode": ["L", "L", "U"],
+        }
+    )
+
+    timed = rustyssim.add_leg_times(legs)
+    assert timed["arrival_utc"].dtype == pl.Datetime("ms")
+    assert timed["arrival_local"][1] == datetime.datetime(2018, 4, 4, 1, 30)
+    assert timed["arrival_utc"][1] == datetime.datetime(2018, 4, 3, 23, 30)
+    assert timed["block_time_minutes"].to_list() == [50, 30, -220]
+    assert timed["block_time_suspect"].to_list() == [False, False, True]
+
+    patterns = rustyssim.add_leg_times(legs.drop("flight_date"))
+    assert patterns["departure_local"].dtype == pl.Duration("ms")
+    assert patterns["arrival_local"][1] == datetime.timedelta(days=1, minutes=90)
+
+    with pytest.raises(RuntimeError, match="time_variation_arrival"):
+        rustyssim.add_leg_times(legs.drop("time_variation_arrival"))
+
+
+def test_add_dei_columns(temp_ssim_file):
+    """Test DEI registry names and decoded segment data"""
+    segments = pl.DataFrame(
+        {
+            "data_element_identifier": ["050", "010", "501", "999", "505"],
+            "data": ["KL 2562", "AF 1234/KQ5678A", "YBJM", "UNKNOWN", "XX"],
+        }
+    )
+
+    decoded = rustyssim.add_dei_columns(segments)
+    assert decoded["dei_name"].to_list() == [
+        "Duplicate Leg Cross Reference",
+        "Code Share - Commercial Duplicate",
+        "Meal Service Note",
+        None,
+        "Electronic Ticketing Information",
+    ]
+    assert decoded["dei_level"].to_list() == ["leg", "leg", "segment", None, "flight"]
+    assert decoded["dei_values"].to_list() == [
+        ["KL 2562"],
+        ["AF 1234", "KQ 5678A"],
+        ["Y:B", "J:M"],
+        None,
+        None,
+    ]
+
+    _, _, split = rustyssim.split_ssim_to_dataframes(temp_ssim_file, decode_deis=True)
+    assert {"dei_name", "dei_level", "dei_values"} <= set(split.columns)
+
+    with pytest.raises(RuntimeError, match="data_element_identifier"):
+        rustyssim.add_dei_columns(segments.drop("data_element_identifier"))
+
+
+def test_codeshares():
+    """Test the codeshare table, operating flights and collapsed duplicates"""
+    flights = pl.DataFrame(
+        {
+            "flight_designator": ["XX _   12  01  ", "KL _ 2562  01  "],
+            "leg_sequence_number": ["01", "01"],
+            "airline_designator": ["XX ", "KL "],
+            "flight_number": ["  12", "2562"],
+            "operational_suffix": [" ", " "],
+            "departure_station": ["KEF", "KEF"],
+            "arrival_station": ["AMS", "AMS"],
+        }
+    )
+    segments = flights.select(
+        "flight_designator", "leg_sequence_number", "airline_designator", "flight_number", "operational_suffix"
+    ).with_columns(
+        board_point=pl.lit("KEF"),
+        off_point=pl.lit("AMS"),
+        data_element_identifier=pl.Series(["010", "050"]),
+        data=pl.Series(["KL 2562/AF 1234", "XX   12"]),
+    )
+
+    table = rustyssim.codeshare_table(segments)
+    assert table["marketing_airline"].to_list() == ["KL", "AF"]
+    assert table["marketing_flight_number"].to_list() == [2562, 1234]
+    assert table["operating_airline"].to_list() == ["XX", "XX"]
+
+    marked = rustyssim.add_operating_flights(flights, segments)
+    assert marked["operating_flight"].to_list() == [None, "XX 12"]
+    expanded = rustyssim.add_operating_flights(flights, segments, emit_marketing_legs=True)
+    assert expanded["airline_designator"].to_list() == ["XX ", "KL ", "AF "]
+    assert expanded["operating_flight"][2] == "XX 12"
+
+    assert rustyssim.collapse_codeshares(flights, segments)["airline_designator"].to_list() == ["XX "]
+
+
+def test_add_seat_columns(tmp_path):
+    """Test seats per cabin from aircraft configurations and a version table"""
+    versions = tmp_path / "versions.csv"
+    versions.write_text("airline_designator,aircraft_version,aircraft_configuration\nXX,32A,C12Y150\n")
+    legs = pl.DataFrame(
+        {
+            "airline_designator": ["XX ", "XX ", "XX "],
+            "aircraft_configuration": ["J30W24Y250", "VV32A", "VV999"],
+        }
+    )
+
+    seats = rustyssim.add_seat_columns(legs, aircraft_versions=versions)
+    assert seats["seats_c"].to_list() == [30, 12, None]
+    assert seats["seats_w"].to_list() == [24, 0, None]
+    assert seats["total_seats"].to_list() == [304, 162, None]
+    assert seats["cabin_seats"][0].struct.field("cabin").to_list() == ["J", "W", "Y"]
+    assert rustyssim.add_seat_columns(legs)["total_seats"].to_list() == [304, None, None]
+
+
+def test_column_selection(temp_ssim_file):
+    """Test that columns limits and orders the output columns"""
+    columns = ["departure_station", "flight_number", "board_point"]
+    full = rustyssim.parse_ssim_to_dataframe(temp_ssim_file)
+    df = rustyssim.parse_ssim_to_dataframe(temp_ssim_file, columns=columns)
+
+    assert df.columns == columns
+    assert df.equals(full.select(columns))
+
+    with pytest.raises(ValueError, match="unknown column"):
+        rustyssim.parse_ssim_to_dataframe(temp_ssim_file, columns=["no_such_column"])
+
+
+def test_filters(temp_multi_ssim_file):
+    """Test that filters skips other carriers and flight legs before parsing"""
+    import datetime
+
+    full = rustyssim.parse_ssim_to_dataframe(temp_multi_ssim_file)
+    df = rustyssim.parse_ssim_to_dataframe(
+        temp_multi_ssim_file,
+        filters={"airlines": ["YY"], "departure_stations": ["KEF"], "date_from": datetime.date(2018, 4, 1)},
+    )
+
+    assert len(df) > 0
+    assert df.equals(
+        full.filter((pl.col("airline_designator") == "YY ") & (pl.col("departure_station") == "KEF"))
+    )
+
+    _, flights_df, _ = rustyssim.split_ssim_to_dataframes(
+        temp_multi_ssim_file, filters={"date_from": "2018-04-04"}
+    )
+    assert len(flights_df) == 0
+
+    with pytest.raises(ValueError, match="unknown filter"):
+        rustyssim.parse_ssim_to_dataframe(temp_multi_ssim_file, filters={"carriers": ["YY"]})
+
+
+def test_chunk_size_matches_sequential(temp_multi_ssim_file):
+    """Test that the chunk-parallel reader returns the same rows as the sequential one"""
+    expected = rustyssim.parse_ssim_to_dataframe(temp_multi_ssim_file)
+    df = rustyssim.parse_ssim_to_dataframe(temp_multi_ssim_file, chunk_size=1024)
+
+    assert df.equals(expected)
+
+
+def test_parse_ssim_from_bytes_and_file_like(temp_ssim_file):
+    """Test parsing from bytes and file-like objects instead of a path"""
+    import io
+
+    expected = rustyssim.parse_ssim_to_dataframe(temp_ssim_file)
+    raw = Path(temp_ssim_file).read_bytes()
+
+    from_bytes = rustyssim.parse_ssim_to_dataframe(raw)
+    from_buffer = rustyssim.parse_ssim_to_dataframe(io.BytesIO(raw), buffer_size=512)
+    with open(temp_ssim_file, "r") as text_file:
+        from_text_file = rustyssim.parse_ssim_to_dataframe(text_file)
+
+    assert from_bytes.equals(expected)
+    assert from_buffer.equals(expected)
+    assert from_text_file.equals(expected)
+    assert rustyssim.parse_ssim_to_dataframe(Path(temp_ssim_file)).equals(expected)
+
+
 @pytest.mark.parametrize("batch_size", [1, 10, 100, 1000])
 def test_batch_size_consistency(temp_ssim_file, batch_size):
     """Test that different batch sizes produce consistent results"""
@@ -223,6 +492,49 @@ def test_parse_ssim_to_csv(temp_ssim_file):
             os.unlink(output_path)
 
 
+def test_parse_ssim_to_csv_write_modes_and_formats(temp_ssim_file):
+    """Test CSV write modes, format options and compression"""
+    with tempfile.TemporaryDirectory() as temp_dir:
+        output_path = os.path.join(temp_dir, "output.csv")
+        rustyssim.parse_ssim_to_csv(temp_ssim_file, output_path)
+        rows = len(pl.read_csv(output_path))
+
+        # Overwriting is the default, so rows are not doubled.
+        rustyssim.parse_ssim_to_csv(temp_ssim_file, output_path)
+        assert len(pl.read_csv(output_path)) == rows
+
+        with pytest.raises(RuntimeError):
+            rustyssim.parse_ssim_to_csv(temp_ssim_file, output_path, write_mode="fail")
+
+        rustyssim.parse_ssim_to_csv(temp_ssim_file, output_path, write_mode="append")
+        assert len(pl.read_csv(output_path)) == 2 * rows
+
+        with pytest.raises(RuntimeError):
+            rustyssim.parse_ssim_to_csv(
+                temp_ssim_file, output_path, write_mode="append", delimiter=";"
+            )
+
+        gzip_path = os.path.join(temp_dir, "output.csv.gz")
+        rustyssim.parse_ssim_to_csv(
+            temp_ssim_file,
+            gzip_path,
+            delimiter="|",
+            compression="gzip",
+            native_dtypes=True,
+            date_format="%d/%m/%Y",
+        )
+        df = pl.read_csv(gzip_path, separator="|")
+        assert len(df) == rows
+        assert df["period_of_operation_from"].str.contains("/").all()
+
+        carrier_dir = os.path.join(temp_dir, "carriers")
+        rustyssim.parse_ssim_to_csv(temp_ssim_file, carrier_dir, per_carrier=True)
+        assert os.listdir(carrier_dir) == ["ssim_XX_.csv"]
+
+        with pytest.raises(ValueError):
+            rustyssim.parse_ssim_to_csv(temp_ssim_file, output_path, compression="bzip2")
+
+
 def test_parse_ssim_to_parquets(temp_ssim_file):
     """Test parsing SSIM to Parquet files"""
     with tempfile.TemporaryDirectory() as temp_dir:
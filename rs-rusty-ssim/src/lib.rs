@@ -21,8 +21,11 @@
 //! |----------|--------|
 //! | [`ssim_to_dataframe`] | Single `DataFrame` |
 //! | [`ssim_to_dataframes`] | Three `DataFrame`s (carriers, flights, segments) |
-//! | [`ssim_to_csv`] | CSV file on disk |
+//! | [`ssim_to_csv`] | CSV file on disk, or one per carrier (see [`CsvOptions`]) |
+//! | [`ssim_to_ipc`] | Arrow IPC (Feather) file on disk |
+//! | [`ssim_to_ndjson`] | Newline-delimited JSON file on disk |
 //! | [`ssim_to_parquets`] | Parquet files (one per carrier) |
+//! | [`ssim_to_dated_flights`] | `DataFrame` of flight legs, one row per operating date |
 //!
 //! ## Segment Condensing
 //!
@@ -30,19 +33,265 @@
 //! records for the same flight into a single JSON column, reducing row count
 //! and file size.
 //!
+//! ## Column Selection
+//!
+//! `SsimOptions::columns` limits the output to the given columns, in order.
+//! Flight leg and segment fields that are not selected are never sliced out
+//! of the line or copied, which speeds up jobs that only need a few fields:
+//!
+//! ```no_run
+//! use rustyssim::{SsimOptions, ssim_to_dataframe_with_options};
+//!
+//! let options = SsimOptions {
+//!     columns: "flight_designator,departure_station,arrival_station".parse()?,
+//!     ..Default::default()
+//! };
+//! let (df, _) = ssim_to_dataframe_with_options("schedule.ssim", None, None, &options)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! ## Filtering
+//!
+//! `SsimOptions::filter` takes an [`SsimFilter`] that is matched against the
+//! raw lines before they are parsed. Carrier blocks of other airlines are
+//! skipped whole, and flight legs can be limited by station, service type,
+//! aircraft type and a date window overlapping their period of operation:
+//!
+//! ```no_run
+//! use rustyssim::chrono::NaiveDate;
+//! use rustyssim::{SsimFilter, SsimOptions, ssim_to_dataframe_with_options};
+//!
+//! let options = SsimOptions {
+//!     filter: SsimFilter::new()
+//!         .with_airlines(["XX"])
+//!         .with_departure_stations(["KEF"])
+//!         .with_date_from(NaiveDate::from_ymd_opt(2025, 3, 30).unwrap())
+//!         .with_date_to(NaiveDate::from_ymd_opt(2025, 10, 25).unwrap()),
+//!     ..Default::default()
+//! };
+//! let (df, _) = ssim_to_dataframe_with_options("schedule.ssim", None, None, &options)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! ## Lenient Parsing
+//!
+//! The `*_with_options` variants take an [`SsimOptions`] and return a
+//! [`Diagnostics`] report alongside the output. With
+//! [`ParseMode::Lenient`], malformed lines are skipped and counted per
+//! [`IssueKind`] instead of failing the whole read:
+//!
+//! ```no_run
+//! use rustyssim::{ParseMode, SsimOptions, ssim_to_dataframe_with_options};
+//!
+//! let options = SsimOptions { parse_mode: ParseMode::Lenient, ..Default::default() };
+//! let (df, diagnostics) = ssim_to_dataframe_with_options("schedule.ssim", None, None, &options)
+//!     .expect("Failed to parse SSIM file");
+//!
+//! if !diagnostics.is_clean() {
+//!     eprintln!("{}", diagnostics);
+//! }
+//! ```
+//!
+//! In both modes, trailer records whose check reference or end code disagree
+//! with their carrier block, breaks in the record serial numbers and lines
+//! longer than 200 bytes are reported as warnings in the same [`Diagnostics`].
+//!
+//! ## Compressed Inputs
+//!
+//! Gzip, zstd and bzip2 inputs are detected from their magic bytes and
+//! decompressed on the fly, whether read from a path or a stream. A `.zip`
+//! path, including one opened with [`SsimReader::new`], is read member by
+//! member as one logical input; errors point at `archive.zip!member.ssim`.
+//!
+//! ## Reading from Streams
+//!
+//! The `*_from_reader` variants accept any [`std::io::Read`] source, so data
+//! from stdin, an HTTP body or an in-memory buffer never has to be written
+//! to a temp file first:
+//!
+//! ```no_run
+//! use rustyssim::{SsimOptions, ssim_to_dataframe_from_reader};
+//!
+//! let stdin = std::io::stdin().lock();
+//! let (df, _) = ssim_to_dataframe_from_reader(stdin, None, None, &SsimOptions::default())
+//!     .expect("Failed to parse SSIM from stdin");
+//! ```
+//!
+//! ## Iterating Records
+//!
+//! [`SsimReader::records`] yields one [`SsimRecord`] per header, carrier,
+//! flight leg, segment and trailer line, tagged with its line number. It never
+//! builds a DataFrame, so a loop can stop as soon as it has what it needs:
+//!
+//! ```no_run
+//! use rustyssim::{SsimReader, SsimRecord};
+//!
+//! let mut reader = SsimReader::new("schedule.ssim", None, None)?;
+//! let first_departure = reader.records().find_map(|record| match record {
+//!     Ok(SsimRecord::FlightLeg { record, .. }) => Some(Ok(record.departure_station.into_owned())),
+//!     Ok(_) => None,
+//!     Err(e) => Some(Err(e)),
+//! });
+//! # Ok::<(), rustyssim::SsimError>(())
+//! ```
+//!
+//! ## Typed Fields
+//!
+//! Record fields are kept as the raw SSIM text. [`TypedFlightLeg`] converts a
+//! flight leg into dates, minutes since midnight, signed-minute UTC offsets
+//! and a [`DaysOfWeek`] bitset, returning a [`FieldError`] for values such as
+//! `32JAN25` or `2460`:
+//!
+//! ```no_run
+//! use rustyssim::chrono::Weekday;
+//! use rustyssim::{SsimReader, SsimRecord, TypedFlightLeg};
+//!
+//! let mut reader = SsimReader::new("schedule.ssim", None, None)?;
+//! for record in reader.records() {
+//!     if let SsimRecord::FlightLeg { record, .. } = record? {
+//!         let leg = TypedFlightLeg::try_from(record.as_ref())?;
+//!         if leg.days_of_operation.contains(Weekday::Mon) {
+//!             println!(
+//!                 "{} departs at minute {}",
+//!                 leg.flight_designator, leg.scheduled_time_of_aircraft_departure
+//!             );
+//!         }
+//!     }
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! For DataFrame and Parquet output, `SsimOptions { native_dtypes: true, .. }`
+//! applies the same conversions column-wise: `Date` periods, `Duration`
+//! scheduled times, `Int16` UTC offsets, a `UInt8` days-of-operation bitmask
+//! and `Categorical` station codes.
+//!
+//! ## Data Element Identifiers
+//!
+//! A segment data record holds one data element, such as a codeshare list or
+//! a meal note, identified by a three-digit DEI. [`dei_info`] looks up the
+//! name and level of the DEIs in [`DEI_REGISTRY`], and [`decode_dei`] or
+//! [`SegmentRecords::decode_data`] turn their data into a typed [`DeiValue`].
+//! `SsimOptions { decode_deis: true, .. }` adds the decoded DEIs to the
+//! segments `DataFrame` of [`ssim_to_dataframes_with_options`], and
+//! [`add_dei_columns`] does the same for any `DataFrame` with segment columns:
+//!
+//! ```no_run
+//! use rustyssim::{DeiValue, decode_dei};
+//!
+//! if let Some(DeiValue::Flights(marketing)) = decode_dei(10, "AF 1234/KQ 5678")? {
+//!     assert_eq!(marketing[0].to_string(), "AF 1234");
+//! }
+//! # Ok::<(), rustyssim::FieldError>(())
+//! ```
+//!
+//! ## Codeshares
+//!
+//! [`codeshare_table`] pairs operating and marketing flights from the DEI 10
+//! and DEI 50 records of a segments `DataFrame`. [`add_operating_flights`]
+//! marks the marketing duplicate legs of a flights `DataFrame` with their
+//! operating flight and can append the marketing legs a carrier did not file;
+//! [`collapse_codeshares`] keeps only the operating legs, so capacity is
+//! counted once per flown leg:
+//!
+//! ```no_run
+//! use rustyssim::{collapse_codeshares, ssim_to_dataframes};
+//!
+//! let (_, flights, segments) = ssim_to_dataframes("schedule.ssim", None, None)?;
+//! let flown = collapse_codeshares(&flights, &segments)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! ## Seats
+//!
+//! [`add_seat_columns`] decodes the `aircraft_configuration` of each flight
+//! leg, e.g. `J30W24Y250`, into a `cabin_seats` list, the `seats_f`,
+//! `seats_c`, `seats_w` and `seats_y` totals of each [`Cabin`] and
+//! `total_seats`. Legs that only name an aircraft version, e.g. `VV738H189`,
+//! get their seats from an [`AircraftVersions`] table read from a CSV file:
+//!
+//! ```no_run
+//! use rustyssim::{AircraftVersions, add_seat_columns, ssim_to_dataframes};
+//!
+//! let (_, flights, _) = ssim_to_dataframes("schedule.ssim", None, None)?;
+//! let versions = AircraftVersions::from_csv("versions.csv")?;
+//! let seats = add_seat_columns(&flights, Some(&versions))?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! ## Dated Flights
+//!
+//! A flight leg record describes a pattern of dates. [`expand_to_dated_flights`]
+//! repeats each leg of a flights `DataFrame` for every date of its period of
+//! operation on its days of operation, every other week for a `frequency_rate`
+//! of `2`, and adds `flight_date`, `departure_date` and `arrival_date` columns.
+//! An optional window bounds the flight dates and is required for periods
+//! without an end date:
+//!
+//! ```no_run
+//! use rustyssim::chrono::NaiveDate;
+//! use rustyssim::{expand_to_dated_flights, ssim_to_dataframes};
+//!
+//! let (_, flights, _) = ssim_to_dataframes("schedule.ssim", None, None)?;
+//! let week = expand_to_dated_flights(
+//!     &flights,
+//!     NaiveDate::from_ymd_opt(2025, 3, 30),
+//!     NaiveDate::from_ymd_opt(2025, 4, 5),
+//! )?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [`add_leg_times`] then turns the scheduled times, UTC time variations and
+//! date variation of each leg into local and UTC departure and arrival
+//! timestamps and a block time in minutes, flagging negative or implausibly
+//! long block times.
+//!
+//! ## Errors
+//!
+//! All functions return [`SsimResult`]. Malformed input (truncated records,
+//! unknown record types, invalid UTF-8, flights before any carrier record)
+//! is reported as an [`SsimError`] whose [`RecordLocation`] points at the
+//! offending line instead of panicking.
+//!
 //! ## Performance Tuning
 //!
 //! - **`batch_size`**: Number of records processed per batch. Larger values
 //!   use more memory but reduce overhead. Default: `10,000`.
 //! - **`buffer_size`**: File read buffer in bytes. Increase for large files.
 //!   Default: `8,192` (8 KB). Try `131,072` (128 KB) for large files.
+//! - **`chunk_size`** (on [`SsimOptions`]): memory-map uncompressed files and
+//!   parse chunks of this many bytes on all cores with [`ChunkedSsimReader`].
+//!   The output is the same as the sequential reader's. Default: off.
 
 // Re-export the public API
-pub use rusty_ssim_core::{ssim_to_csv, ssim_to_dataframe, ssim_to_dataframes, ssim_to_parquets};
+pub use rusty_ssim_core::records::{
+    carrier_record::CarrierRecord, flight_leg_records::FlightLegRecord,
+    header_record::HeaderRecord, segment_records::SegmentRecords, trailer_record::TrailerRecord,
+};
+pub use rusty_ssim_core::{
+    AircraftConfiguration, AircraftVersions, Cabin, ChunkedSsimReader, ColumnSelection,
+    Compression, CsvOptions, DEI_REGISTRY, DaysOfWeek, DeiInfo, DeiLevel, DeiValue, Diagnostics,
+    FieldError, FlightReference, Issue, IssueKind, MealService, ParquetLayout, ParseMode,
+    PartitionKey, QuoteStyle, RecordLocation, SsimError, SsimFileMetadata, SsimFilter, SsimOptions,
+    SsimReader, SsimRecord, SsimRecordIter, SsimResult, TrafficType, TypedFlightLeg, WriteMode,
+    add_dei_columns, add_leg_times, add_operating_flights, add_seat_columns, codeshare_table,
+    collapse_codeshares, decode_dei, dei_info, expand_to_dated_flights, read_parquet_metadata,
+    ssim_to_csv, ssim_to_csv_from_reader, ssim_to_csv_with_options, ssim_to_dataframe,
+    ssim_to_dataframe_from_reader, ssim_to_dataframe_with_options, ssim_to_dataframes,
+    ssim_to_dataframes_from_reader, ssim_to_dataframes_with_options, ssim_to_dated_flights,
+    ssim_to_dated_flights_from_reader, ssim_to_dated_flights_with_options, ssim_to_ipc,
+    ssim_to_ipc_from_reader, ssim_to_ipc_with_options, ssim_to_ndjson, ssim_to_ndjson_from_reader,
+    ssim_to_ndjson_with_options, ssim_to_parquets, ssim_to_parquets_from_reader,
+    ssim_to_parquets_with_options,
+};
 
 /// Re-exported [Polars](https://pola.rs) crate for working with the DataFrames
 /// returned by this library.
 ///
 /// Includes ["lazy", "parquet", "dtype-struct", "ipc", "performant", "json"] needed by rustyssim.
 /// For additional features, add `polars` directly to your `Cargo.toml` —
-pub use polars;
\ No newline at end of file
+pub use polars;
+
+/// Re-exported [chrono](https://docs.rs/chrono) crate for the dates and weekdays
+/// used by [`TypedFlightLeg`] and [`DaysOfWeek`].
+pub use chrono;
\ No newline at end of file
@@ -212,7 +212,7 @@ fn create_temp_ssim_file(flights_count: i16, ivi_count: i8, multi_leg: bool) ->
         let temp_dir = TempDir::new().expect("Failed to create temp directory");
         let file_path = temp_dir.path().join("test.ssim");
         fs::write(&file_path, content).expect("Failed to write SSIM file");
-        return (file_path.to_string_lossy().to_string(), temp_dir);
+        (file_path.to_string_lossy().to_string(), temp_dir)
     } else {
         let content = ssim_file_generator(flights_count, ivi_count);
         let temp_dir = TempDir::new().expect("Failed to create temp directory");
@@ -220,7 +220,7 @@ fn create_temp_ssim_file(flights_count: i16, ivi_count: i8, multi_leg: bool) ->
 
         fs::write(&file_path, content).expect("Failed to write SSIM file");
 
-        return (file_path.to_string_lossy().to_string(), temp_dir);
+        (file_path.to_string_lossy().to_string(), temp_dir)
     }
 }
 
@@ -268,7 +268,7 @@ mod integration_tests {
             carriers_df.height() > 0,
             "Carriers DataFrame should not be empty"
         );
-        assert_eq!(carriers_df.get_column_names().len(), 16);
+        assert_eq!(carriers_df.get_column_names().len(), 21);
         assert_eq!(carriers_df.height(), 1);
 
         // Check flights DataFrame
@@ -386,7 +386,7 @@ mod integration_tests {
                     .path()
                     .extension()
                     .and_then(|ext| ext.to_str())
-                    .map_or(false, |ext| ext == "parquet")
+                    .is_some_and(|ext| ext == "parquet")
             })
             .collect();
 
@@ -493,7 +493,7 @@ mod integration_tests {
                         .path()
                         .extension()
                         .and_then(|ext| ext.to_str())
-                        .map_or(false, |ext| ext == expected_ext)
+                        .is_some_and(|ext| ext == expected_ext)
                 })
                 .count();
 
@@ -0,0 +1,107 @@
+//! Benchmarks of the DataFrame outputs on the large-file test generator.
+//!
+//! `columnar` is the default path, which builds the columns straight from the
+//! raw lines; `records` parses each batch into record structs first and builds
+//! the same DataFrames from those. Run with `cargo bench -p rusty-ssim-core`.
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use common::ssim_file_generator;
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use polars::prelude::PolarsResult;
+use rusty_ssim_core::utils::ssim_parser::{
+    CarrierRecord, FlightLegRecord, HeaderRecord, SegmentRecords, TrailerRecord,
+};
+use rusty_ssim_core::{
+    BatchProcessor, CombinedDataFrameProcessor, SplitDataFrameProcessor, SsimReader,
+};
+use std::hint::black_box;
+
+/// Hands the processor parsed records instead of raw lines.
+struct ThroughRecords<P>(P);
+
+impl<P: BatchProcessor> BatchProcessor for ThroughRecords<P> {
+    fn process_batch(
+        &mut self,
+        flight_batch: Vec<FlightLegRecord<'_>>,
+        segment_batch: Vec<SegmentRecords<'_>>,
+        carrier: Option<&CarrierRecord>,
+    ) -> PolarsResult<()> {
+        self.0.process_batch(flight_batch, segment_batch, carrier)
+    }
+
+    fn on_header(&mut self, header: &HeaderRecord) -> PolarsResult<()> {
+        self.0.on_header(header)
+    }
+
+    fn on_trailer(
+        &mut self,
+        trailer: &TrailerRecord,
+        carrier: Option<&CarrierRecord>,
+    ) -> PolarsResult<()> {
+        self.0.on_trailer(trailer, carrier)
+    }
+
+    fn on_carrier_complete(&mut self, carrier: Option<&CarrierRecord>) -> PolarsResult<()> {
+        self.0.on_carrier_complete(carrier)
+    }
+
+    fn finalize(&mut self) -> PolarsResult<()> {
+        self.0.finalize()
+    }
+}
+
+fn process<P: BatchProcessor>(data: &[u8], processor: &mut P) {
+    SsimReader::from_reader(data, Some(100_000))
+        .process(processor)
+        .expect("Failed to parse SSIM data");
+}
+
+fn dataframe_benchmarks(c: &mut Criterion) {
+    // The schedule of `test_large_file_performance`: 300,000 records.
+    let data = ssim_file_generator(5000, 20).into_bytes();
+
+    let mut group = c.benchmark_group("dataframe");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(data.len() as u64));
+
+    group.bench_function("columnar", |b| {
+        b.iter(|| {
+            let mut processor = CombinedDataFrameProcessor::new(false, false);
+            process(&data, &mut processor);
+            black_box(processor.into_result())
+        })
+    });
+    group.bench_function("records", |b| {
+        b.iter(|| {
+            let mut processor = ThroughRecords(CombinedDataFrameProcessor::new(false, false));
+            process(&data, &mut processor);
+            black_box(processor.0.into_result())
+        })
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("dataframes");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(data.len() as u64));
+
+    group.bench_function("columnar", |b| {
+        b.iter(|| {
+            let mut processor = SplitDataFrameProcessor::new();
+            process(&data, &mut processor);
+            black_box(processor.into_result())
+        })
+    });
+    group.bench_function("records", |b| {
+        b.iter(|| {
+            let mut processor = ThroughRecords(SplitDataFrameProcessor::new());
+            process(&data, &mut processor);
+            black_box(processor.0.into_result())
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, dataframe_benchmarks);
+criterion_main!(benches);
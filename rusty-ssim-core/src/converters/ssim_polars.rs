@@ -1,3 +1,8 @@
+use crate::utils::ssim_columns::SEGMENT_DATA_FIELDS;
+use crate::utils::ssim_fields::{
+    DaysOfWeek, FieldError, parse_date, parse_number, parse_time, parse_utc_offset,
+};
+use chrono::NaiveDate;
 use polars::error::PolarsResult;
 use polars::prelude::*;
 
@@ -42,6 +47,7 @@ use polars::prelude::*;
 ///
 /// ```
 fn condense_segments_to_structs(segments: DataFrame) -> PolarsResult<DataFrame> {
+    let fields = present_columns(&segments, SEGMENT_DATA_FIELDS);
     let grouped = segments
         .lazy()
         .group_by([
@@ -49,17 +55,8 @@ fn condense_segments_to_structs(segments: DataFrame) -> PolarsResult<DataFrame>
             col("control_duplicate_indicator"),
             col("leg_sequence_number"),
         ])
-        .agg([
-            as_struct(vec![
-                col("board_point_indicator"),
-                col("off_point_indicator"),
-                col("board_point"),
-                col("off_point"),
-                col("data_element_identifier"),
-                col("data"),
-            ])
-            .alias("segment_data"),
-        ]).with_streaming(true)
+        .agg([as_struct(fields).alias("segment_data")])
+        .with_streaming(true)
         .collect()?;
 
     Ok(grouped)
@@ -72,6 +69,11 @@ fn condense_segments_to_structs(segments: DataFrame) -> PolarsResult<DataFrame>
 /// This is only nneeded if `condense_segments` is true and you want to export to CSV. If exporting to Parquet, you can keep the nested List<Struct> format without serialization.
 /// 
 pub(crate) fn serialize_segment_data_to_json(df: DataFrame) -> PolarsResult<DataFrame> {
+    // Projected out by a column selection.
+    if !df.schema().contains("segment_data") {
+        return Ok(df);
+    }
+
     let mut df = df
         .lazy()
         .with_column(
@@ -126,23 +128,25 @@ pub(crate) fn combine_all_dataframes(
     segments: DataFrame,
     condense_segments: bool,
 ) -> PolarsResult<DataFrame> {
-    let flights_with_carrier = flights
-        .lazy()
-        .drop(cols(["record_type", "record_serial_number"]))
-        .join(
-            carrier
-                .lazy()
-                .drop(cols(["record_type", "record_serial_number"])),
-            [
-                col("airline_designator"),
-                col("control_duplicate_indicator"),
-            ],
-            [
-                col("airline_designator"),
-                col("control_duplicate_indicator"),
-            ],
-            JoinArgs::new(JoinType::Left),
-        );
+    // Projected inputs may lack these columns, so drop them eagerly (missing names are ignored).
+    let record_meta = ["record_type", "record_serial_number"];
+    let flights_with_carrier = flights.drop_many(record_meta).lazy().join(
+        carrier.drop_many(record_meta).lazy(),
+        [
+            col("airline_designator"),
+            col("control_duplicate_indicator"),
+        ],
+        [
+            col("airline_designator"),
+            col("control_duplicate_indicator"),
+        ],
+        JoinArgs::new(JoinType::Left),
+    );
+
+    if condense_segments && present_columns(&segments, SEGMENT_DATA_FIELDS).is_empty() {
+        // No segment field is selected, so there is nothing to nest.
+        return flights_with_carrier.with_streaming(true).collect();
+    }
 
     if condense_segments {
         // Condense segments to List<Struct> per flight
@@ -167,19 +171,15 @@ pub(crate) fn combine_all_dataframes(
             .collect()
     } else {
         // Flat format - each segment is a separate row (original behavior)
+        let mut segment_columns = vec![
+            col("flight_designator"),
+            col("leg_sequence_number"),
+            col("control_duplicate_indicator"),
+        ];
+        segment_columns.extend(present_columns(&segments, SEGMENT_DATA_FIELDS));
         flights_with_carrier
             .join(
-                segments.lazy().select([
-                    col("flight_designator"),
-                    col("leg_sequence_number"),
-                    col("control_duplicate_indicator"),
-                    col("board_point_indicator"),
-                    col("off_point_indicator"),
-                    col("board_point"),
-                    col("off_point"),
-                    col("data_element_identifier"),
-                    col("data"),
-                ]),
+                segments.lazy().select(segment_columns),
                 [
                     col("flight_designator"),
                     col("control_duplicate_indicator"),
@@ -196,3 +196,149 @@ pub(crate) fn combine_all_dataframes(
             .collect()
     }
 }
+
+/// Column expressions for the `names` that `df` has, in `names` order.
+fn present_columns(df: &DataFrame, names: &[&str]) -> Vec<Expr> {
+    names
+        .iter()
+        .filter(|name| df.schema().contains(name))
+        .map(|name| col(*name))
+        .collect()
+}
+
+/// Date columns (`DDMMMYY`), converted to `Date`. `00XXX00` and blanks become null.
+pub(crate) const DATE_COLUMNS: &[&str] = &[
+    "period_of_schedule_validity_from",
+    "period_of_schedule_validity_to",
+    "creation_date",
+    "release_date",
+    "period_of_operation_from",
+    "period_of_operation_to",
+];
+
+/// Local time columns (`HHMM`), converted to a `Duration` since midnight so `2400` fits.
+const TIME_COLUMNS: &[&str] = &[
+    "scheduled_time_of_passenger_departure",
+    "scheduled_time_of_aircraft_departure",
+    "scheduled_time_of_aircraft_arrival",
+    "scheduled_time_of_passenger_arrival",
+];
+
+/// UTC variation columns (`±HHMM`), converted to `Int16` minutes.
+const UTC_OFFSET_COLUMNS: &[&str] = &["time_variation_departure", "time_variation_arrival"];
+
+/// Low-cardinality code columns, converted to `Categorical`.
+const CATEGORICAL_COLUMNS: &[&str] = &[
+    "service_type",
+    "departure_station",
+    "arrival_station",
+    "aircraft_type",
+    "board_point",
+    "off_point",
+];
+
+/// Converts the known SSIM text columns of `df` to native Polars dtypes.
+///
+/// * periods and carrier dates → `Date`
+/// * scheduled times → `Duration(ms)` since local midnight
+/// * UTC time variations → `Int16` minutes
+/// * `leg_sequence_number` → `UInt8`
+/// * `days_of_operation` → `UInt8` bitmask, bit 0 = Monday (see [`DaysOfWeek`])
+/// * stations, aircraft and service types → `Categorical`
+///
+/// Columns that are missing or no longer `String` are left untouched, so the
+/// function can run on carrier, flight, segment and combined DataFrames alike.
+///
+/// # Errors
+/// Returns a `ComputeError` naming the column and value for impossible values
+/// such as `32JAN25` or `2460`.
+pub(crate) fn to_native_dtypes(mut df: DataFrame) -> PolarsResult<DataFrame> {
+    let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).expect("valid date");
+
+    for &name in DATE_COLUMNS {
+        if let Some(ca) = parse_string_column::<Int32Type, _>(&df, name, |field, value| {
+            Ok(parse_date(field, value)?.map(|date| (date - epoch).num_days() as i32))
+        })? {
+            df.replace(name, ca.into_date().into_series().into())?;
+        }
+    }
+    for &name in TIME_COLUMNS {
+        if let Some(ca) = parse_string_column::<Int64Type, _>(&df, name, |field, value| {
+            Ok(Some(parse_time(field, value)? as i64 * 60_000))
+        })? {
+            df.replace(
+                name,
+                ca.into_duration(TimeUnit::Milliseconds)
+                    .into_series()
+                    .into(),
+            )?;
+        }
+    }
+    for &name in UTC_OFFSET_COLUMNS {
+        if let Some(ca) = parse_string_column::<Int16Type, _>(&df, name, |field, value| {
+            parse_utc_offset(field, value).map(Some)
+        })? {
+            df.replace(name, ca.into_series().into())?;
+        }
+    }
+    if let Some(ca) =
+        parse_string_column::<UInt8Type, _>(&df, "leg_sequence_number", |field, value| {
+            parse_number(field, value).map(Some)
+        })?
+    {
+        df.replace("leg_sequence_number", ca.into_series().into())?;
+    }
+    if let Some(ca) =
+        parse_string_column::<UInt8Type, _>(&df, "days_of_operation", |field, value| {
+            Ok(Some(DaysOfWeek::parse(field, value)?.bits()))
+        })?
+    {
+        df.replace("days_of_operation", ca.into_series().into())?;
+    }
+
+    let categorical = DataType::from_categories(Categories::global());
+    for &name in CATEGORICAL_COLUMNS {
+        if let Ok(column) = df.column(name)
+            && column.dtype() == &DataType::String
+        {
+            let converted = column.cast(&categorical)?;
+            df.replace(name, converted)?;
+        }
+    }
+
+    Ok(df)
+}
+
+/// Parses a `String` column value by value; blank and null cells become null.
+///
+/// Returns `None` if the column is absent or not a `String` column.
+fn parse_string_column<T, F>(
+    df: &DataFrame,
+    name: &'static str,
+    parse: F,
+) -> PolarsResult<Option<ChunkedArray<T>>>
+where
+    T: PolarsNumericType,
+    F: Fn(&'static str, &str) -> Result<Option<T::Native>, FieldError>,
+{
+    let Ok(column) = df.column(name) else {
+        return Ok(None);
+    };
+    let Ok(strings) = column.str() else {
+        return Ok(None);
+    };
+
+    let values = strings
+        .iter()
+        .map(|value| match value {
+            Some(value) if !value.trim().is_empty() => parse(name, value),
+            _ => Ok(None),
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+
+    Ok(Some(ChunkedArray::from_iter_options(
+        name.into(),
+        values.into_iter(),
+    )))
+}
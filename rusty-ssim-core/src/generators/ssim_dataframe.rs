@@ -1,4 +1,11 @@
-use crate::utils::ssim_parser::{CarrierRecord, FlightLegRecord, SegmentRecords};
+use crate::utils::ssim_columns::{
+    ColumnSelection, FLIGHT_LEG_COLUMNS, SEGMENT_COLUMNS, SEGMENT_DATA_FIELDS,
+};
+use crate::utils::ssim_parser::{
+    CarrierRecord, FLIGHT_LEG_FIELDS, FlightLegRecord, LineField, SEGMENT_FIELDS, SegmentRecords,
+    push_flight_designator,
+};
+use polars::chunked_array::builder::AnonymousOwnedListBuilder;
 use polars::prelude::*;
 use rayon::prelude::*;
 
@@ -9,37 +16,61 @@ use rayon::prelude::*;
 ///
 /// For fields that are not `AsRef<str>` (e.g. `char`), use the `display` marker:
 /// `"column_name" => display field_name,` which calls `.to_string()` instead.
+///
+/// For a value shared by every record, use the `constant` marker:
+/// `"column_name" => constant value,` which broadcasts the `&str` as a scalar
+/// column instead of copying it per row.
+///
+/// Columns that the [`ColumnSelection`] does not keep are skipped.
 macro_rules! build_dataframe {
-    ($records_expr:expr, $($rest:tt)*) => {{
+    ($records_expr:expr, $columns:expr, $($rest:tt)*) => {{
         let records = $records_expr;
+        let columns: &ColumnSelection = $columns;
         let builders: Vec<Box<dyn Fn() -> Column + Sync + '_>> = Vec::new();
         #[allow(unused_mut)]
         let mut builders = builders;
-        build_dataframe!(@step records, builders, $($rest)*);
+        build_dataframe!(@step records, columns, builders, $($rest)*);
         let columns: Vec<Column> = builders.par_iter().map(|f| f()).collect();
         DataFrame::new_infer_height(columns)
     }};
-    (@step $records:ident, $builders:ident,) => {};
-    (@step $records:ident, $builders:ident, $col:expr => display $field:ident, $($rest:tt)*) => {
-        $builders.push(Box::new(|| Column::new(
-            PlSmallStr::from_static($col),
-            $records.iter().map(|r| r.$field.to_string()).collect::<Vec<_>>(),
-        )));
-        build_dataframe!(@step $records, $builders, $($rest)*);
+    (@step $records:ident, $columns:ident, $builders:ident,) => {};
+    (@step $records:ident, $columns:ident, $builders:ident, $col:expr => display $field:ident, $($rest:tt)*) => {
+        if $columns.keeps($col) {
+            $builders.push(Box::new(|| Column::new(
+                PlSmallStr::from_static($col),
+                $records.iter().map(|r| r.$field.to_string()).collect::<Vec<_>>(),
+            )));
+        }
+        build_dataframe!(@step $records, $columns, $builders, $($rest)*);
+    };
+    (@step $records:ident, $columns:ident, $builders:ident, $col:expr => constant $value:expr, $($rest:tt)*) => {
+        if $columns.keeps($col) {
+            $builders.push(Box::new(|| Column::new_scalar(
+                PlSmallStr::from_static($col),
+                Scalar::from(PlSmallStr::from_str($value)),
+                $records.len(),
+            )));
+        }
+        build_dataframe!(@step $records, $columns, $builders, $($rest)*);
     };
-    (@step $records:ident, $builders:ident, $col:expr => $field:ident, $($rest:tt)*) => {
-        $builders.push(Box::new(|| Column::new(
-            PlSmallStr::from_static($col),
-            $records.iter().map(|r| r.$field.as_ref() as &str).collect::<Vec<_>>(),
-        )));
-        build_dataframe!(@step $records, $builders, $($rest)*);
+    (@step $records:ident, $columns:ident, $builders:ident, $col:expr => $field:ident, $($rest:tt)*) => {
+        if $columns.keeps($col) {
+            $builders.push(Box::new(|| Column::new(
+                PlSmallStr::from_static($col),
+                $records.iter().map(|r| r.$field.as_ref() as &str).collect::<Vec<_>>(),
+            )));
+        }
+        build_dataframe!(@step $records, $columns, $builders, $($rest)*);
     };
 }
 
 /// Build a carrier DataFrame from a carrier record.
-fn build_carrier_dataframe(carrier: Option<&CarrierRecord>) -> PolarsResult<DataFrame> {
+fn build_carrier_dataframe(
+    carrier: Option<&CarrierRecord>,
+    columns: &ColumnSelection,
+) -> PolarsResult<DataFrame> {
     let carriers: Vec<&CarrierRecord> = carrier.into_iter().collect();
-    build_dataframe!(&carriers,
+    build_dataframe!(&carriers, columns,
         "airline_designator" => airline_designator,
         "control_duplicate_indicator" => control_duplicate_indicator,
         "time_mode" => time_mode,
@@ -59,13 +90,17 @@ fn build_carrier_dataframe(carrier: Option<&CarrierRecord>) -> PolarsResult<Data
     )
 }
 
-/// Build a flight DataFrame from flight leg records.
-fn build_flight_dataframe(flights: &[FlightLegRecord<'_>]) -> PolarsResult<DataFrame> {
-    build_dataframe!(flights,
+/// Build a flight DataFrame from the flight leg records of one carrier.
+fn build_flight_dataframe(
+    flights: &[FlightLegRecord<'_>],
+    control_duplicate_indicator: &str,
+    columns: &ColumnSelection,
+) -> PolarsResult<DataFrame> {
+    build_dataframe!(flights, columns,
         "flight_designator" => flight_designator,
         "operational_suffix" => operational_suffix,
         "airline_designator" => airline_designator,
-        "control_duplicate_indicator" => control_duplicate_indicator,
+        "control_duplicate_indicator" => constant control_duplicate_indicator,
         "flight_number" => flight_number,
         "itinerary_variation_identifier" => itinerary_variation_identifier,
         "leg_sequence_number" => leg_sequence_number,
@@ -112,13 +147,17 @@ fn build_flight_dataframe(flights: &[FlightLegRecord<'_>]) -> PolarsResult<DataF
     )
 }
 
-/// Build a segment DataFrame from segment records.
-fn build_segment_dataframe(segments: &[SegmentRecords<'_>]) -> PolarsResult<DataFrame> {
-    build_dataframe!(segments,
+/// Build a segment DataFrame from the segment records of one carrier.
+fn build_segment_dataframe(
+    segments: &[SegmentRecords<'_>],
+    control_duplicate_indicator: &str,
+    columns: &ColumnSelection,
+) -> PolarsResult<DataFrame> {
+    build_dataframe!(segments, columns,
         "flight_designator" => flight_designator,
         "operational_suffix" => operational_suffix,
         "airline_designator" => airline_designator,
-        "control_duplicate_indicator" => control_duplicate_indicator,
+        "control_duplicate_indicator" => constant control_duplicate_indicator,
         "flight_number" => flight_number,
         "itinerary_variation_identifier" => itinerary_variation_identifier,
         "leg_sequence_number" => leg_sequence_number,
@@ -135,21 +174,317 @@ fn build_segment_dataframe(segments: &[SegmentRecords<'_>]) -> PolarsResult<Data
 }
 
 // Converts the provided records into three Polars DataFrames in parallel, returning a tuple of (carrier_df, flight_df, segment_df).
+// Only the columns kept by `columns` are built. The records all belong to `carrier`, whose
+// control duplicate indicator is broadcast to the flight and segment DataFrames.
 pub fn convert_to_dataframes(
     carrier: Option<&CarrierRecord>,
     flights: Vec<FlightLegRecord<'_>>,
     segments: Vec<SegmentRecords<'_>>,
+    columns: &ColumnSelection,
 ) -> PolarsResult<(DataFrame, DataFrame, DataFrame)> {
+    let control_duplicate_indicator =
+        carrier.map_or("", |carrier| carrier.control_duplicate_indicator.as_str());
+
     // Build all three DataFrames in parallel
     let (carrier_df, (flight_df, segment_df)) = rayon::join(
-        || build_carrier_dataframe(carrier),
+        || build_carrier_dataframe(carrier, columns),
+        || {
+            rayon::join(
+                || build_flight_dataframe(&flights, control_duplicate_indicator, columns),
+                || build_segment_dataframe(&segments, control_duplicate_indicator, columns),
+            )
+        },
+    );
+
+    Ok((carrier_df?, flight_df?, segment_df?))
+}
+
+/// Build one column of a flight leg or segment DataFrame straight from the raw lines.
+///
+/// Fields are sliced out of each line into a string view column; only the
+/// flight designator is assembled, in one buffer reused for every line.
+fn build_line_column<L: AsRef<str>>(
+    name: &'static str,
+    field: LineField,
+    lines: &[L],
+    control_duplicate_indicator: &str,
+) -> Column {
+    let name = PlSmallStr::from_static(name);
+    match field {
+        LineField::Raw(start, end) => StringChunked::from_iter_values(
+            name,
+            lines.iter().map(|line| &line.as_ref()[start..end]),
+        )
+        .into_column(),
+        LineField::Trimmed(start, end) => StringChunked::from_iter_values(
+            name,
+            lines.iter().map(|line| line.as_ref()[start..end].trim()),
+        )
+        .into_column(),
+        LineField::FlightDesignator => {
+            let mut builder = StringChunkedBuilder::new(name, lines.len());
+            let mut designator = String::new();
+            for line in lines {
+                designator.clear();
+                push_flight_designator(&mut designator, line.as_ref(), control_duplicate_indicator);
+                builder.append_value(&designator);
+            }
+            builder.finish().into_column()
+        }
+        LineField::ControlDuplicateIndicator => Column::new_scalar(
+            name,
+            Scalar::from(PlSmallStr::from_str(control_duplicate_indicator)),
+            lines.len(),
+        ),
+    }
+}
+
+/// Build a flight leg or segment DataFrame from raw lines, constructing the
+/// kept columns of `fields` in parallel.
+fn build_line_dataframe<L: AsRef<str> + Sync>(
+    lines: &[L],
+    fields: impl Iterator<Item = (&'static str, LineField)>,
+    control_duplicate_indicator: &str,
+    columns: &ColumnSelection,
+) -> PolarsResult<DataFrame> {
+    let kept: Vec<(&'static str, LineField)> =
+        fields.filter(|(name, _)| columns.keeps(name)).collect();
+    let columns: Vec<Column> = kept
+        .par_iter()
+        .map(|&(name, field)| build_line_column(name, field, lines, control_duplicate_indicator))
+        .collect();
+    DataFrame::new_infer_height(columns)
+}
+
+/// Columnar counterpart of [`convert_to_dataframes`]: builds the three DataFrames
+/// straight from validated flight leg and segment lines of `carrier`, without
+/// parsing them into records first.
+pub fn lines_to_dataframes<L: AsRef<str> + Sync>(
+    carrier: Option<&CarrierRecord>,
+    flight_lines: &[L],
+    segment_lines: &[L],
+    columns: &ColumnSelection,
+) -> PolarsResult<(DataFrame, DataFrame, DataFrame)> {
+    // Without a carrier there is nothing to attach the lines to.
+    let (flight_lines, segment_lines) = match carrier {
+        Some(_) => (flight_lines, segment_lines),
+        None => (&[][..], &[][..]),
+    };
+    let control_duplicate_indicator =
+        carrier.map_or("", |carrier| carrier.control_duplicate_indicator.as_str());
+
+    let (carrier_df, (flight_df, segment_df)) = rayon::join(
+        || build_carrier_dataframe(carrier, columns),
         || {
             rayon::join(
-                || build_flight_dataframe(&flights),
-                || build_segment_dataframe(&segments),
+                || {
+                    build_line_dataframe(
+                        flight_lines,
+                        FLIGHT_LEG_COLUMNS.iter().copied().zip(FLIGHT_LEG_FIELDS),
+                        control_duplicate_indicator,
+                        columns,
+                    )
+                },
+                || {
+                    // As in `build_segment_dataframe`, the service type is
+                    // left to the flight legs.
+                    let fields = SEGMENT_COLUMNS
+                        .iter()
+                        .copied()
+                        .zip(SEGMENT_FIELDS)
+                        .filter(|&(name, _)| name != "service_type");
+                    build_line_dataframe(
+                        segment_lines,
+                        fields,
+                        control_duplicate_indicator,
+                        columns,
+                    )
+                },
             )
         },
     );
 
     Ok((carrier_df?, flight_df?, segment_df?))
 }
+
+/// Build the combined DataFrame of a batch straight from its lines, attaching
+/// each segment to the flight leg the reader found for it in `segment_legs`
+/// instead of joining on the flight designator.
+///
+/// Flight legs get the fields of `carrier` when their airline designator is the
+/// carrier's. In the flat layout each segment of a leg gets a row of its own and
+/// a leg without segments one row with null segment fields. With
+/// `condense_segments` the segments of each leg are nested in a `segment_data`
+/// `List<Struct>` column instead, null for a leg without segments. Segments
+/// without a leg are left out.
+pub fn lines_to_combined_dataframe<L: AsRef<str> + Sync>(
+    carrier: Option<&CarrierRecord>,
+    flight_lines: &[L],
+    segment_lines: &[L],
+    segment_legs: &[Option<usize>],
+    columns: &ColumnSelection,
+    condense_segments: bool,
+) -> PolarsResult<DataFrame> {
+    let (carrier_df, flight_df, segment_df) =
+        lines_to_dataframes(carrier, flight_lines, segment_lines, columns)?;
+    let record_meta = ["record_type", "record_serial_number"];
+    let flight_df = flight_df.drop_many(record_meta);
+    let carrier_df = carrier_df.drop_many(
+        record_meta
+            .into_iter()
+            .chain(["airline_designator", "control_duplicate_indicator"]),
+    );
+    let segment_fields: Vec<&str> = SEGMENT_DATA_FIELDS
+        .iter()
+        .copied()
+        .filter(|name| segment_df.schema().contains(name))
+        .collect();
+    let segment_df = segment_df.select(segment_fields)?;
+
+    // Without a carrier there are no flight legs to attach segments to.
+    let (flight_lines, segment_legs) = match carrier {
+        Some(_) => (flight_lines, segment_legs),
+        None => (&[][..], &[][..]),
+    };
+    let legs = SegmentsByLeg::new(flight_lines.len(), segment_legs);
+
+    if condense_segments {
+        let segment_data = if segment_df.width() == 0 {
+            // No segment field is selected, so there is nothing to nest.
+            None
+        } else {
+            Some(legs.nest(&segment_df)?)
+        };
+        let leg_rows: Vec<usize> = (0..flight_lines.len()).collect();
+        let mut combined = flight_df;
+        combined.hstack_mut(&carrier_columns(
+            &carrier_df,
+            carrier,
+            flight_lines,
+            &leg_rows,
+        )?)?;
+        if let Some(segment_data) = segment_data {
+            combined.with_column(segment_data)?;
+        }
+        return Ok(combined);
+    }
+
+    // One row per segment of each leg, or per leg without segments.
+    let mut leg_rows = Vec::with_capacity(flight_lines.len() + segment_legs.len());
+    let mut segment_rows = Vec::with_capacity(leg_rows.capacity());
+    for leg in 0..flight_lines.len() {
+        let segments = legs.segments(leg);
+        if segments.is_empty() {
+            leg_rows.push(leg);
+            segment_rows.push(None);
+        }
+        for &segment in segments {
+            leg_rows.push(leg);
+            segment_rows.push(Some(segment as usize));
+        }
+    }
+
+    let mut combined = flight_df.take(&to_indices(leg_rows.iter().map(|&leg| Some(leg))))?;
+    combined.hstack_mut(&carrier_columns(
+        &carrier_df,
+        carrier,
+        flight_lines,
+        &leg_rows,
+    )?)?;
+    combined.hstack_mut(
+        segment_df
+            .take(&to_indices(segment_rows.into_iter()))?
+            .columns(),
+    )?;
+    Ok(combined)
+}
+
+/// The segments of each flight leg of a batch, in file order.
+struct SegmentsByLeg {
+    /// The segments of leg `i` are `segments[starts[i]..starts[i + 1]]`.
+    starts: Vec<usize>,
+    segments: Vec<IdxSize>,
+}
+
+impl SegmentsByLeg {
+    fn new(legs: usize, segment_legs: &[Option<usize>]) -> Self {
+        let mut starts = vec![0; legs + 1];
+        for &leg in segment_legs.iter().flatten() {
+            starts[leg + 1] += 1;
+        }
+        for leg in 0..legs {
+            starts[leg + 1] += starts[leg];
+        }
+        let mut next = starts.clone();
+        let mut segments = vec![0; starts[legs]];
+        for (segment, &leg) in segment_legs.iter().enumerate() {
+            if let Some(leg) = leg {
+                segments[next[leg]] = segment as IdxSize;
+                next[leg] += 1;
+            }
+        }
+        Self { starts, segments }
+    }
+
+    fn segments(&self, leg: usize) -> &[IdxSize] {
+        &self.segments[self.starts[leg]..self.starts[leg + 1]]
+    }
+
+    /// Nest the rows of `segment_df` into a `segment_data` list per leg.
+    fn nest(&self, segment_df: &DataFrame) -> PolarsResult<Column> {
+        let name = PlSmallStr::from_static("segment_data");
+        let grouped = segment_df
+            .take(&IdxCa::from_vec(PlSmallStr::EMPTY, self.segments.clone()))?
+            .into_struct(name.clone())
+            .into_series();
+        let mut builder = AnonymousOwnedListBuilder::new(
+            name,
+            self.starts.len() - 1,
+            Some(grouped.dtype().clone()),
+        );
+        for bounds in self.starts.windows(2) {
+            let (start, end) = (bounds[0], bounds[1]);
+            if start == end {
+                builder.append_null();
+            } else {
+                builder.append_series(&grouped.slice(start as i64, end - start))?;
+            }
+        }
+        Ok(builder.finish().into_column())
+    }
+}
+
+fn to_indices(rows: impl Iterator<Item = Option<usize>>) -> IdxCa {
+    IdxCa::from_iter_options(
+        PlSmallStr::EMPTY,
+        rows.map(|row| row.map(|row| row as IdxSize)),
+    )
+}
+
+/// The carrier columns of each row of flight leg `leg_rows`, null for legs of
+/// another airline than the carrier's.
+fn carrier_columns<L: AsRef<str>>(
+    carrier_df: &DataFrame,
+    carrier: Option<&CarrierRecord>,
+    flight_lines: &[L],
+    leg_rows: &[usize],
+) -> PolarsResult<Vec<Column>> {
+    let airline = carrier.map_or("", |carrier| carrier.airline_designator.as_str());
+    let matches: Vec<bool> = flight_lines
+        .iter()
+        .map(|line| &line.as_ref()[2..5] == airline)
+        .collect();
+    if carrier_df.height() == 1 && matches.iter().all(|&m| m) {
+        return Ok(carrier_df
+            .columns()
+            .iter()
+            .map(|column| column.new_from_index(0, leg_rows.len()))
+            .collect());
+    }
+    let rows = to_indices(leg_rows.iter().map(|&leg| matches[leg].then_some(0)));
+    carrier_df
+        .columns()
+        .iter()
+        .map(|column| column.take(&rows))
+        .collect()
+}
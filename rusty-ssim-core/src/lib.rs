@@ -10,7 +10,11 @@
 pub mod records {
     pub mod carrier_record;
     pub mod flight_leg_records;
+    pub mod header_record;
     pub mod segment_records;
+    pub mod ssim_record;
+    pub mod trailer_record;
+    pub mod typed_flight_leg;
 }
 
 mod generators {
@@ -22,23 +26,88 @@ mod converters {
 }
 
 // Public API from unified reader
-pub use crate::utils::ssim_exporters::to_parquet;
+pub use crate::records::ssim_record::SsimRecord;
+pub use crate::records::typed_flight_leg::TypedFlightLeg;
+pub use crate::utils::ssim_batch::{BatchFrames, BatchLayout, LineBatch};
+pub use crate::utils::ssim_chunked::ChunkedSsimReader;
+pub use crate::utils::ssim_codeshare::{
+    add_operating_flights, codeshare_table, collapse_codeshares,
+};
+pub use crate::utils::ssim_columns::ColumnSelection;
+pub use crate::utils::ssim_csv::{
+    CsvOptions, QuoteStyle, WriteMode, parse_quote_style, parse_separator,
+};
+pub use crate::utils::ssim_dei::{
+    DEI_REGISTRY, DeiInfo, DeiLevel, DeiValue, FlightReference, MealService, TrafficType,
+    add_dei_columns, decode_dei, dei_info,
+};
+pub use crate::utils::ssim_diagnostics::{Diagnostics, Issue, IssueKind, ParseMode};
+pub use crate::utils::ssim_error::{RecordLocation, SsimError, SsimResult};
+pub use crate::utils::ssim_expand::expand_to_dated_flights;
+pub use crate::utils::ssim_exporters::{to_csv, to_parquet, to_parquet_with_metadata};
+pub use crate::utils::ssim_fields::{DaysOfWeek, FieldError};
+pub use crate::utils::ssim_filter::SsimFilter;
+pub use crate::utils::ssim_layout::{ParquetLayout, PartitionKey};
+pub use crate::utils::ssim_metadata::{SsimFileMetadata, read_parquet_metadata};
+pub use crate::utils::ssim_seats::{
+    AircraftConfiguration, AircraftVersions, CABIN_SEATS_COLUMN, Cabin, SEAT_INPUT_COLUMNS,
+    TOTAL_SEATS_COLUMN, add_seat_columns,
+};
+pub use crate::utils::ssim_source::{Compression, FileSource, LineSource};
+pub use crate::utils::ssim_times::add_leg_times;
 pub use crate::utils::ssim_reader::{
     // Export types for custom processors
     BatchProcessor,
     CombinedDataFrameProcessor,
     CsvWriterProcessor,
+    IpcWriterProcessor,
+    NdjsonWriterProcessor,
     ParquetWriterProcessor,
     SplitDataFrameProcessor,
+    SsimOptions,
     SsimReader,
+    SsimRecordIter,
     ssim_to_csv,
+    ssim_to_csv_from_reader,
+    ssim_to_csv_with_options,
     ssim_to_dataframe,
+    ssim_to_dataframe_from_reader,
+    ssim_to_dataframe_with_options,
     ssim_to_dataframes,
+    ssim_to_dataframes_from_reader,
+    ssim_to_dataframes_with_options,
+    ssim_to_dated_flights,
+    ssim_to_dated_flights_from_reader,
+    ssim_to_dated_flights_with_options,
+    ssim_to_ipc,
+    ssim_to_ipc_from_reader,
+    ssim_to_ipc_with_options,
+    ssim_to_ndjson,
+    ssim_to_ndjson_from_reader,
+    ssim_to_ndjson_with_options,
     ssim_to_parquets,
+    ssim_to_parquets_from_reader,
+    ssim_to_parquets_with_options,
 };
 
 pub mod utils {
+    pub mod ssim_batch;
+    pub mod ssim_chunked;
+    pub mod ssim_codeshare;
+    pub mod ssim_columns;
+    pub mod ssim_csv;
+    pub mod ssim_dei;
+    pub mod ssim_diagnostics;
+    pub mod ssim_error;
+    pub mod ssim_expand;
     pub mod ssim_exporters;
+    pub mod ssim_fields;
+    pub mod ssim_filter;
+    pub mod ssim_layout;
+    pub mod ssim_metadata;
     pub mod ssim_parser;
     pub mod ssim_reader;
+    pub mod ssim_seats;
+    pub mod ssim_source;
+    pub mod ssim_times;
 }
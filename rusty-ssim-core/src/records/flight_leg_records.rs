@@ -51,3 +51,87 @@ pub struct FlightLegRecord<'a> {
     pub record_type: char,
     pub record_serial_number: Cow<'a, str>,
 }
+
+impl FlightLegRecord<'_> {
+    /// Detach the record from the line it was parsed from.
+    pub fn into_owned(self) -> FlightLegRecord<'static> {
+        FlightLegRecord {
+            flight_designator: Cow::Owned(self.flight_designator.into_owned()),
+            operational_suffix: Cow::Owned(self.operational_suffix.into_owned()),
+            airline_designator: Cow::Owned(self.airline_designator.into_owned()),
+            control_duplicate_indicator: Cow::Owned(self.control_duplicate_indicator.into_owned()),
+            flight_number: Cow::Owned(self.flight_number.into_owned()),
+            itinerary_variation_identifier: Cow::Owned(
+                self.itinerary_variation_identifier.into_owned(),
+            ),
+            leg_sequence_number: Cow::Owned(self.leg_sequence_number.into_owned()),
+            service_type: Cow::Owned(self.service_type.into_owned()),
+            period_of_operation_from: Cow::Owned(self.period_of_operation_from.into_owned()),
+            period_of_operation_to: Cow::Owned(self.period_of_operation_to.into_owned()),
+            days_of_operation: Cow::Owned(self.days_of_operation.into_owned()),
+            frequency_rate: Cow::Owned(self.frequency_rate.into_owned()),
+            departure_station: Cow::Owned(self.departure_station.into_owned()),
+            scheduled_time_of_passenger_departure: Cow::Owned(
+                self.scheduled_time_of_passenger_departure.into_owned(),
+            ),
+            scheduled_time_of_aircraft_departure: Cow::Owned(
+                self.scheduled_time_of_aircraft_departure.into_owned(),
+            ),
+            time_variation_departure: Cow::Owned(self.time_variation_departure.into_owned()),
+            passenger_terminal_departure: Cow::Owned(
+                self.passenger_terminal_departure.into_owned(),
+            ),
+            arrival_station: Cow::Owned(self.arrival_station.into_owned()),
+            scheduled_time_of_aircraft_arrival: Cow::Owned(
+                self.scheduled_time_of_aircraft_arrival.into_owned(),
+            ),
+            scheduled_time_of_passenger_arrival: Cow::Owned(
+                self.scheduled_time_of_passenger_arrival.into_owned(),
+            ),
+            time_variation_arrival: Cow::Owned(self.time_variation_arrival.into_owned()),
+            passenger_terminal_arrival: Cow::Owned(self.passenger_terminal_arrival.into_owned()),
+            aircraft_type: Cow::Owned(self.aircraft_type.into_owned()),
+            passenger_reservations_booking_designator: Cow::Owned(
+                self.passenger_reservations_booking_designator.into_owned(),
+            ),
+            passenger_reservations_booking_modifier: Cow::Owned(
+                self.passenger_reservations_booking_modifier.into_owned(),
+            ),
+            meal_service_note: Cow::Owned(self.meal_service_note.into_owned()),
+            joint_operation_airline_designators: Cow::Owned(
+                self.joint_operation_airline_designators.into_owned(),
+            ),
+            min_connecting_time_status_departure: Cow::Owned(
+                self.min_connecting_time_status_departure.into_owned(),
+            ),
+            min_connecting_time_status_arrival: Cow::Owned(
+                self.min_connecting_time_status_arrival.into_owned(),
+            ),
+            secure_flight_indicator: Cow::Owned(self.secure_flight_indicator.into_owned()),
+            itinerary_variation_identifier_overflow: Cow::Owned(
+                self.itinerary_variation_identifier_overflow.into_owned(),
+            ),
+            aircraft_owner: Cow::Owned(self.aircraft_owner.into_owned()),
+            cockpit_crew_employer: Cow::Owned(self.cockpit_crew_employer.into_owned()),
+            cabin_crew_employer: Cow::Owned(self.cabin_crew_employer.into_owned()),
+            onward_flight: Cow::Owned(self.onward_flight.into_owned()),
+            airline_designator2: Cow::Owned(self.airline_designator2.into_owned()),
+            flight_number2: Cow::Owned(self.flight_number2.into_owned()),
+            aircraft_rotation_layover: Cow::Owned(self.aircraft_rotation_layover.into_owned()),
+            operational_suffix2: Cow::Owned(self.operational_suffix2.into_owned()),
+            flight_transit_layover: Cow::Owned(self.flight_transit_layover.into_owned()),
+            operating_airline_disclosure: Cow::Owned(
+                self.operating_airline_disclosure.into_owned(),
+            ),
+            traffic_restriction_code: Cow::Owned(self.traffic_restriction_code.into_owned()),
+            traffic_restriction_code_leg_overflow_indicator: Cow::Owned(
+                self.traffic_restriction_code_leg_overflow_indicator
+                    .into_owned(),
+            ),
+            aircraft_configuration: Cow::Owned(self.aircraft_configuration.into_owned()),
+            date_variation: Cow::Owned(self.date_variation.into_owned()),
+            record_serial_number: Cow::Owned(self.record_serial_number.into_owned()),
+            record_type: self.record_type,
+        }
+    }
+}
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HeaderRecord {
+    pub title_of_contents: String,
+    pub number_of_seasons: String,
+    pub data_set_serial_number: String,
+    pub record_type: char,
+    pub record_serial_number: String,
+}
@@ -21,3 +21,32 @@ pub struct SegmentRecords<'a> {
     pub record_type: char,
     pub record_serial_number: Cow<'a, str>,
 }
+
+impl SegmentRecords<'_> {
+    /// Detach the record from the line it was parsed from.
+    pub fn into_owned(self) -> SegmentRecords<'static> {
+        SegmentRecords {
+            flight_designator: Cow::Owned(self.flight_designator.into_owned()),
+            operational_suffix: Cow::Owned(self.operational_suffix.into_owned()),
+            airline_designator: Cow::Owned(self.airline_designator.into_owned()),
+            control_duplicate_indicator: Cow::Owned(self.control_duplicate_indicator.into_owned()),
+            flight_number: Cow::Owned(self.flight_number.into_owned()),
+            itinerary_variation_identifier: Cow::Owned(
+                self.itinerary_variation_identifier.into_owned(),
+            ),
+            leg_sequence_number: Cow::Owned(self.leg_sequence_number.into_owned()),
+            service_type: Cow::Owned(self.service_type.into_owned()),
+            itinerary_variation_identifier_overflow: Cow::Owned(
+                self.itinerary_variation_identifier_overflow.into_owned(),
+            ),
+            board_point_indicator: Cow::Owned(self.board_point_indicator.into_owned()),
+            off_point_indicator: Cow::Owned(self.off_point_indicator.into_owned()),
+            data_element_identifier: Cow::Owned(self.data_element_identifier.into_owned()),
+            board_point: Cow::Owned(self.board_point.into_owned()),
+            off_point: Cow::Owned(self.off_point.into_owned()),
+            data: Cow::Owned(self.data.into_owned()),
+            record_serial_number: Cow::Owned(self.record_serial_number.into_owned()),
+            record_type: self.record_type,
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use crate::records::carrier_record::CarrierRecord;
+use crate::records::flight_leg_records::FlightLegRecord;
+use crate::records::header_record::HeaderRecord;
+use crate::records::segment_records::SegmentRecords;
+use crate::records::trailer_record::TrailerRecord;
+
+/// A single parsed SSIM record, tagged with the 1-based line it was read from.
+///
+/// Yielded by [`SsimReader::records`](crate::SsimReader::records). The larger
+/// records are boxed to keep the enum small.
+#[derive(Debug)]
+pub enum SsimRecord {
+    Header {
+        line_number: usize,
+        record: HeaderRecord,
+    },
+    Carrier {
+        line_number: usize,
+        record: Box<CarrierRecord>,
+    },
+    FlightLeg {
+        line_number: usize,
+        record: Box<FlightLegRecord<'static>>,
+    },
+    Segment {
+        line_number: usize,
+        record: Box<SegmentRecords<'static>>,
+    },
+    Trailer {
+        line_number: usize,
+        record: TrailerRecord,
+    },
+}
+
+impl SsimRecord {
+    /// 1-based line number of the record in the input.
+    pub fn line_number(&self) -> usize {
+        match self {
+            SsimRecord::Header { line_number, .. }
+            | SsimRecord::Carrier { line_number, .. }
+            | SsimRecord::FlightLeg { line_number, .. }
+            | SsimRecord::Segment { line_number, .. }
+            | SsimRecord::Trailer { line_number, .. } => *line_number,
+        }
+    }
+
+    /// The SSIM record type digit (`'1'` to `'5'`).
+    pub fn record_type(&self) -> char {
+        match self {
+            SsimRecord::Header { .. } => '1',
+            SsimRecord::Carrier { .. } => '2',
+            SsimRecord::FlightLeg { .. } => '3',
+            SsimRecord::Segment { .. } => '4',
+            SsimRecord::Trailer { .. } => '5',
+        }
+    }
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrailerRecord {
+    pub airline_designator: String,
+    pub release_date: String,
+    pub serial_number_check_reference: String,
+    pub continuation_end_code: String,
+    pub record_type: char,
+    pub record_serial_number: String,
+}
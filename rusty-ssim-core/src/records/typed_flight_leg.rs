@@ -0,0 +1,178 @@
+use crate::records::flight_leg_records::FlightLegRecord;
+use crate::utils::ssim_fields::{
+    DaysOfWeek, FieldError, optional_text, parse_date, parse_date_variation, parse_number,
+    parse_time, parse_utc_offset,
+};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// A flight leg record with its fields converted to typed values.
+///
+/// Times are minutes since local midnight, UTC offsets are signed minutes and
+/// blank optional fields are `None`. Built from a [`FlightLegRecord`] with
+/// `TypedFlightLeg::try_from`, which fails on the first impossible value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypedFlightLeg {
+    pub flight_designator: String,
+    pub operational_suffix: Option<String>,
+    pub airline_designator: String,
+    pub control_duplicate_indicator: Option<String>,
+    pub flight_number: u16,
+    pub itinerary_variation_identifier: u16,
+    pub leg_sequence_number: u8,
+    pub service_type: String,
+    pub period_of_operation_from: NaiveDate,
+    /// `None` when the period runs until further notice (`00XXX00`).
+    pub period_of_operation_to: Option<NaiveDate>,
+    pub days_of_operation: DaysOfWeek,
+    /// `Some(2)` for flights operating every other week.
+    pub frequency_rate: Option<u8>,
+    pub departure_station: String,
+    pub scheduled_time_of_passenger_departure: u16,
+    pub scheduled_time_of_aircraft_departure: u16,
+    pub time_variation_departure: i16,
+    pub passenger_terminal_departure: Option<String>,
+    pub arrival_station: String,
+    pub scheduled_time_of_aircraft_arrival: u16,
+    pub scheduled_time_of_passenger_arrival: u16,
+    pub time_variation_arrival: i16,
+    pub passenger_terminal_arrival: Option<String>,
+    pub aircraft_type: String,
+    pub passenger_reservations_booking_designator: Option<String>,
+    pub meal_service_note: Option<String>,
+    pub joint_operation_airline_designators: Option<String>,
+    pub aircraft_owner: Option<String>,
+    pub cockpit_crew_employer: Option<String>,
+    pub cabin_crew_employer: Option<String>,
+    pub onward_flight: Option<String>,
+    pub traffic_restriction_code: Option<String>,
+    pub aircraft_configuration: Option<String>,
+    /// Days between the period date and the departure (`-1` for the day before).
+    pub date_variation_departure: i8,
+    /// Days between the period date and the arrival (`-1` for the day before).
+    pub date_variation_arrival: i8,
+    pub record_serial_number: u32,
+}
+
+impl TryFrom<&FlightLegRecord<'_>> for TypedFlightLeg {
+    type Error = FieldError;
+
+    fn try_from(record: &FlightLegRecord<'_>) -> Result<Self, Self::Error> {
+        let period_of_operation_from =
+            parse_date("period_of_operation_from", &record.period_of_operation_from)?.ok_or_else(
+                || FieldError {
+                    field: "period_of_operation_from",
+                    value: record.period_of_operation_from.to_string(),
+                    reason: "the period must have a start date",
+                },
+            )?;
+        let period_of_operation_to =
+            parse_date("period_of_operation_to", &record.period_of_operation_to)?;
+        if period_of_operation_to.is_some_and(|to| to < period_of_operation_from) {
+            return Err(FieldError {
+                field: "period_of_operation_to",
+                value: record.period_of_operation_to.to_string(),
+                reason: "the period ends before it starts",
+            });
+        }
+
+        let leg_sequence_number: u8 =
+            parse_number("leg_sequence_number", &record.leg_sequence_number)?;
+        if leg_sequence_number == 0 {
+            return Err(FieldError {
+                field: "leg_sequence_number",
+                value: record.leg_sequence_number.to_string(),
+                reason: "leg sequence numbers start at 1",
+            });
+        }
+
+        // The overflow character holds the hundreds digit of the variation identifier.
+        let ivi: u16 = parse_number(
+            "itinerary_variation_identifier",
+            &record.itinerary_variation_identifier,
+        )?;
+        let ivi_overflow: u16 = match record.itinerary_variation_identifier_overflow.trim() {
+            "" => 0,
+            overflow => parse_number("itinerary_variation_identifier_overflow", overflow)?,
+        };
+
+        let frequency_rate = match record.frequency_rate.trim() {
+            "" => None,
+            rate => Some(parse_number("frequency_rate", rate)?),
+        };
+
+        let date_variation = record.date_variation.as_ref();
+        let (variation_departure, variation_arrival) = if date_variation.len() == 2 {
+            date_variation.split_at(1)
+        } else {
+            return Err(FieldError {
+                field: "date_variation",
+                value: date_variation.to_string(),
+                reason: "expected 2 characters",
+            });
+        };
+
+        Ok(TypedFlightLeg {
+            flight_designator: record.flight_designator.to_string(),
+            operational_suffix: optional_text(&record.operational_suffix),
+            airline_designator: record.airline_designator.trim().to_string(),
+            control_duplicate_indicator: optional_text(&record.control_duplicate_indicator),
+            flight_number: parse_number("flight_number", &record.flight_number)?,
+            itinerary_variation_identifier: ivi_overflow * 100 + ivi,
+            leg_sequence_number,
+            service_type: record.service_type.to_string(),
+            period_of_operation_from,
+            period_of_operation_to,
+            days_of_operation: DaysOfWeek::parse("days_of_operation", &record.days_of_operation)?,
+            frequency_rate,
+            departure_station: record.departure_station.to_string(),
+            scheduled_time_of_passenger_departure: parse_time(
+                "scheduled_time_of_passenger_departure",
+                &record.scheduled_time_of_passenger_departure,
+            )?,
+            scheduled_time_of_aircraft_departure: parse_time(
+                "scheduled_time_of_aircraft_departure",
+                &record.scheduled_time_of_aircraft_departure,
+            )?,
+            time_variation_departure: parse_utc_offset(
+                "time_variation_departure",
+                &record.time_variation_departure,
+            )?,
+            passenger_terminal_departure: optional_text(&record.passenger_terminal_departure),
+            arrival_station: record.arrival_station.to_string(),
+            scheduled_time_of_aircraft_arrival: parse_time(
+                "scheduled_time_of_aircraft_arrival",
+                &record.scheduled_time_of_aircraft_arrival,
+            )?,
+            scheduled_time_of_passenger_arrival: parse_time(
+                "scheduled_time_of_passenger_arrival",
+                &record.scheduled_time_of_passenger_arrival,
+            )?,
+            time_variation_arrival: parse_utc_offset(
+                "time_variation_arrival",
+                &record.time_variation_arrival,
+            )?,
+            passenger_terminal_arrival: optional_text(&record.passenger_terminal_arrival),
+            aircraft_type: record.aircraft_type.to_string(),
+            passenger_reservations_booking_designator: optional_text(
+                &record.passenger_reservations_booking_designator,
+            ),
+            meal_service_note: optional_text(&record.meal_service_note),
+            joint_operation_airline_designators: optional_text(
+                &record.joint_operation_airline_designators,
+            ),
+            aircraft_owner: optional_text(&record.aircraft_owner),
+            cockpit_crew_employer: optional_text(&record.cockpit_crew_employer),
+            cabin_crew_employer: optional_text(&record.cabin_crew_employer),
+            onward_flight: optional_text(&record.onward_flight),
+            traffic_restriction_code: optional_text(&record.traffic_restriction_code),
+            aircraft_configuration: optional_text(&record.aircraft_configuration),
+            date_variation_departure: parse_date_variation("date_variation", variation_departure)?,
+            date_variation_arrival: parse_date_variation("date_variation", variation_arrival)?,
+            record_serial_number: parse_number(
+                "record_serial_number",
+                &record.record_serial_number,
+            )?,
+        })
+    }
+}
@@ -0,0 +1,246 @@
+//! The batches of lines the reader hands to processors.
+//!
+//! [`SsimReader`](crate::SsimReader) collects the validated flight leg and
+//! segment lines of a carrier into a [`LineBatch`], which a
+//! [`BatchProcessor`] parses into records or builds into the DataFrames of its
+//! [`BatchLayout`]. [`ChunkedSsimReader`](crate::ChunkedSsimReader) builds those DataFrames on the
+//! threads that read its chunks.
+
+use crate::generators::ssim_dataframe::{lines_to_combined_dataframe, lines_to_dataframes};
+use crate::records::carrier_record::CarrierRecord;
+use crate::records::flight_leg_records::FlightLegRecord;
+use crate::records::header_record::HeaderRecord;
+use crate::records::segment_records::SegmentRecords;
+use crate::records::trailer_record::TrailerRecord;
+use crate::utils::ssim_columns::ColumnSelection;
+use crate::utils::ssim_parser::{FlightDesignators, parse_flight_leg, parse_segment};
+use crate::utils::ssim_reader::BatchProcessor;
+use polars::prelude::*;
+use rayon::prelude::*;
+use std::borrow::Cow;
+
+/// The DataFrames a processor builds from each batch, see [`BatchProcessor::batch_layout`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchLayout {
+    /// The carrier, flight leg and segment DataFrames of [`LineBatch::to_dataframes`].
+    Split,
+    /// The combined DataFrame of [`LineBatch::to_combined_dataframe`].
+    Combined { condense_segments: bool },
+}
+
+/// The DataFrames of one batch, built in a [`BatchLayout`].
+#[derive(Debug, Clone)]
+pub enum BatchFrames {
+    Split(DataFrame, DataFrame, DataFrame),
+    Combined(DataFrame),
+}
+
+impl BatchFrames {
+    /// The carrier, flight leg and segment DataFrames of a [`BatchLayout::Split`] batch.
+    pub fn into_split(self) -> PolarsResult<(DataFrame, DataFrame, DataFrame)> {
+        match self {
+            BatchFrames::Split(carriers, flights, segments) => Ok((carriers, flights, segments)),
+            BatchFrames::Combined(_) => Err(PolarsError::ComputeError(
+                "expected split batch DataFrames, got a combined one".into(),
+            )),
+        }
+    }
+
+    /// The combined DataFrame of a [`BatchLayout::Combined`] batch.
+    pub fn into_combined(self) -> PolarsResult<DataFrame> {
+        match self {
+            BatchFrames::Combined(combined) => Ok(combined),
+            BatchFrames::Split(..) => Err(PolarsError::ComputeError(
+                "expected a combined batch DataFrame, got split ones".into(),
+            )),
+        }
+    }
+}
+
+/// Where [`SsimReader`](crate::SsimReader) hands the records it reads: a
+/// [`BatchProcessor`], or the recorder of a chunk of
+/// [`ChunkedSsimReader`](crate::ChunkedSsimReader).
+pub(crate) trait RecordSink<L> {
+    fn lines(&mut self, batch: LineBatch<'_, L>) -> PolarsResult<()>;
+
+    fn header(&mut self, header: &HeaderRecord) -> PolarsResult<()>;
+
+    fn trailer(
+        &mut self,
+        trailer: &TrailerRecord,
+        carrier: Option<&CarrierRecord>,
+    ) -> PolarsResult<()>;
+
+    fn carrier_complete(&mut self, carrier: Option<&CarrierRecord>) -> PolarsResult<()>;
+}
+
+impl<P: BatchProcessor + ?Sized> RecordSink<String> for P {
+    fn lines(&mut self, batch: LineBatch<'_>) -> PolarsResult<()> {
+        self.process_lines(batch)
+    }
+
+    fn header(&mut self, header: &HeaderRecord) -> PolarsResult<()> {
+        self.on_header(header)
+    }
+
+    fn trailer(
+        &mut self,
+        trailer: &TrailerRecord,
+        carrier: Option<&CarrierRecord>,
+    ) -> PolarsResult<()> {
+        self.on_trailer(trailer, carrier)
+    }
+
+    fn carrier_complete(&mut self, carrier: Option<&CarrierRecord>) -> PolarsResult<()> {
+        self.on_carrier_complete(carrier)
+    }
+}
+
+/// Flight designator buffers reused from batch to batch by the record parser.
+#[derive(Debug, Default)]
+pub(crate) struct DesignatorBuffers {
+    flights: FlightDesignators,
+    segments: FlightDesignators,
+}
+
+/// The validated flight leg and segment lines of one batch, all of one carrier.
+///
+/// Every line has passed [`check_record`](crate::utils::ssim_parser::check_record),
+/// so its fields can be sliced without
+/// further checks. Lines are `String`s read from the input, or borrowed from
+/// a memory-mapped file by [`ChunkedSsimReader`](crate::ChunkedSsimReader).
+pub struct LineBatch<'a, L = String> {
+    flight_lines: &'a [L],
+    segment_lines: &'a [L],
+    segment_legs: &'a [Option<usize>],
+    carrier: Option<&'a CarrierRecord>,
+    columns: &'a ColumnSelection,
+    designators: &'a mut DesignatorBuffers,
+}
+
+impl<'a, L: AsRef<str> + Sync> LineBatch<'a, L> {
+    pub(crate) fn new(
+        flight_lines: &'a [L],
+        segment_lines: &'a [L],
+        segment_legs: &'a [Option<usize>],
+        carrier: Option<&'a CarrierRecord>,
+        columns: &'a ColumnSelection,
+        designators: &'a mut DesignatorBuffers,
+    ) -> Self {
+        Self {
+            flight_lines,
+            segment_lines,
+            segment_legs,
+            carrier,
+            columns,
+            designators,
+        }
+    }
+
+    /// The flight leg (type 3) lines.
+    pub fn flight_lines(&self) -> &'a [L] {
+        self.flight_lines
+    }
+
+    /// The segment data (type 4) lines.
+    pub fn segment_lines(&self) -> &'a [L] {
+        self.segment_lines
+    }
+
+    /// For each segment line, the index of its flight leg in
+    /// [`flight_lines`](Self::flight_lines), or `None` if the leg was not read.
+    pub fn segment_legs(&self) -> &'a [Option<usize>] {
+        self.segment_legs
+    }
+
+    /// The carrier the lines belong to.
+    pub fn carrier(&self) -> Option<&'a CarrierRecord> {
+        self.carrier
+    }
+
+    /// The columns the reader was asked to build.
+    pub fn columns(&self) -> &'a ColumnSelection {
+        self.columns
+    }
+
+    /// Build the carrier, flight leg and segment DataFrames straight from
+    /// the lines, with a string view column per kept field.
+    pub fn to_dataframes(&self) -> PolarsResult<(DataFrame, DataFrame, DataFrame)> {
+        lines_to_dataframes(
+            self.carrier,
+            self.flight_lines,
+            self.segment_lines,
+            self.columns,
+        )
+    }
+
+    /// Build the combined DataFrame straight from the lines, with each
+    /// segment attached to its flight leg, flat or with the segments of each
+    /// leg condensed into a `segment_data` column.
+    pub fn to_combined_dataframe(&self, condense_segments: bool) -> PolarsResult<DataFrame> {
+        lines_to_combined_dataframe(
+            self.carrier,
+            self.flight_lines,
+            self.segment_lines,
+            self.segment_legs,
+            self.columns,
+            condense_segments,
+        )
+    }
+
+    /// Build the DataFrames of `layout` straight from the lines.
+    pub fn to_frames(&self, layout: BatchLayout) -> PolarsResult<BatchFrames> {
+        Ok(match layout {
+            BatchLayout::Split => {
+                let (carriers, flights, segments) = self.to_dataframes()?;
+                BatchFrames::Split(carriers, flights, segments)
+            }
+            BatchLayout::Combined { condense_segments } => {
+                BatchFrames::Combined(self.to_combined_dataframe(condense_segments)?)
+            }
+        })
+    }
+
+    /// Parse the lines into records in parallel using rayon.
+    ///
+    /// The records borrow their fields from the lines, their flight
+    /// designators from the reader's reused buffers and their duplicate
+    /// indicator from the carrier. Without a carrier there are no records.
+    pub fn into_records(self) -> (Vec<FlightLegRecord<'a>>, Vec<SegmentRecords<'a>>) {
+        let Some(carrier) = self.carrier else {
+            return (Vec::new(), Vec::new());
+        };
+        let (flight_lines, segment_lines, columns) =
+            (self.flight_lines, self.segment_lines, self.columns);
+        let DesignatorBuffers { flights, segments } = self.designators;
+        let duplicate_indicator = carrier.control_duplicate_indicator.as_str();
+
+        // Parse flights and segments in parallel
+        rayon::join(
+            move || {
+                flights.fill(flight_lines, duplicate_indicator);
+                let designators: &FlightDesignators = flights;
+                flight_lines
+                    .par_iter()
+                    .enumerate()
+                    .filter_map(|(i, line)| {
+                        let designator = Cow::Borrowed(designators.get(i));
+                        parse_flight_leg(line.as_ref(), carrier, designator, columns)
+                    })
+                    .collect()
+            },
+            move || {
+                segments.fill(segment_lines, duplicate_indicator);
+                let designators: &FlightDesignators = segments;
+                segment_lines
+                    .par_iter()
+                    .enumerate()
+                    .filter_map(|(i, line)| {
+                        let designator = Cow::Borrowed(designators.get(i));
+                        parse_segment(line.as_ref(), carrier, designator, columns)
+                    })
+                    .collect()
+            },
+        )
+    }
+}
@@ -0,0 +1,555 @@
+//! Memory-mapped, chunk-parallel reading of plain SSIM files.
+//!
+//! The file is split into chunks at lines where no state but the current
+//! carrier crosses over: carrier records (type 2), and flight legs (type 3)
+//! that start a new flight after the previous flight's legs and segments.
+//! Each chunk is read by its own [`SsimReader`], seeded with the carrier
+//! block it starts in, from lines borrowed from the mapping. The DataFrames
+//! of the processor's [`BatchLayout`] are built on the thread reading the
+//! chunk, and handed to the processor in file order, so the output is the
+//! same as the sequential reader's.
+
+use crate::records::carrier_record::CarrierRecord;
+use crate::records::header_record::HeaderRecord;
+use crate::records::trailer_record::TrailerRecord;
+use crate::utils::ssim_batch::{
+    BatchFrames, BatchLayout, DesignatorBuffers, LineBatch, RecordSink,
+};
+use crate::utils::ssim_columns::ColumnSelection;
+use crate::utils::ssim_diagnostics::{DEFAULT_MAX_REPORTED_ISSUES, Diagnostics, IssueKind};
+use crate::utils::ssim_error::{RecordLocation, SsimError, SsimResult};
+use crate::utils::ssim_parser::{check_record, parse_carrier_record};
+use crate::utils::ssim_reader::{
+    BatchProcessor, ChunkEdges, EndCodeCheck, SsimOptions, SsimReader, end_code_issue,
+    serial_number_mismatch, trailer_check_issue,
+};
+use crate::utils::ssim_source::{Compression, LineSource, SourceLine, sealed};
+use memmap2::Mmap;
+use polars::prelude::PolarsResult;
+use rayon::prelude::*;
+use std::borrow::Cow;
+use std::fs::File;
+
+/// Default number of bytes per chunk.
+pub const DEFAULT_CHUNK_SIZE: usize = 16 * 1024 * 1024; // 16 MB
+
+/// Reads a plain (uncompressed) SSIM file by memory-mapping it and parsing
+/// chunks of it on all cores.
+///
+/// Produces the same records, in the same order, and the same [`Diagnostics`]
+/// as [`SsimReader`]. Compressed files and zip archives cannot be split and
+/// are rejected; [`SsimOptions::chunk_size`] falls back to the sequential
+/// reader for them instead.
+///
+/// ```no_run
+/// use rusty_ssim_core::{ChunkedSsimReader, CombinedDataFrameProcessor, SsimOptions};
+///
+/// let reader = ChunkedSsimReader::open("schedule.ssim", &SsimOptions::default())?;
+/// let mut processor = CombinedDataFrameProcessor::default();
+/// let diagnostics = reader.process(&mut processor)?;
+/// let df = processor.into_result();
+/// # Ok::<(), rusty_ssim_core::SsimError>(())
+/// ```
+pub struct ChunkedSsimReader {
+    mmap: Mmap,
+    file_path: String,
+    options: SsimOptions,
+}
+
+impl ChunkedSsimReader {
+    /// Memory-map `file_path`, reading it with the settings in `options`.
+    ///
+    /// Chunks are `options.chunk_size` bytes long (default: 16 MB), give or
+    /// take the distance to the next split point.
+    ///
+    /// # Errors
+    /// Fails if the file cannot be opened or mapped, or is compressed.
+    pub fn open(file_path: &str, options: &SsimOptions) -> SsimResult<Self> {
+        match map_plain_file(file_path)? {
+            Some(mmap) => Ok(Self::from_mmap(mmap, file_path, options)),
+            None => Err(SsimError::Io {
+                file_path: Some(file_path.to_string()),
+                source: std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "compressed files cannot be memory-mapped; use SsimReader to read them",
+                ),
+            }),
+        }
+    }
+
+    pub(crate) fn from_mmap(mmap: Mmap, file_path: &str, options: &SsimOptions) -> Self {
+        ChunkedSsimReader {
+            mmap,
+            file_path: file_path.to_string(),
+            options: options.clone(),
+        }
+    }
+
+    /// Feed every record to `processor`, then finalize it.
+    ///
+    /// Chunks are parsed in parallel, as many at a time as there are rayon
+    /// threads, and handed to `processor` in file order. If the processor has
+    /// a [`BatchProcessor::batch_layout`], the chunks' DataFrames are built in
+    /// parallel too, and handed over through [`BatchProcessor::process_frames`].
+    pub fn process<P: BatchProcessor>(&self, processor: &mut P) -> SsimResult<Diagnostics> {
+        let data: &[u8] = &self.mmap;
+        let layout = processor.batch_layout();
+        let chunk_size = self.options.chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE).max(1);
+        let starts = split_points(data, chunk_size);
+        let chunks: Vec<&[u8]> = starts
+            .iter()
+            .enumerate()
+            .map(|(i, &start)| &data[start..starts.get(i + 1).copied().unwrap_or(data.len())])
+            .collect();
+
+        // Where each chunk starts: line number and the carrier block it is in.
+        let scans: Vec<(usize, Option<CarrierContext>)> = chunks
+            .par_iter()
+            .zip(&starts)
+            .map(|(chunk, &start)| (count_lines(chunk), last_carrier_context(chunk, start)))
+            .collect();
+        let mut seeds = Vec::with_capacity(chunks.len());
+        let mut line_number = 1;
+        let mut context = CarrierContext::Closed;
+        for (lines, last_context) in scans {
+            seeds.push((line_number, context));
+            line_number += lines;
+            context = last_context.unwrap_or(context);
+        }
+
+        let mut diagnostics = Diagnostics::new(
+            self.options
+                .max_reported_issues
+                .unwrap_or(DEFAULT_MAX_REPORTED_ISSUES),
+        );
+        let mut carried = CarriedChecks::default();
+        let mut designators = DesignatorBuffers::default();
+        let columns = self.options.parsed_columns();
+        let wave = rayon::current_num_threads().max(1);
+        for first in (0..chunks.len()).step_by(wave) {
+            let last = (first + wave).min(chunks.len());
+            let outputs: Vec<ChunkOutput> = (first..last)
+                .into_par_iter()
+                .map(|i| {
+                    let (line_number, context) = seeds[i];
+                    self.read_chunk(
+                        chunks[i],
+                        starts[i] as u64,
+                        line_number,
+                        context,
+                        i + 1 == chunks.len(),
+                        layout,
+                    )
+                })
+                .collect();
+
+            for output in outputs {
+                let mut chunk_diagnostics = output.diagnostics;
+                carried.settle(output.edges, &mut chunk_diagnostics);
+                diagnostics.merge(chunk_diagnostics);
+                for event in output.events {
+                    event.replay(processor, &columns, &mut designators)?;
+                }
+                output.result?;
+            }
+        }
+
+        processor.finalize()?;
+        Ok(diagnostics)
+    }
+
+    /// Parse one chunk, recording what it hands to the processor and building
+    /// the DataFrames of `layout`, if any.
+    fn read_chunk(
+        &self,
+        chunk: &[u8],
+        byte_offset: u64,
+        line_number: usize,
+        context: CarrierContext,
+        at_input_end: bool,
+        layout: Option<BatchLayout>,
+    ) -> ChunkOutput {
+        let (carrier, skipping_carrier) = match context {
+            CarrierContext::Closed => (None, false),
+            CarrierContext::Carrier(offset) => {
+                let line = record_at(&self.mmap, offset);
+                if self.options.filter.matches_carrier(line) {
+                    (parse_carrier_record(line), false)
+                } else {
+                    (None, true)
+                }
+            }
+        };
+
+        let reader = SsimReader::from_reader(MappedLines::new(chunk), self.options.batch_size)
+            .with_file_path(&self.file_path);
+        let mut reader = self.options.configure(reader).into_chunk(
+            line_number,
+            byte_offset,
+            carrier,
+            skipping_carrier,
+            at_input_end,
+        );
+        let mut recorder = ChunkRecorder {
+            layout,
+            events: Vec::new(),
+        };
+        let result = reader.read_records(&mut recorder);
+        ChunkOutput {
+            events: recorder.events,
+            result,
+            edges: reader.take_chunk_edges(),
+            diagnostics: reader.take_diagnostics(),
+        }
+    }
+}
+
+/// Memory-map `file_path`, or return `None` if it is compressed.
+pub(crate) fn map_plain_file(file_path: &str) -> SsimResult<Option<Mmap>> {
+    let io_error = |source: std::io::Error| SsimError::Io {
+        file_path: Some(file_path.to_string()),
+        source,
+    };
+
+    let file = File::open(file_path).map_err(io_error)?;
+    // SAFETY: the mapping is only read, and is invalidated if the file is
+    // modified while it is read, as with any memory-mapped input.
+    let mmap = unsafe { Mmap::map(&file) }.map_err(io_error)?;
+    Ok((Compression::detect(&mmap) == Compression::None).then_some(mmap))
+}
+
+/// The lines of a chunk of the mapped file, borrowed unless they are not
+/// valid UTF-8.
+struct MappedLines<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> MappedLines<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        MappedLines { data, position: 0 }
+    }
+}
+
+impl sealed::Sealed for MappedLines<'_> {}
+
+impl<'a> LineSource for MappedLines<'a> {
+    type Line = Cow<'a, str>;
+
+    fn read_line(&mut self) -> std::io::Result<Option<SourceLine<Cow<'a, str>>>> {
+        if self.position >= self.data.len() {
+            return Ok(None);
+        }
+        let start = self.position;
+        let (line, next) = line_at(self.data, start);
+        self.position = next;
+        let (text, invalid_utf8_column) = match std::str::from_utf8(line) {
+            Ok(text) => (Cow::Borrowed(text), None),
+            Err(e) => (String::from_utf8_lossy(line), Some(e.valid_up_to() + 1)),
+        };
+        Ok(Some(SourceLine {
+            text,
+            bytes_read: next - start,
+            invalid_utf8_column,
+        }))
+    }
+}
+
+/// The carrier block a chunk starts in.
+#[derive(Debug, Clone, Copy)]
+enum CarrierContext {
+    /// Outside of any carrier block, or after a trailer.
+    Closed,
+    /// In the block opened by the carrier record at this byte offset.
+    Carrier(usize),
+}
+
+/// Byte offsets of the chunk starts, the first being 0.
+///
+/// Every chunk after the first starts at the first split point at or after
+/// `chunk_size` bytes from the previous start.
+fn split_points(data: &[u8], chunk_size: usize) -> Vec<usize> {
+    let mut starts = vec![0];
+    let mut target = chunk_size;
+    while target < data.len() {
+        let Some(start) = next_split_point(data, target) else {
+            break;
+        };
+        starts.push(start);
+        target = start + chunk_size;
+    }
+    starts
+}
+
+/// The start of the first line at or after `from` that a chunk can start at.
+///
+/// That is a valid carrier record, or a flight leg of another flight than the
+/// flight leg or segment record before it. A flight is identified by its
+/// airline, flight number, itinerary variation and operational suffix.
+fn next_split_point(data: &[u8], from: usize) -> Option<usize> {
+    let mut start = if data[from - 1] == b'\n' {
+        from
+    } else {
+        from + data[from..].iter().position(|&b| b == b'\n')? + 1
+    };
+
+    let mut previous_type = None;
+    let mut previous_flight: Option<&[u8]> = None;
+    while start < data.len() {
+        let (line, next) = line_at(data, start);
+        match line.first() {
+            // Filler and blank lines carry no state.
+            None | Some(b'0') => {}
+            Some(b'2') if is_valid_record(line) => return Some(start),
+            Some(&record_type) => {
+                if record_type == b'3' {
+                    let flight = line.get(1..11);
+                    if matches!(previous_type, Some(b'3' | b'4'))
+                        && flight.is_some()
+                        && previous_flight.is_some()
+                        && flight != previous_flight
+                    {
+                        return Some(start);
+                    }
+                    previous_flight = flight;
+                }
+                previous_type = Some(record_type);
+            }
+        }
+        start = next;
+    }
+    None
+}
+
+/// The carrier block in force at the end of `chunk`, which starts at
+/// `chunk_start` in the file, if the chunk opens or closes one: its last valid
+/// carrier record or trailer, whichever is later.
+fn last_carrier_context(chunk: &[u8], chunk_start: usize) -> Option<CarrierContext> {
+    let mut end = chunk.len();
+    while end > 0 {
+        let start = chunk[..end - 1]
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map_or(0, |i| i + 1);
+        let line = strip_line_end(&chunk[start..end]);
+        match line.first() {
+            // A malformed trailer still closes its carrier block.
+            Some(b'5') => return Some(CarrierContext::Closed),
+            Some(b'2') if is_valid_record(line) => {
+                return Some(CarrierContext::Carrier(chunk_start + start));
+            }
+            _ => {}
+        }
+        end = start;
+    }
+    None
+}
+
+fn count_lines(chunk: &[u8]) -> usize {
+    let newlines = chunk.iter().filter(|&&b| b == b'\n').count();
+    newlines + usize::from(!chunk.is_empty() && !chunk.ends_with(b"\n"))
+}
+
+/// The line starting at `start` without its terminator, and the start of the next line.
+fn line_at(data: &[u8], start: usize) -> (&[u8], usize) {
+    let end = data[start..]
+        .iter()
+        .position(|&b| b == b'\n')
+        .map_or(data.len(), |i| start + i + 1);
+    (strip_line_end(&data[start..end]), end)
+}
+
+fn strip_line_end(line: &[u8]) -> &[u8] {
+    match line.strip_suffix(b"\n") {
+        Some(line) => line.strip_suffix(b"\r").unwrap_or(line),
+        None => line,
+    }
+}
+
+/// Whether a line passes the reader's validation, so the reader uses it.
+fn is_valid_record(line: &[u8]) -> bool {
+    std::str::from_utf8(line).is_ok_and(|text| check_record(text).is_ok())
+}
+
+/// The valid record line starting at `offset` in the mapped file.
+fn record_at(data: &[u8], offset: usize) -> &str {
+    let (line, _) = line_at(data, offset);
+    std::str::from_utf8(line).unwrap_or_default()
+}
+
+/// The serial number and end code checks carried from one chunk to the next.
+#[derive(Default)]
+struct CarriedChecks {
+    last_record_serial: Option<String>,
+    pending_end_code: Option<(String, RecordLocation)>,
+}
+
+impl CarriedChecks {
+    /// Make the checks of the previous chunks that the chunk with `edges`
+    /// could not make on its own, then carry its own state forward.
+    fn settle(&mut self, edges: ChunkEdges, diagnostics: &mut Diagnostics) {
+        match edges.first_end_code_check {
+            Some(EndCodeCheck::Checked {
+                another_carrier_follows,
+                line_number,
+            }) => {
+                if let Some((code, location)) = self.pending_end_code.take()
+                    && let Some(reason) = end_code_issue(&code, &location, another_carrier_follows)
+                {
+                    diagnostics.insert_warning(
+                        IssueKind::TrailerEndCodeMismatch,
+                        location,
+                        reason,
+                        line_number,
+                    );
+                }
+                self.pending_end_code = edges.pending_end_code;
+            }
+            Some(EndCodeCheck::Replaced) => self.pending_end_code = edges.pending_end_code,
+            None => {}
+        }
+
+        if let Some((check_reference, location)) = edges.first_trailer_check
+            && let Some(expected) = &self.last_record_serial
+            && let Some(reason) = trailer_check_issue(&check_reference, expected, &location)
+        {
+            let line_number = location.line_number;
+            diagnostics.insert_warning(
+                IssueKind::TrailerCheckMismatch,
+                location,
+                reason,
+                line_number,
+            );
+        }
+
+        if let Some((found, location)) = edges.first_serial
+            && let Some((kind, detail)) =
+                serial_number_mismatch(self.last_record_serial.as_deref(), &found)
+        {
+            let line_number = location.line_number;
+            let reason = format!("record serial number {} at {} {}", found, location, detail);
+            diagnostics.insert_warning(kind, location, reason, line_number);
+        }
+
+        if edges.last_record_serial.is_some() {
+            self.last_record_serial = edges.last_record_serial;
+        }
+    }
+}
+
+/// What one chunk produced.
+struct ChunkOutput {
+    events: Vec<ChunkEvent>,
+    /// The error that stopped the chunk, after its events.
+    result: SsimResult<()>,
+    edges: ChunkEdges,
+    diagnostics: Diagnostics,
+}
+
+/// A processor call made while reading a chunk.
+enum ChunkEvent {
+    Header(HeaderRecord),
+    /// The DataFrames of a batch, for a processor with a batch layout.
+    Frames {
+        frames: BatchFrames,
+        carrier: Option<Box<CarrierRecord>>,
+    },
+    /// The lines of a batch, for a processor without one.
+    Lines {
+        flight_lines: Vec<String>,
+        segment_lines: Vec<String>,
+        segment_legs: Vec<Option<usize>>,
+        carrier: Option<Box<CarrierRecord>>,
+    },
+    Trailer(TrailerRecord, Option<Box<CarrierRecord>>),
+    CarrierComplete(Option<Box<CarrierRecord>>),
+}
+
+impl ChunkEvent {
+    fn replay<P: BatchProcessor>(
+        self,
+        processor: &mut P,
+        columns: &ColumnSelection,
+        designators: &mut DesignatorBuffers,
+    ) -> PolarsResult<()> {
+        match self {
+            ChunkEvent::Header(header) => processor.on_header(&header),
+            ChunkEvent::Frames { frames, carrier } => {
+                processor.process_frames(frames, carrier.as_deref())
+            }
+            ChunkEvent::Lines {
+                flight_lines,
+                segment_lines,
+                segment_legs,
+                carrier,
+            } => processor.process_lines(LineBatch::new(
+                &flight_lines,
+                &segment_lines,
+                &segment_legs,
+                carrier.as_deref(),
+                columns,
+                designators,
+            )),
+            ChunkEvent::Trailer(trailer, carrier) => {
+                processor.on_trailer(&trailer, carrier.as_deref())
+            }
+            ChunkEvent::CarrierComplete(carrier) => {
+                processor.on_carrier_complete(carrier.as_deref())
+            }
+        }
+    }
+}
+
+/// Records the processor calls of a chunk, to replay them in file order.
+struct ChunkRecorder {
+    /// The processor's batch layout, to build each batch's DataFrames in.
+    layout: Option<BatchLayout>,
+    events: Vec<ChunkEvent>,
+}
+
+impl<'a> RecordSink<Cow<'a, str>> for ChunkRecorder {
+    fn lines(&mut self, batch: LineBatch<'_, Cow<'a, str>>) -> PolarsResult<()> {
+        let carrier = batch.carrier().cloned().map(Box::new);
+        let event = match self.layout {
+            Some(layout) => ChunkEvent::Frames {
+                frames: batch.to_frames(layout)?,
+                carrier,
+            },
+            None => ChunkEvent::Lines {
+                flight_lines: batch.flight_lines().iter().map(|l| l.to_string()).collect(),
+                segment_lines: batch
+                    .segment_lines()
+                    .iter()
+                    .map(|l| l.to_string())
+                    .collect(),
+                segment_legs: batch.segment_legs().to_vec(),
+                carrier,
+            },
+        };
+        self.events.push(event);
+        Ok(())
+    }
+
+    fn header(&mut self, header: &HeaderRecord) -> PolarsResult<()> {
+        self.events.push(ChunkEvent::Header(header.clone()));
+        Ok(())
+    }
+
+    fn trailer(
+        &mut self,
+        trailer: &TrailerRecord,
+        carrier: Option<&CarrierRecord>,
+    ) -> PolarsResult<()> {
+        self.events.push(ChunkEvent::Trailer(
+            trailer.clone(),
+            carrier.cloned().map(Box::new),
+        ));
+        Ok(())
+    }
+
+    fn carrier_complete(&mut self, carrier: Option<&CarrierRecord>) -> PolarsResult<()> {
+        self.events
+            .push(ChunkEvent::CarrierComplete(carrier.cloned().map(Box::new)));
+        Ok(())
+    }
+}
@@ -0,0 +1,337 @@
+//! Codeshares from the DEI 10 and DEI 50 segment data of flight legs.
+//!
+//! An operating carrier lists the marketing flights of a leg in DEI 10
+//! (Code Share - Commercial Duplicate); a marketing carrier that files its
+//! duplicate legs points each of them at the operating flight in DEI 50
+//! (Duplicate Leg Cross Reference). [`codeshare_table`] collects both into one
+//! table of operating and marketing flights, [`add_operating_flights`] marks the
+//! duplicate legs of a flights DataFrame and can add the marketing legs that
+//! were not filed, and [`collapse_codeshares`] keeps only the operating legs,
+//! e.g. to count seats once.
+
+use crate::utils::ssim_dei::{DeiValue, FlightReference, decode_dei, parse_dei};
+use crate::utils::ssim_expand::required_column;
+use crate::utils::ssim_fields::parse_number;
+use polars::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+/// Name of the operating flight column added by [`add_operating_flights`].
+pub const OPERATING_FLIGHT_COLUMN: &str = "operating_flight";
+
+const DUPLICATE_LEGS: u16 = 10;
+const OPERATING_FLIGHT: u16 = 50;
+const TO: &str = "to find codeshares";
+
+/// A DEI 10 or DEI 50 record of a flight leg: the leg's own flight and the
+/// flights its data names.
+struct CodeshareRecord {
+    dei: u16,
+    flight: FlightReference,
+    leg: (String, String),
+    board_point: String,
+    off_point: String,
+    partners: Vec<FlightReference>,
+}
+
+impl CodeshareRecord {
+    /// The operating and the marketing flights of the record.
+    fn pairs(&self) -> impl Iterator<Item = (&FlightReference, &FlightReference)> {
+        self.partners.iter().map(move |partner| match self.dei {
+            DUPLICATE_LEGS => (&self.flight, partner),
+            _ => (partner, &self.flight),
+        })
+    }
+}
+
+/// One row per operating and marketing flight pair and leg covered.
+///
+/// `segments` needs the flight key columns (`flight_designator`,
+/// `leg_sequence_number`, `airline_designator`, `flight_number`,
+/// `operational_suffix`), `board_point`, `off_point`,
+/// `data_element_identifier` and `data`, as in the segments of
+/// [`ssim_to_dataframes`](crate::ssim_to_dataframes). DEI 10 records make
+/// their own flight the operating flight of each marketing flight they list;
+/// DEI 50 records make their own flight a marketing flight of the one they
+/// name. The columns are:
+///
+/// * `operating_airline`, `operating_flight_number` (`UInt16`), `operating_suffix`
+/// * `marketing_airline`, `marketing_flight_number` (`UInt16`), `marketing_suffix`
+/// * `board_point`, `off_point` — the leg covered
+/// * `data_element_identifier` (`UInt16`) — 10 or 50, the first record of the pair
+///
+/// A pair filed by both carriers is listed once. Records whose data does not
+/// decode are skipped.
+///
+/// # Errors
+/// Returns a `ColumnNotFound` error if a required column is missing.
+pub fn codeshare_table(segments: &DataFrame) -> PolarsResult<DataFrame> {
+    let records = codeshare_records(segments)?;
+    let mut seen = HashSet::new();
+    let mut rows = Vec::new();
+    for record in &records {
+        for (operating, marketing) in record.pairs() {
+            if seen.insert((operating, marketing, &record.board_point, &record.off_point)) {
+                rows.push((operating, marketing, record));
+            }
+        }
+    }
+
+    let operating: Vec<_> = rows.iter().map(|(operating, _, _)| *operating).collect();
+    let marketing: Vec<_> = rows.iter().map(|(_, marketing, _)| *marketing).collect();
+    let mut columns = flight_columns("operating", &operating);
+    columns.extend(flight_columns("marketing", &marketing));
+    columns.extend([
+        Column::new(
+            "board_point".into(),
+            rows.iter()
+                .map(|(_, _, record)| record.board_point.as_str())
+                .collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "off_point".into(),
+            rows.iter()
+                .map(|(_, _, record)| record.off_point.as_str())
+                .collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "data_element_identifier".into(),
+            rows.iter()
+                .map(|(_, _, record)| record.dei)
+                .collect::<Vec<_>>(),
+        ),
+    ]);
+    DataFrame::new_infer_height(columns)
+}
+
+/// The `{prefix}_airline`, `{prefix}_flight_number` and `{prefix}_suffix` columns of `flights`.
+fn flight_columns(prefix: &str, flights: &[&FlightReference]) -> Vec<Column> {
+    vec![
+        Column::new(
+            format!("{prefix}_airline").into(),
+            flights
+                .iter()
+                .map(|flight| flight.airline_designator.as_str())
+                .collect::<Vec<_>>(),
+        ),
+        Column::new(
+            format!("{prefix}_flight_number").into(),
+            flights
+                .iter()
+                .map(|flight| flight.flight_number)
+                .collect::<Vec<_>>(),
+        ),
+        Column::new(
+            format!("{prefix}_suffix").into(),
+            flights
+                .iter()
+                .map(|flight| flight.operational_suffix.map(String::from))
+                .collect::<Vec<_>>(),
+        ),
+    ]
+}
+
+/// Add the `operating_flight` of every marketing duplicate leg of `flights`.
+///
+/// A leg is a marketing duplicate if one of its DEI 50 records in `segments`
+/// names its operating flight, or if a DEI 10 record of another leg with the
+/// same departure and arrival station lists it. `operating_flight` is the
+/// designator of the operating flight, e.g. `KL 2562`, and null for operating
+/// legs and legs without codeshares.
+///
+/// With `emit_marketing_legs`, a copy of the operating leg is appended for
+/// each marketing flight its DEI 10 records list but `flights` does not
+/// have, with the marketing airline, flight number and operational suffix.
+///
+/// `flights` needs the flight key columns and `departure_station` and
+/// `arrival_station`, and `segments` the columns of [`codeshare_table`].
+///
+/// # Errors
+/// Returns a `ColumnNotFound` error if a required column is missing.
+pub fn add_operating_flights(
+    flights: &DataFrame,
+    segments: &DataFrame,
+    emit_marketing_legs: bool,
+) -> PolarsResult<DataFrame> {
+    let designators = text_column(flights, "flight_designator")?;
+    let leg_numbers = text_column(flights, "leg_sequence_number")?;
+    let departures = text_column(flights, "departure_station")?;
+    let arrivals = text_column(flights, "arrival_station")?;
+    let own_flights = flight_references(flights)?;
+
+    let mut legs: HashMap<_, Vec<usize>> = HashMap::new();
+    let mut routes: HashMap<_, Vec<usize>> = HashMap::new();
+    for (row, own_flight) in own_flights.iter().enumerate() {
+        let leg = (text(&designators, row), text(&leg_numbers, row));
+        legs.entry(leg).or_default().push(row);
+        if let Some(flight) = own_flight {
+            let route = (flight, text(&departures, row), text(&arrivals, row));
+            routes.entry(route).or_default().push(row);
+        }
+    }
+
+    let mut operating: Vec<Option<String>> = vec![None; flights.height()];
+    let mut marketing_legs = Vec::new();
+    let mut emitted = HashSet::new();
+    let records = codeshare_records(segments)?;
+    for record in &records {
+        let leg = (record.leg.0.as_str(), record.leg.1.as_str());
+        let Some(rows) = legs.get(&leg) else {
+            continue;
+        };
+        for &row in rows {
+            if record.dei == OPERATING_FLIGHT {
+                operating[row] = Some(record.partners[0].to_string());
+                continue;
+            }
+            for partner in &record.partners {
+                let route = (partner, text(&departures, row), text(&arrivals, row));
+                match routes.get(&route) {
+                    Some(duplicates) => {
+                        for &duplicate in duplicates {
+                            operating[duplicate].get_or_insert_with(|| record.flight.to_string());
+                        }
+                    }
+                    None if emit_marketing_legs && emitted.insert((row, partner)) => {
+                        marketing_legs.push((row, partner, &record.flight));
+                    }
+                    None => {}
+                }
+            }
+        }
+    }
+
+    let mut marked = flights.clone();
+    marked.with_column(Column::new(OPERATING_FLIGHT_COLUMN.into(), operating))?;
+    if marketing_legs.is_empty() {
+        return Ok(marked);
+    }
+
+    let rows: Vec<IdxSize> = marketing_legs
+        .iter()
+        .map(|(row, _, _)| *row as IdxSize)
+        .collect();
+    let mut duplicates = marked.take(&IdxCa::from_vec("rows".into(), rows))?;
+    let designators: Vec<String> = marketing_legs
+        .iter()
+        .map(|(row, partner, _)| marketing_designator(text(&designators, *row), partner))
+        .collect();
+    let airlines: Vec<String> = marketing_legs
+        .iter()
+        .map(|(_, partner, _)| format!("{:<3}", partner.airline_designator))
+        .collect();
+    let numbers: Vec<String> = marketing_legs
+        .iter()
+        .map(|(_, partner, _)| format!("{:>4}", partner.flight_number))
+        .collect();
+    let suffixes: Vec<String> = marketing_legs
+        .iter()
+        .map(|(_, partner, _)| partner.operational_suffix.unwrap_or(' ').to_string())
+        .collect();
+    let operating: Vec<String> = marketing_legs
+        .iter()
+        .map(|(_, _, flight)| flight.to_string())
+        .collect();
+    for (name, values) in [
+        ("flight_designator", designators),
+        ("airline_designator", airlines),
+        ("flight_number", numbers),
+        ("operational_suffix", suffixes),
+        (OPERATING_FLIGHT_COLUMN, operating),
+    ] {
+        if duplicates.schema().contains(name) {
+            duplicates.with_column(Column::new(name.into(), values))?;
+        }
+    }
+    marked.vstack(&duplicates)
+}
+
+/// The operating legs of `flights`: every leg that [`add_operating_flights`]
+/// does not mark as a marketing duplicate, so that each flown leg is counted
+/// once, e.g. when summing seats.
+///
+/// # Errors
+/// Returns a `ColumnNotFound` error if a required column is missing.
+pub fn collapse_codeshares(flights: &DataFrame, segments: &DataFrame) -> PolarsResult<DataFrame> {
+    let marked = add_operating_flights(flights, segments, false)?;
+    let operating = marked.column(OPERATING_FLIGHT_COLUMN)?.is_null();
+    marked.filter(&operating)?.drop(OPERATING_FLIGHT_COLUMN)
+}
+
+/// The decodable DEI 10 and DEI 50 records of `segments`.
+fn codeshare_records(segments: &DataFrame) -> PolarsResult<Vec<CodeshareRecord>> {
+    let codes = text_column(segments, "data_element_identifier")?;
+    let data = text_column(segments, "data")?;
+    let designators = text_column(segments, "flight_designator")?;
+    let leg_numbers = text_column(segments, "leg_sequence_number")?;
+    let board_points = text_column(segments, "board_point")?;
+    let off_points = text_column(segments, "off_point")?;
+    let flights = flight_references(segments)?;
+
+    let mut records = Vec::new();
+    for (row, flight) in flights.into_iter().enumerate() {
+        let Ok(dei @ (DUPLICATE_LEGS | OPERATING_FLIGHT)) = parse_dei(text(&codes, row)) else {
+            continue;
+        };
+        let Ok(Some(DeiValue::Flights(partners))) = decode_dei(dei, text(&data, row)) else {
+            continue;
+        };
+        let Some(flight) = flight else {
+            continue;
+        };
+        records.push(CodeshareRecord {
+            dei,
+            flight,
+            leg: (
+                text(&designators, row).to_string(),
+                text(&leg_numbers, row).to_string(),
+            ),
+            board_point: text(&board_points, row).trim().to_string(),
+            off_point: text(&off_points, row).trim().to_string(),
+            partners,
+        });
+    }
+    Ok(records)
+}
+
+/// The flight of each row of a flight leg or segment DataFrame, `None` if its
+/// flight number is blank or invalid.
+fn flight_references(df: &DataFrame) -> PolarsResult<Vec<Option<FlightReference>>> {
+    let airlines = text_column(df, "airline_designator")?;
+    let numbers = text_column(df, "flight_number")?;
+    let suffixes = text_column(df, "operational_suffix")?;
+    Ok((0..df.height())
+        .map(|row| {
+            let flight_number = parse_number("flight_number", text(&numbers, row)).ok()?;
+            Some(FlightReference {
+                airline_designator: text(&airlines, row).trim().to_string(),
+                flight_number,
+                operational_suffix: text(&suffixes, row).trim().chars().next(),
+            })
+        })
+        .collect())
+}
+
+/// The flight designator of `partner` for a copy of the leg with `designator`,
+/// keeping its duplicate indicator and itinerary variation: `XX _   12  01  `
+/// is airline, `_`, duplicate indicator, flight number, suffix, then the rest.
+fn marketing_designator(designator: &str, partner: &FlightReference) -> String {
+    format!(
+        "{:<3}{}{:>4}{}{}",
+        partner.airline_designator,
+        designator.get(3..5).unwrap_or("_ "),
+        partner.flight_number,
+        partner.operational_suffix.unwrap_or(' '),
+        designator.get(10..).unwrap_or_default()
+    )
+}
+
+fn text_column(df: &DataFrame, name: &str) -> PolarsResult<StringChunked> {
+    Ok(required_column(df, name, TO)?
+        .cast(&DataType::String)?
+        .str()?
+        .clone())
+}
+
+fn text(column: &StringChunked, row: usize) -> &str {
+    column.get(row).unwrap_or_default()
+}
@@ -0,0 +1,332 @@
+//! Column projection for the DataFrame, CSV and Parquet outputs.
+//!
+//! A [`ColumnSelection`] is pushed down into the record parsers and the
+//! DataFrame builders: fields that are not selected are never sliced out of
+//! the line, trimmed or copied, and their columns are never built. The join
+//! keys between carriers, flight legs and segments are always parsed and are
+//! dropped from the output again if they were not selected.
+
+use polars::prelude::*;
+use std::str::FromStr;
+
+/// Columns built from the carrier record (type 2).
+pub const CARRIER_COLUMNS: &[&str] = &[
+    "airline_designator",
+    "control_duplicate_indicator",
+    "time_mode",
+    "season",
+    "period_of_schedule_validity_from",
+    "period_of_schedule_validity_to",
+    "creation_date",
+    "title_of_data",
+    "release_date",
+    "schedule_status",
+    "general_information",
+    "in_flight_service_information",
+    "electronic_ticketing_information",
+    "creation_time",
+    "record_type",
+    "record_serial_number",
+];
+
+/// Header and trailer columns joined onto the split carriers DataFrame.
+pub const CARRIER_BLOCK_COLUMNS: &[&str] = &[
+    "title_of_contents",
+    "number_of_seasons",
+    "data_set_serial_number",
+    "serial_number_check_reference",
+    "continuation_end_code",
+];
+
+/// Columns built from the flight leg record (type 3).
+pub const FLIGHT_LEG_COLUMNS: &[&str] = &[
+    "flight_designator",
+    "operational_suffix",
+    "airline_designator",
+    "control_duplicate_indicator",
+    "flight_number",
+    "itinerary_variation_identifier",
+    "leg_sequence_number",
+    "service_type",
+    "period_of_operation_from",
+    "period_of_operation_to",
+    "days_of_operation",
+    "frequency_rate",
+    "departure_station",
+    "scheduled_time_of_passenger_departure",
+    "scheduled_time_of_aircraft_departure",
+    "time_variation_departure",
+    "passenger_terminal_departure",
+    "arrival_station",
+    "scheduled_time_of_aircraft_arrival",
+    "scheduled_time_of_passenger_arrival",
+    "time_variation_arrival",
+    "passenger_terminal_arrival",
+    "aircraft_type",
+    "passenger_reservations_booking_designator",
+    "passenger_reservations_booking_modifier",
+    "meal_service_note",
+    "joint_operation_airline_designators",
+    "min_connecting_time_status_departure",
+    "min_connecting_time_status_arrival",
+    "secure_flight_indicator",
+    "itinerary_variation_identifier_overflow",
+    "aircraft_owner",
+    "cockpit_crew_employer",
+    "cabin_crew_employer",
+    "onward_flight",
+    "airline_designator2",
+    "flight_number2",
+    "aircraft_rotation_layover",
+    "operational_suffix2",
+    "flight_transit_layover",
+    "operating_airline_disclosure",
+    "traffic_restriction_code",
+    "traffic_restriction_code_leg_overflow_indicator",
+    "aircraft_configuration",
+    "date_variation",
+    "record_type",
+    "record_serial_number",
+];
+
+/// Columns built from the segment data record (type 4).
+pub const SEGMENT_COLUMNS: &[&str] = &[
+    "flight_designator",
+    "operational_suffix",
+    "airline_designator",
+    "control_duplicate_indicator",
+    "flight_number",
+    "itinerary_variation_identifier",
+    "leg_sequence_number",
+    "service_type",
+    "itinerary_variation_identifier_overflow",
+    "board_point_indicator",
+    "off_point_indicator",
+    "data_element_identifier",
+    "board_point",
+    "off_point",
+    "data",
+    "record_type",
+    "record_serial_number",
+];
+
+/// Segment fields nested in the condensed `segment_data` column.
+pub(crate) const SEGMENT_DATA_FIELDS: &[&str] = &[
+    "board_point_indicator",
+    "off_point_indicator",
+    "board_point",
+    "off_point",
+    "data_element_identifier",
+    "data",
+];
+
+/// Columns that join carriers, flight legs and segments; always parsed.
+const KEY_COLUMNS: &[&str] = &[
+    "airline_designator",
+    "control_duplicate_indicator",
+    "flight_designator",
+    "leg_sequence_number",
+    "record_serial_number",
+];
+
+/// Bit of `column` in a mask over `columns`.
+///
+/// Used in `const` blocks by the parsers, so a misspelt column fails to compile.
+pub(crate) const fn column_bit(columns: &[&str], column: &str) -> u64 {
+    let mut i = 0;
+    while i < columns.len() {
+        if const_str_eq(columns[i], column) {
+            return 1 << i;
+        }
+        i += 1;
+    }
+    panic!("unknown column");
+}
+
+const fn const_str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// The output columns to build, in output order.
+///
+/// The default selects every column in the schema order. Parse a selection
+/// from a list of names with [`ColumnSelection::new`] or from a
+/// comma-separated string with [`str::parse`]:
+///
+/// ```
+/// use rusty_ssim_core::ColumnSelection;
+///
+/// let columns: ColumnSelection = "flight_designator,departure_station,arrival_station"
+///     .parse()
+///     .unwrap();
+/// assert_eq!(columns.names().map(|n| n.len()), Some(3));
+/// assert!("no_such_column".parse::<ColumnSelection>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnSelection {
+    names: Option<Vec<String>>,
+    flight_leg_mask: u64,
+    segment_mask: u64,
+}
+
+impl ColumnSelection {
+    /// Select every column.
+    pub fn all() -> Self {
+        Self {
+            names: None,
+            flight_leg_mask: u64::MAX,
+            segment_mask: u64::MAX,
+        }
+    }
+
+    /// Select `columns`, in that order.
+    ///
+    /// # Errors
+    /// Returns a message naming the first unknown column, or an error if no
+    /// column is given.
+    pub fn new<I, S>(columns: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut names: Vec<String> = Vec::new();
+        for column in columns {
+            let column = column.as_ref().trim();
+            if !is_known_column(column) {
+                return Err(format!("unknown column '{}'", column));
+            }
+            if !names.iter().any(|n| n == column) {
+                names.push(column.to_string());
+            }
+        }
+        if names.is_empty() {
+            return Err("no columns selected".to_string());
+        }
+
+        let mut selection = Self {
+            names: Some(names),
+            flight_leg_mask: 0,
+            segment_mask: 0,
+        };
+        selection.flight_leg_mask = selection.mask(FLIGHT_LEG_COLUMNS);
+        selection.segment_mask = selection.mask(SEGMENT_COLUMNS);
+        Ok(selection)
+    }
+
+    /// The selected column names, or `None` if every column is selected.
+    pub fn names(&self) -> Option<&[String]> {
+        self.names.as_deref()
+    }
+
+    pub fn is_all(&self) -> bool {
+        self.names.is_none()
+    }
+
+    fn is_selected(&self, column: &str) -> bool {
+        match &self.names {
+            None => true,
+            Some(names) => names.iter().any(|n| n == column),
+        }
+    }
+
+    /// Whether `column` has to be parsed and built: it is selected, a join
+    /// key, or a field of a selected `segment_data` column.
+    pub(crate) fn keeps(&self, column: &str) -> bool {
+        self.is_selected(column)
+            || KEY_COLUMNS.contains(&column)
+            || (SEGMENT_DATA_FIELDS.contains(&column) && self.is_selected("segment_data"))
+    }
+
+    fn mask(&self, columns: &[&str]) -> u64 {
+        columns
+            .iter()
+            .enumerate()
+            .filter(|(_, column)| self.keeps(column))
+            .fold(0, |mask, (i, _)| mask | 1 << i)
+    }
+
+    /// Kept flight leg fields, as bits over [`FLIGHT_LEG_COLUMNS`].
+    pub(crate) fn flight_leg_mask(&self) -> u64 {
+        self.flight_leg_mask
+    }
+
+    /// Kept segment fields, as bits over [`SEGMENT_COLUMNS`].
+    pub(crate) fn segment_mask(&self) -> u64 {
+        self.segment_mask
+    }
+
+    /// This selection with `inputs` added, for columns derived from them
+    /// after parsing, e.g. [`SEAT_INPUT_COLUMNS`](crate::SEAT_INPUT_COLUMNS).
+    /// Undo with [`Self::drop_inputs`].
+    pub fn with_inputs(&self, inputs: &[&str]) -> Self {
+        match &self.names {
+            None => self.clone(),
+            Some(names) => {
+                let columns = names
+                    .iter()
+                    .map(String::as_str)
+                    .chain(inputs.iter().copied());
+                Self::new(columns).expect("inputs are known columns")
+            }
+        }
+    }
+
+    /// Drop the `inputs` of [`Self::with_inputs`] that this selection does not select.
+    pub fn drop_inputs(&self, df: DataFrame, inputs: &[&str]) -> DataFrame {
+        let unselected: Vec<&str> = inputs
+            .iter()
+            .copied()
+            .filter(|input| !self.is_selected(input) && df.schema().contains(input))
+            .collect();
+        df.drop_many(unselected)
+    }
+
+    /// Select the chosen columns of `df` that it has, in selection order.
+    pub(crate) fn project(&self, df: DataFrame) -> PolarsResult<DataFrame> {
+        let Some(names) = &self.names else {
+            return Ok(df);
+        };
+        if df.width() == 0 {
+            return Ok(df);
+        }
+        let present = names
+            .iter()
+            .filter(|name| df.schema().contains(name.as_str()))
+            .map(|name| PlSmallStr::from_str(name));
+        df.select(present)
+    }
+}
+
+impl Default for ColumnSelection {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl FromStr for ColumnSelection {
+    type Err = String;
+
+    /// Parse a comma-separated list of column names.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s.split(',').filter(|c| !c.trim().is_empty()))
+    }
+}
+
+pub(crate) fn is_known_column(column: &str) -> bool {
+    column == "segment_data"
+        || CARRIER_COLUMNS.contains(&column)
+        || CARRIER_BLOCK_COLUMNS.contains(&column)
+        || FLIGHT_LEG_COLUMNS.contains(&column)
+        || SEGMENT_COLUMNS.contains(&column)
+}
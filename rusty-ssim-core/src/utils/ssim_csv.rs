@@ -0,0 +1,366 @@
+//! Settings and output files of the CSV writer.
+//!
+//! A conversion overwrites its CSV output by default. It can instead refuse
+//! to touch an existing file, or append to it after checking that the
+//! existing header matches the columns about to be written. Output can be
+//! gzip or zstd compressed; appending adds a new compressed member, which
+//! readers of both formats treat as one stream.
+
+use crate::utils::ssim_source::{Compression, decompress};
+use flate2::write::GzEncoder;
+use polars::prelude::*;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+pub use polars::prelude::QuoteStyle;
+
+/// How a writer treats an output file that already exists.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WriteMode {
+    /// Fail without touching the existing file.
+    FailIfExists,
+    /// Replace the existing file.
+    #[default]
+    Overwrite,
+    /// Add rows to the end of the existing file. Its header must match the
+    /// output columns.
+    Append,
+}
+
+impl FromStr for WriteMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "fail" | "fail_if_exists" | "fail-if-exists" => Ok(WriteMode::FailIfExists),
+            "overwrite" => Ok(WriteMode::Overwrite),
+            "append" => Ok(WriteMode::Append),
+            other => Err(format!(
+                "invalid write mode '{}', expected 'fail', 'overwrite' or 'append'",
+                other
+            )),
+        }
+    }
+}
+
+/// Settings of the CSV output, see [`SsimOptions::csv`](crate::SsimOptions::csv).
+///
+/// ```
+/// use rusty_ssim_core::{Compression, CsvOptions, WriteMode};
+///
+/// let options = CsvOptions {
+///     write_mode: WriteMode::Append,
+///     separator: b';',
+///     compression: Compression::Gzip,
+///     ..Default::default()
+/// };
+/// assert_eq!(options.file_name("ssim_XX_"), "ssim_XX_.csv.gz");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// How an existing output file is treated. Default: overwrite.
+    pub write_mode: WriteMode,
+    /// Field separator. Default: `,`.
+    pub separator: u8,
+    /// When fields are quoted. Default: only when they contain a separator,
+    /// quote or line break.
+    pub quote_style: QuoteStyle,
+    /// Text written for null values. Default: empty.
+    pub null_value: String,
+    /// chrono format of `Date` columns, e.g. `%d/%m/%Y`. Only native dtypes
+    /// have `Date` columns; their scheduled times are written as `HH:MM`.
+    /// Default: `%Y-%m-%d`.
+    pub date_format: Option<String>,
+    /// `None`, `Gzip` or `Zstd`. Default: `None`.
+    pub compression: Compression,
+    /// Write a file per carrier block into the output directory, named like
+    /// the Parquet files, e.g. `ssim_XX_.csv`, instead of a single file.
+    pub per_carrier: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            write_mode: WriteMode::default(),
+            separator: b',',
+            quote_style: QuoteStyle::default(),
+            null_value: String::new(),
+            date_format: None,
+            compression: Compression::None,
+            per_carrier: false,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// `stem` with the `.csv` extension and that of the compression.
+    pub fn file_name(&self, stem: &str) -> String {
+        match self.compression {
+            Compression::Gzip => format!("{}.csv.gz", stem),
+            Compression::Zstd => format!("{}.csv.zst", stem),
+            _ => format!("{}.csv", stem),
+        }
+    }
+
+    fn writer<W: Write>(&self, sink: W) -> CsvWriter<W> {
+        CsvWriter::new(sink)
+            .with_separator(self.separator)
+            .with_quote_style(self.quote_style)
+            .with_null_value(self.null_value.as_str().into())
+            .with_date_format(self.date_format.as_deref().map(Into::into))
+    }
+
+    /// The header line written for DataFrames with the columns of `df`.
+    fn header(&self, df: &DataFrame) -> PolarsResult<Vec<u8>> {
+        let mut header = Vec::new();
+        self.writer(&mut header)
+            .include_header(true)
+            .finish(&mut df.clear())?;
+        Ok(header)
+    }
+}
+
+/// Parse a quote style: "necessary", "always", "non_numeric" or "never".
+pub fn parse_quote_style(s: &str) -> Result<QuoteStyle, String> {
+    match s.to_lowercase().replace('-', "_").as_str() {
+        "necessary" => Ok(QuoteStyle::Necessary),
+        "always" => Ok(QuoteStyle::Always),
+        "non_numeric" => Ok(QuoteStyle::NonNumeric),
+        "never" => Ok(QuoteStyle::Never),
+        other => Err(format!(
+            "invalid quote style '{}', expected 'necessary', 'always', 'non_numeric' or 'never'",
+            other
+        )),
+    }
+}
+
+/// Parse a field separator: a single ASCII character, or `\t` / "tab".
+pub fn parse_separator(s: &str) -> Result<u8, String> {
+    match s {
+        "\\t" | "tab" => Ok(b'\t'),
+        _ if s.len() == 1 && s.is_ascii() => Ok(s.as_bytes()[0]),
+        _ => Err(format!(
+            "invalid separator '{}', expected a single ASCII character",
+            s
+        )),
+    }
+}
+
+/// `Duration` columns as `HH:MM` text, which CSV has no type for. The end of
+/// the day stays `24:00`.
+pub(crate) fn format_durations(mut df: DataFrame) -> PolarsResult<DataFrame> {
+    let names: Vec<PlSmallStr> = df
+        .schema()
+        .iter()
+        .filter(|(_, dtype)| matches!(dtype, DataType::Duration(_)))
+        .map(|(name, _)| name.clone())
+        .collect();
+    for name in names {
+        let durations = df.column(&name)?.duration()?;
+        let per_minute = match durations.time_unit() {
+            TimeUnit::Nanoseconds => 60_000_000_000,
+            TimeUnit::Microseconds => 60_000_000,
+            TimeUnit::Milliseconds => 60_000,
+        };
+        let text: StringChunked = durations
+            .physical()
+            .iter()
+            .map(|value| {
+                value.map(|value| {
+                    let minutes = value / per_minute;
+                    format!("{:02}:{:02}", minutes / 60, minutes % 60)
+                })
+            })
+            .collect();
+        df.replace(&name, text.with_name(name.clone()).into_column())?;
+    }
+    Ok(df)
+}
+
+/// The compressed or plain stream of a CSV file.
+enum CsvSink {
+    Plain(File),
+    Gzip(GzEncoder<File>),
+    Zstd(zstd::Encoder<'static, File>),
+}
+
+impl CsvSink {
+    fn new(file: File, compression: Compression) -> io::Result<Self> {
+        match compression {
+            Compression::None => Ok(CsvSink::Plain(file)),
+            Compression::Gzip => Ok(CsvSink::Gzip(GzEncoder::new(
+                file,
+                flate2::Compression::default(),
+            ))),
+            Compression::Zstd => Ok(CsvSink::Zstd(zstd::Encoder::new(file, 0)?)),
+            other => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!(
+                    "{} compression is not supported for CSV output",
+                    other.as_str()
+                ),
+            )),
+        }
+    }
+
+    /// Write the end of the compressed stream.
+    fn finish(self) -> io::Result<()> {
+        match self {
+            CsvSink::Plain(mut file) => file.flush(),
+            CsvSink::Gzip(encoder) => encoder.finish().map(|_| ()),
+            CsvSink::Zstd(encoder) => encoder.finish().map(|_| ()),
+        }
+    }
+}
+
+impl Write for CsvSink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            CsvSink::Plain(file) => file.write(buf),
+            CsvSink::Gzip(encoder) => encoder.write(buf),
+            CsvSink::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            CsvSink::Plain(file) => file.flush(),
+            CsvSink::Gzip(encoder) => encoder.flush(),
+            CsvSink::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// A CSV file open for writing DataFrames of one set of columns.
+pub(crate) struct CsvFileWriter {
+    sink: CsvSink,
+    file_path: String,
+    options: CsvOptions,
+    header: HeaderState,
+}
+
+enum HeaderState {
+    /// A new or empty file: write the header with the first DataFrame.
+    Write,
+    /// The first line of the file appended to, checked against the first DataFrame.
+    Check(Vec<u8>),
+    Done,
+}
+
+impl CsvFileWriter {
+    /// Open `file_path` as `mode` says, with the format of `options`.
+    ///
+    /// # Errors
+    /// Fails if `mode` is [`WriteMode::FailIfExists`] and the file exists,
+    /// or if it is [`WriteMode::Append`] and the file has another compression.
+    pub(crate) fn open(
+        file_path: &str,
+        mode: WriteMode,
+        options: &CsvOptions,
+    ) -> PolarsResult<Self> {
+        let io_error = |e: io::Error| PolarsError::IO {
+            error: Arc::from(e),
+            msg: Some(format!("Unable to open file {}", file_path).into()),
+        };
+
+        let header = match mode {
+            WriteMode::Append => match read_first_line(file_path, options.compression)? {
+                Some(line) => HeaderState::Check(line),
+                None => HeaderState::Write,
+            },
+            _ => HeaderState::Write,
+        };
+        let file = match mode {
+            WriteMode::FailIfExists => OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(file_path),
+            WriteMode::Overwrite => File::create(file_path),
+            WriteMode::Append => OpenOptions::new().create(true).append(true).open(file_path),
+        }
+        .map_err(io_error)?;
+
+        Ok(Self {
+            sink: CsvSink::new(file, options.compression).map_err(io_error)?,
+            file_path: file_path.to_string(),
+            options: options.clone(),
+            header,
+        })
+    }
+
+    /// Append the rows of `df`, after the header if the file is new.
+    ///
+    /// # Errors
+    /// Fails if the file is appended to and its header differs from the
+    /// columns of `df`.
+    pub(crate) fn write(&mut self, df: &mut DataFrame) -> PolarsResult<()> {
+        let include_header = match std::mem::replace(&mut self.header, HeaderState::Done) {
+            HeaderState::Write => true,
+            HeaderState::Check(existing) => {
+                if existing != self.options.header(df)? {
+                    return Err(PolarsError::SchemaMismatch(
+                        format!(
+                            "cannot append to {}: its header {:?} does not match the output columns",
+                            self.file_path,
+                            String::from_utf8_lossy(&existing).trim_end()
+                        )
+                        .into(),
+                    ));
+                }
+                false
+            }
+            HeaderState::Done => false,
+        };
+        self.options
+            .writer(&mut self.sink)
+            .include_header(include_header)
+            .finish(df)
+    }
+
+    /// Finish the compressed stream and close the file.
+    pub(crate) fn finish(self) -> PolarsResult<()> {
+        let file_path = self.file_path;
+        self.sink.finish().map_err(|e| PolarsError::IO {
+            error: Arc::from(e),
+            msg: Some(format!("Unable to write file {}", file_path).into()),
+        })
+    }
+}
+
+/// The first line of the CSV file at `file_path`, with its line break, or
+/// `None` if the file does not exist or is empty.
+fn read_first_line(file_path: &str, compression: Compression) -> PolarsResult<Option<Vec<u8>>> {
+    if !Path::new(file_path).exists() {
+        return Ok(None);
+    }
+    let io_error = |e: io::Error| PolarsError::IO {
+        error: Arc::from(e),
+        msg: Some(format!("Unable to read file {}", file_path).into()),
+    };
+
+    let mut reader = BufReader::new(File::open(file_path).map_err(io_error)?);
+    let magic = reader.fill_buf().map_err(io_error)?;
+    if magic.is_empty() {
+        return Ok(None);
+    }
+    let existing = Compression::detect(magic);
+    if existing != compression {
+        return Err(PolarsError::InvalidOperation(
+            format!(
+                "cannot append {} compressed rows to {}, which is {} compressed",
+                compression.as_str(),
+                file_path,
+                existing.as_str()
+            )
+            .into(),
+        ));
+    }
+
+    let mut line = Vec::new();
+    decompress(reader, 8 * 1024)
+        .and_then(|mut reader| reader.read_until(b'\n', &mut line))
+        .map_err(io_error)?;
+    Ok((!line.is_empty()).then_some(line))
+}
@@ -0,0 +1,398 @@
+//! Data Element Identifiers (DEIs) of segment data records (type 4).
+//!
+//! A segment data record carries one data element: a three-digit DEI saying
+//! what the free-text `data` field holds, for the leg or segment between its
+//! board and off points. [`dei_info`] looks up the name and level of the
+//! common DEIs and [`decode_dei`] turns their data into a [`DeiValue`];
+//! [`add_dei_columns`] does both for a segments DataFrame.
+
+use crate::records::segment_records::SegmentRecords;
+use crate::utils::ssim_expand::required_column;
+use crate::utils::ssim_fields::{FieldError, parse_number};
+use polars::prelude::*;
+use std::fmt;
+
+/// Name of the DEI name column added by [`add_dei_columns`].
+pub const DEI_NAME_COLUMN: &str = "dei_name";
+/// Name of the DEI level column added by [`add_dei_columns`].
+pub const DEI_LEVEL_COLUMN: &str = "dei_level";
+/// Name of the decoded values column added by [`add_dei_columns`].
+pub const DEI_VALUES_COLUMN: &str = "dei_values";
+
+/// What a data element applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeiLevel {
+    /// The whole flight, all of its legs.
+    Flight,
+    /// One flight leg, from its departure to its arrival station.
+    Leg,
+    /// A segment between any board and off point of the flight.
+    Segment,
+}
+
+impl DeiLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeiLevel::Flight => "flight",
+            DeiLevel::Leg => "leg",
+            DeiLevel::Segment => "segment",
+        }
+    }
+}
+
+/// A registered Data Element Identifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeiInfo {
+    pub code: u16,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub level: DeiLevel,
+}
+
+const fn dei(code: u16, name: &'static str, description: &'static str, level: DeiLevel) -> DeiInfo {
+    DeiInfo {
+        code,
+        name,
+        description,
+        level,
+    }
+}
+
+/// The DEIs that [`decode_dei`] decodes, ordered by code.
+pub const DEI_REGISTRY: &[DeiInfo] = &[
+    dei(
+        10,
+        "Code Share - Commercial Duplicate",
+        "Marketing flight designators that duplicate this operating leg",
+        DeiLevel::Leg,
+    ),
+    dei(
+        50,
+        "Duplicate Leg Cross Reference",
+        "Flight designator of the operating flight of this duplicate leg",
+        DeiLevel::Leg,
+    ),
+    dei(
+        113,
+        "Passenger Terminal - Departure",
+        "Departure terminal at the board point",
+        DeiLevel::Segment,
+    ),
+    dei(
+        114,
+        "Passenger Terminal - Arrival",
+        "Arrival terminal at the off point",
+        DeiLevel::Segment,
+    ),
+    dei(
+        127,
+        "Operating Airline Disclosure",
+        "Free text naming the airline that operates the leg",
+        DeiLevel::Leg,
+    ),
+    dei(
+        170,
+        "Traffic Restriction Code - Passengers",
+        "Traffic restriction codes applicable to passengers",
+        DeiLevel::Segment,
+    ),
+    dei(
+        171,
+        "Traffic Restriction Code - Cargo and Mail",
+        "Traffic restriction codes applicable to cargo and mail",
+        DeiLevel::Segment,
+    ),
+    dei(
+        172,
+        "Traffic Restriction Code - Cargo",
+        "Traffic restriction codes applicable to cargo",
+        DeiLevel::Segment,
+    ),
+    dei(
+        173,
+        "Traffic Restriction Code - Mail",
+        "Traffic restriction codes applicable to mail",
+        DeiLevel::Segment,
+    ),
+    dei(
+        501,
+        "Meal Service Note",
+        "Meal service codes per reservations booking designator",
+        DeiLevel::Segment,
+    ),
+    dei(
+        503,
+        "In-Flight Service Information",
+        "Numeric in-flight service codes",
+        DeiLevel::Segment,
+    ),
+    dei(
+        505,
+        "Electronic Ticketing Information",
+        "Whether the flight is an electronic ticketing candidate",
+        DeiLevel::Flight,
+    ),
+];
+
+/// The registry entry of `code`, if it is a registered DEI.
+pub fn dei_info(code: u16) -> Option<&'static DeiInfo> {
+    DEI_REGISTRY
+        .binary_search_by_key(&code, |info| info.code)
+        .ok()
+        .map(|i| &DEI_REGISTRY[i])
+}
+
+/// A flight designator in the data of a DEI, such as `KL 2562`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FlightReference {
+    pub airline_designator: String,
+    pub flight_number: u16,
+    pub operational_suffix: Option<char>,
+}
+
+impl fmt::Display for FlightReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:<3}{}", self.airline_designator, self.flight_number)?;
+        if let Some(suffix) = self.operational_suffix {
+            write!(f, "{}", suffix)?;
+        }
+        Ok(())
+    }
+}
+
+/// The traffic a restriction of DEIs 170-173 applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrafficType {
+    Passengers,
+    CargoAndMail,
+    Cargo,
+    Mail,
+}
+
+/// A meal service code for one reservations booking designator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MealService {
+    pub booking_designator: char,
+    pub meal_code: char,
+}
+
+/// The decoded data of a registered DEI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeiValue {
+    /// DEI 10: the marketing flights duplicating the leg. DEI 50: the operating flight.
+    Flights(Vec<FlightReference>),
+    /// DEIs 113 and 114.
+    Terminal(String),
+    /// DEI 127.
+    OperatingAirline(String),
+    /// DEIs 170-173.
+    TrafficRestriction {
+        applies_to: TrafficType,
+        codes: Vec<char>,
+    },
+    /// DEI 501.
+    Meals(Vec<MealService>),
+    /// DEI 503.
+    InFlightServices(Vec<u8>),
+    /// DEI 505: `ET` for a candidate, `EN` for a flight that is not.
+    ElectronicTicketing(bool),
+}
+
+impl DeiValue {
+    /// The decoded items as text, e.g. `["KL 2562", "AF 1234"]` for DEI 10.
+    pub fn items(&self) -> Vec<String> {
+        match self {
+            DeiValue::Flights(flights) => flights.iter().map(ToString::to_string).collect(),
+            DeiValue::Terminal(text) | DeiValue::OperatingAirline(text) => vec![text.clone()],
+            DeiValue::TrafficRestriction { codes, .. } => {
+                codes.iter().map(ToString::to_string).collect()
+            }
+            DeiValue::Meals(meals) => meals
+                .iter()
+                .map(|meal| format!("{}:{}", meal.booking_designator, meal.meal_code))
+                .collect(),
+            DeiValue::InFlightServices(codes) => codes.iter().map(ToString::to_string).collect(),
+            DeiValue::ElectronicTicketing(candidate) => {
+                vec![if *candidate { "ET" } else { "EN" }.to_string()]
+            }
+        }
+    }
+}
+
+/// Parse a three-digit DEI such as `050`.
+pub fn parse_dei(value: &str) -> Result<u16, FieldError> {
+    parse_number("data_element_identifier", value)
+}
+
+/// Decode the `data` of a segment data record with DEI `code`.
+///
+/// Returns `None` for DEIs without a decoder, i.e. those not in
+/// [`DEI_REGISTRY`], and for blank data.
+///
+/// # Errors
+/// Returns a [`FieldError`] on `data` if it does not have the format of the DEI.
+pub fn decode_dei(code: u16, data: &str) -> Result<Option<DeiValue>, FieldError> {
+    let data = data.trim();
+    if data.is_empty() {
+        return Ok(None);
+    }
+    let error = |reason| FieldError {
+        field: "data",
+        value: data.to_string(),
+        reason,
+    };
+
+    let value = match code {
+        10 | 50 => DeiValue::Flights(
+            data.split('/')
+                .map(|designator| {
+                    parse_flight_reference(designator.trim())
+                        .ok_or_else(|| error("expected flight designators such as 'KL 2562'"))
+                })
+                .collect::<Result<_, _>>()?,
+        ),
+        113 | 114 => DeiValue::Terminal(data.to_string()),
+        127 => DeiValue::OperatingAirline(data.to_string()),
+        170..=173 => {
+            let codes: Vec<char> = data.chars().filter(|c| *c != ' ').collect();
+            if !codes.iter().all(char::is_ascii_uppercase) {
+                return Err(error("expected traffic restriction code letters"));
+            }
+            let applies_to = match code {
+                170 => TrafficType::Passengers,
+                171 => TrafficType::CargoAndMail,
+                172 => TrafficType::Cargo,
+                _ => TrafficType::Mail,
+            };
+            DeiValue::TrafficRestriction { applies_to, codes }
+        }
+        501 => {
+            let chars: Vec<char> = data.chars().filter(|c| *c != ' ').collect();
+            if !chars.len().is_multiple_of(2) || !chars.iter().all(char::is_ascii_alphanumeric) {
+                return Err(error("expected booking designator and meal code pairs"));
+            }
+            DeiValue::Meals(
+                chars
+                    .chunks(2)
+                    .map(|pair| MealService {
+                        booking_designator: pair[0],
+                        meal_code: pair[1],
+                    })
+                    .collect(),
+            )
+        }
+        503 => DeiValue::InFlightServices(
+            data.split('/')
+                .map(|code| {
+                    code.trim()
+                        .parse::<u8>()
+                        .map_err(|_| error("expected in-flight service numbers such as '9/11'"))
+                })
+                .collect::<Result<_, _>>()?,
+        ),
+        505 => match data {
+            "ET" => DeiValue::ElectronicTicketing(true),
+            "EN" => DeiValue::ElectronicTicketing(false),
+            _ => return Err(error("expected 'ET' or 'EN'")),
+        },
+        _ => return Ok(None),
+    };
+    Ok(Some(value))
+}
+
+/// `KL 2562`, `KL2562`, `U2A 12` or `KL 2562A`: a two- or three-character
+/// airline designator, a flight number of up to four digits and an optional
+/// operational suffix.
+fn parse_flight_reference(designator: &str) -> Option<FlightReference> {
+    if !designator.is_ascii() || designator.len() < 3 {
+        return None;
+    }
+    let airline_len = match designator.as_bytes()[2] {
+        b if b.is_ascii_uppercase() => 3,
+        _ => 2,
+    };
+    let (airline, rest) = designator.split_at(airline_len);
+    let rest = rest.trim_start();
+    let (number, suffix) = match rest.as_bytes().last() {
+        Some(b) if b.is_ascii_uppercase() => (&rest[..rest.len() - 1], Some(*b as char)),
+        _ => (rest, None),
+    };
+    if !airline.bytes().all(|b| b.is_ascii_alphanumeric())
+        || number.is_empty()
+        || number.len() > 4
+        || !number.bytes().all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    Some(FlightReference {
+        airline_designator: airline.to_string(),
+        flight_number: number.parse().ok()?,
+        operational_suffix: suffix,
+    })
+}
+
+impl SegmentRecords<'_> {
+    /// The registry entry of the record's DEI.
+    pub fn dei_info(&self) -> Option<&'static DeiInfo> {
+        parse_dei(&self.data_element_identifier)
+            .ok()
+            .and_then(dei_info)
+    }
+
+    /// The record's data decoded for its DEI, see [`decode_dei`].
+    pub fn decode_data(&self) -> Result<Option<DeiValue>, FieldError> {
+        decode_dei(parse_dei(&self.data_element_identifier)?, &self.data)
+    }
+}
+
+/// Add the registry name and level and the decoded data of every segment
+/// data record of `segments`.
+///
+/// `segments` needs the `data_element_identifier` and `data` columns, such
+/// as the segments of [`ssim_to_dataframes`](crate::ssim_to_dataframes) or
+/// the flat DataFrame of [`ssim_to_dataframe`](crate::ssim_to_dataframe).
+/// Three columns are added, null for unregistered or non-numeric DEIs:
+///
+/// * `dei_name` — the name of the DEI, e.g. `Duplicate Leg Cross Reference`
+/// * `dei_level` — `flight`, `leg` or `segment`
+/// * `dei_values` — the decoded items as a list of strings, see
+///   [`DeiValue::items`]; also null for data that does not decode
+///
+/// # Errors
+/// Returns a `ColumnNotFound` error if a required column is missing.
+pub fn add_dei_columns(segments: &DataFrame) -> PolarsResult<DataFrame> {
+    const TO: &str = "to decode segment data";
+    let codes =
+        required_column(segments, "data_element_identifier", TO)?.cast(&DataType::String)?;
+    let data = required_column(segments, "data", TO)?.cast(&DataType::String)?;
+
+    let mut names = Vec::with_capacity(segments.height());
+    let mut levels = Vec::with_capacity(segments.height());
+    let mut values = ListStringChunkedBuilder::new(
+        DEI_VALUES_COLUMN.into(),
+        segments.height(),
+        segments.height(),
+    );
+    for (code, data) in codes.str()?.iter().zip(data.str()?.iter()) {
+        let code = match code.map(str::trim) {
+            Some(code) if !code.is_empty() => parse_dei(code).ok(),
+            _ => None,
+        };
+        let info = code.and_then(dei_info);
+        names.push(info.map(|info| info.name));
+        levels.push(info.map(|info| info.level.as_str()));
+        match code
+            .zip(data)
+            .and_then(|(code, data)| decode_dei(code, data).ok().flatten())
+        {
+            Some(value) => values.append_values_iter(value.items().iter().map(String::as_str)),
+            None => values.append_null(),
+        }
+    }
+
+    let mut decoded = segments.clone();
+    decoded.with_column(Column::new(DEI_NAME_COLUMN.into(), names))?;
+    decoded.with_column(Column::new(DEI_LEVEL_COLUMN.into(), levels))?;
+    decoded.with_column(values.finish().into_column())?;
+    Ok(decoded)
+}
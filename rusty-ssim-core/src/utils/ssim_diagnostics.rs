@@ -0,0 +1,233 @@
+//! Feed-quality diagnostics collected while reading an SSIM file.
+//!
+//! In [`ParseMode::Strict`] the reader stops at the first malformed line and
+//! returns the corresponding [`SsimError`]. In [`ParseMode::Lenient`] it skips
+//! the line, counts it under its [`IssueKind`] and keeps the first few
+//! offending lines in [`Diagnostics::issues`] so callers can alert on feed
+//! quality without losing the rest of the schedule.
+//!
+//! Structural inconsistencies that do not stop a line from being parsed, such
+//! as a trailer whose check reference does not match its carrier block, a
+//! break in the record serial numbers, an over-long line or a segment without
+//! its flight leg, are recorded as warnings in both modes.
+
+use crate::utils::ssim_error::{RecordLocation, SsimError};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Default number of offending lines kept in [`Diagnostics::issues`].
+pub const DEFAULT_MAX_REPORTED_ISSUES: usize = 100;
+
+/// How the reader reacts to malformed lines.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Fail on the first malformed line.
+    #[default]
+    Strict,
+    /// Skip malformed lines and record them in [`Diagnostics`].
+    Lenient,
+}
+
+impl FromStr for ParseMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "strict" => Ok(ParseMode::Strict),
+            "lenient" => Ok(ParseMode::Lenient),
+            other => Err(format!(
+                "invalid parse mode '{}', expected 'strict' or 'lenient'",
+                other
+            )),
+        }
+    }
+}
+
+/// Category of a skipped or malformed line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IssueKind {
+    TruncatedRecord,
+    InvalidRecordType,
+    InvalidUtf8,
+    MissingCarrierContext,
+    /// A trailer's serial number check reference differs from the serial number
+    /// of the record before it.
+    TrailerCheckMismatch,
+    /// A trailer's continuation/end code is not `E` or `C`, or disagrees with
+    /// whether another carrier follows.
+    TrailerEndCodeMismatch,
+    /// A record serial number skips ahead of the previous record's.
+    SerialNumberGap,
+    /// A record repeats the previous record's serial number.
+    SerialNumberDuplicate,
+    /// A record serial number goes back below the previous record's.
+    SerialNumberReset,
+    /// A line is longer than the fixed SSIM record length.
+    InvalidLineLength,
+    /// A segment data record does not follow a flight leg of its flight with
+    /// the same leg sequence number.
+    OrphanSegment,
+}
+
+impl IssueKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IssueKind::TruncatedRecord => "truncated_record",
+            IssueKind::InvalidRecordType => "invalid_record_type",
+            IssueKind::InvalidUtf8 => "invalid_utf8",
+            IssueKind::MissingCarrierContext => "missing_carrier_context",
+            IssueKind::TrailerCheckMismatch => "trailer_check_mismatch",
+            IssueKind::TrailerEndCodeMismatch => "trailer_end_code_mismatch",
+            IssueKind::SerialNumberGap => "serial_number_gap",
+            IssueKind::SerialNumberDuplicate => "serial_number_duplicate",
+            IssueKind::SerialNumberReset => "serial_number_reset",
+            IssueKind::InvalidLineLength => "invalid_line_length",
+            IssueKind::OrphanSegment => "orphan_segment",
+        }
+    }
+
+    /// Category of a record-level error, or `None` for I/O and Polars errors
+    /// which can never be skipped.
+    pub fn of(error: &SsimError) -> Option<Self> {
+        match error {
+            SsimError::TruncatedRecord { .. } => Some(IssueKind::TruncatedRecord),
+            SsimError::InvalidRecordType { .. } => Some(IssueKind::InvalidRecordType),
+            SsimError::InvalidUtf8 { .. } => Some(IssueKind::InvalidUtf8),
+            SsimError::MissingCarrierContext { .. } => Some(IssueKind::MissingCarrierContext),
+            SsimError::Io { .. } | SsimError::Polars(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for IssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A single offending line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub kind: IssueKind,
+    pub location: RecordLocation,
+    pub reason: String,
+}
+
+/// Summary of the lines read and the problems found in an SSIM input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostics {
+    /// Total number of lines read, including filler and header lines.
+    pub lines_read: usize,
+    /// Number of offending lines per category.
+    pub counts: BTreeMap<IssueKind, usize>,
+    /// The first `max_reported_issues` offending lines, in file order.
+    pub issues: Vec<Issue>,
+    max_reported_issues: usize,
+}
+
+impl Diagnostics {
+    pub fn new(max_reported_issues: usize) -> Self {
+        Self {
+            lines_read: 0,
+            counts: BTreeMap::new(),
+            issues: Vec::new(),
+            max_reported_issues,
+        }
+    }
+
+    /// Record a record-level error. Returns the error back if it cannot be skipped.
+    pub(crate) fn record(&mut self, error: SsimError) -> Result<(), SsimError> {
+        let Some(kind) = IssueKind::of(&error) else {
+            return Err(error);
+        };
+
+        let location = error.location().cloned().unwrap_or_default();
+        self.warn(kind, location, error.to_string());
+        Ok(())
+    }
+
+    /// Record a problem that does not prevent the line from being read.
+    pub(crate) fn warn(&mut self, kind: IssueKind, location: RecordLocation, reason: String) {
+        *self.counts.entry(kind).or_insert(0) += 1;
+        if self.issues.len() < self.max_reported_issues {
+            self.issues.push(Issue {
+                kind,
+                location,
+                reason,
+            });
+        }
+    }
+
+    /// Record a problem found at `line_number` after later lines were already
+    /// recorded, keeping the reported issues in file order.
+    pub(crate) fn insert_warning(
+        &mut self,
+        kind: IssueKind,
+        location: RecordLocation,
+        reason: String,
+        line_number: usize,
+    ) {
+        *self.counts.entry(kind).or_insert(0) += 1;
+        let index = self
+            .issues
+            .partition_point(|issue| issue.location.line_number <= line_number);
+        self.issues.insert(
+            index,
+            Issue {
+                kind,
+                location,
+                reason,
+            },
+        );
+        self.issues.truncate(self.max_reported_issues);
+    }
+
+    /// Append the diagnostics of the input read after this one.
+    pub(crate) fn merge(&mut self, other: Diagnostics) {
+        self.lines_read += other.lines_read;
+        for (kind, count) in other.counts {
+            *self.counts.entry(kind).or_insert(0) += count;
+        }
+        self.issues.extend(other.issues);
+        self.issues.truncate(self.max_reported_issues);
+    }
+
+    /// Number of offending lines in a category.
+    pub fn count(&self, kind: IssueKind) -> usize {
+        self.counts.get(&kind).copied().unwrap_or(0)
+    }
+
+    /// Total number of offending lines across all categories.
+    pub fn total_issues(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.counts.is_empty()
+    }
+}
+
+impl Default for Diagnostics {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_REPORTED_ISSUES)
+    }
+}
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} lines read, {} issue(s)",
+            self.lines_read,
+            self.total_issues()
+        )?;
+        for (kind, count) in &self.counts {
+            write!(f, "\n  {}: {}", kind, count)?;
+        }
+        for issue in &self.issues {
+            write!(f, "\n  - {}", issue.reason)?;
+        }
+        Ok(())
+    }
+}
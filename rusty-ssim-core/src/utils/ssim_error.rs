@@ -0,0 +1,142 @@
+//! Error types returned by the SSIM reader and the public `ssim_to_*` functions.
+//!
+//! Every error raised while reading a record carries a [`RecordLocation`] so
+//! callers can point straight at the offending line of the source file.
+
+use polars::prelude::PolarsError;
+use std::fmt;
+
+/// Position of a record inside the SSIM input.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecordLocation {
+    /// Path of the SSIM file, if the input came from a file.
+    pub file_path: Option<String>,
+    /// 1-based line number.
+    pub line_number: usize,
+    /// Byte offset of the start of the line.
+    pub byte_offset: u64,
+    /// Record serial number (columns 195-200), if the line is long enough to carry one.
+    pub record_serial_number: Option<String>,
+}
+
+impl fmt::Display for RecordLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.file_path {
+            Some(path) => write!(f, "{}:{}", path, self.line_number)?,
+            None => write!(f, "line {}", self.line_number)?,
+        }
+        write!(f, " (byte offset {}", self.byte_offset)?;
+        if let Some(serial) = &self.record_serial_number {
+            write!(f, ", record serial number {}", serial)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Errors produced while reading and converting an SSIM file.
+#[derive(Debug)]
+pub enum SsimError {
+    /// A record is shorter than the fixed SSIM record length.
+    TruncatedRecord {
+        location: RecordLocation,
+        record_type: char,
+        length: usize,
+    },
+    /// A line starts with a character that is not a known record type.
+    InvalidRecordType {
+        location: RecordLocation,
+        found: char,
+    },
+    /// A line is not valid UTF-8, or a multi-byte character straddles a field boundary.
+    InvalidUtf8 {
+        location: RecordLocation,
+        /// 1-based column at which the problem was found.
+        column: usize,
+    },
+    /// A flight leg or segment record appears before any carrier (type 2) record.
+    MissingCarrierContext {
+        location: RecordLocation,
+        record_type: char,
+    },
+    /// The input could not be opened or read.
+    Io {
+        file_path: Option<String>,
+        source: std::io::Error,
+    },
+    /// Building or writing the output DataFrames failed.
+    Polars(PolarsError),
+}
+
+/// Result alias used throughout the reader.
+pub type SsimResult<T> = Result<T, SsimError>;
+
+impl SsimError {
+    /// Location of the offending record, for errors tied to a specific line.
+    pub fn location(&self) -> Option<&RecordLocation> {
+        match self {
+            SsimError::TruncatedRecord { location, .. }
+            | SsimError::InvalidRecordType { location, .. }
+            | SsimError::InvalidUtf8 { location, .. }
+            | SsimError::MissingCarrierContext { location, .. } => Some(location),
+            SsimError::Io { .. } | SsimError::Polars(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for SsimError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SsimError::TruncatedRecord {
+                location,
+                record_type,
+                length,
+            } => write!(
+                f,
+                "truncated record type {} at {}: expected {} bytes, found {}",
+                record_type,
+                location,
+                crate::utils::ssim_parser::RECORD_LENGTH,
+                length
+            ),
+            SsimError::InvalidRecordType { location, found } => {
+                write!(f, "invalid record type {:?} at {}", found, location)
+            }
+            SsimError::InvalidUtf8 { location, column } => {
+                write!(f, "invalid UTF-8 at column {} at {}", column, location)
+            }
+            SsimError::MissingCarrierContext {
+                location,
+                record_type,
+            } => write!(
+                f,
+                "record type {} at {} appears before any carrier (type 2) record",
+                record_type, location
+            ),
+            SsimError::Io {
+                file_path: Some(path),
+                source,
+            } => write!(f, "I/O error reading {}: {}", path, source),
+            SsimError::Io {
+                file_path: None,
+                source,
+            } => write!(f, "I/O error: {}", source),
+            SsimError::Polars(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for SsimError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SsimError::Io { source, .. } => Some(source),
+            SsimError::Polars(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<PolarsError> for SsimError {
+    fn from(e: PolarsError) -> Self {
+        SsimError::Polars(e)
+    }
+}
@@ -0,0 +1,245 @@
+//! Expanding flight leg patterns into dated flight legs.
+//!
+//! A flight leg record is a pattern: the leg operates on the `days_of_operation`
+//! between `period_of_operation_from` and `period_of_operation_to`, every week
+//! or, with a `frequency_rate` of `2`, every other week. [`expand_to_dated_flights`]
+//! turns each pattern into one row per date it operates on.
+//!
+//! The period and days of operation of every leg of a flight are in terms of
+//! the flight date, the local date of the flight's first departure. A leg
+//! that departs after midnight carries a `date_variation`, e.g. `12` for a
+//! departure one day and an arrival two days after the flight date, so all
+//! the legs of one flight share the same `flight_date` in the output.
+
+use crate::utils::ssim_fields::{DaysOfWeek, FieldError, parse_date, parse_date_variation};
+use chrono::{Datelike, Days, NaiveDate, TimeDelta};
+use polars::prelude::*;
+
+/// Name of the operating date column added by [`expand_to_dated_flights`].
+pub const FLIGHT_DATE_COLUMN: &str = "flight_date";
+/// Name of the leg departure date column added by [`expand_to_dated_flights`].
+pub const DEPARTURE_DATE_COLUMN: &str = "departure_date";
+/// Name of the leg arrival date column added by [`expand_to_dated_flights`].
+pub const ARRIVAL_DATE_COLUMN: &str = "arrival_date";
+
+const EXPAND: &str = "to expand flights into dates";
+
+/// Expand every flight leg of `flights` into one row per flight date it operates on.
+///
+/// `flights` needs the `period_of_operation_from`, `period_of_operation_to`
+/// and `days_of_operation` columns, as SSIM text or native dtypes, such as
+/// the flights of [`ssim_to_dataframes`](crate::ssim_to_dataframes). The
+/// optional `frequency_rate` and `date_variation` columns default to weekly
+/// and no variation. Each row is repeated for each date, with three `Date`
+/// columns added:
+///
+/// * `flight_date` — the date of the period of operation
+/// * `departure_date` — the local departure date of the leg, after its date variation
+/// * `arrival_date` — the local arrival date of the leg, after its date variation
+///
+/// A `frequency_rate` of `n` keeps every `n`th week, counted in Monday-based
+/// weeks from the week of `period_of_operation_from`. `date_from` and
+/// `date_to` bound the flight dates, both inclusive. Legs without an end
+/// date (`00XXX00`) need `date_to`.
+///
+/// # Errors
+/// Returns a `ColumnNotFound` error if a required column is missing, and a
+/// `ComputeError` naming the field for impossible values and open-ended
+/// periods without `date_to`.
+///
+/// # Example
+/// ```no_run
+/// use rusty_ssim_core::{expand_to_dated_flights, ssim_to_dataframes};
+/// use chrono::NaiveDate;
+///
+/// let (_, flights, _) = ssim_to_dataframes("schedule.ssim", None, None)?;
+/// let dated = expand_to_dated_flights(
+///     &flights,
+///     NaiveDate::from_ymd_opt(2025, 3, 30),
+///     NaiveDate::from_ymd_opt(2025, 4, 5),
+/// )?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn expand_to_dated_flights(
+    flights: &DataFrame,
+    date_from: Option<NaiveDate>,
+    date_to: Option<NaiveDate>,
+) -> PolarsResult<DataFrame> {
+    let period_from = date_values(flights, "period_of_operation_from")?;
+    let period_to = date_values(flights, "period_of_operation_to")?;
+    let days = days_values(flights)?;
+    let rates = frequency_rates(flights)?;
+    let variations = date_variations(flights)?;
+
+    let mut rows: Vec<IdxSize> = Vec::new();
+    let mut flight_dates: Vec<i32> = Vec::new();
+    let mut departure_dates: Vec<i32> = Vec::new();
+    let mut arrival_dates: Vec<i32> = Vec::new();
+    for row in 0..flights.height() {
+        let Some(from) = period_from[row] else {
+            return Err(field_error(FieldError {
+                field: "period_of_operation_from",
+                value: String::new(),
+                reason: "the period must have a start date",
+            }));
+        };
+        let to = match (period_to[row], date_to) {
+            (Some(to), Some(date_to)) => to.min(date_to),
+            (Some(to), None) => to,
+            (None, Some(date_to)) => date_to,
+            (None, None) => {
+                return Err(field_error(FieldError {
+                    field: "period_of_operation_to",
+                    value: "00XXX00".to_string(),
+                    reason: "an open-ended period needs the end of a date window",
+                }));
+            }
+        };
+        let start = date_from.map_or(from, |date_from| from.max(date_from));
+        let first_week = week_start(from);
+        let (departure_variation, arrival_variation) = variations[row];
+
+        for date in start.iter_days().take_while(|date| *date <= to) {
+            let week = (week_start(date) - first_week).num_days() / 7;
+            if !days[row].contains(date.weekday()) || week % rates[row] != 0 {
+                continue;
+            }
+            rows.push(row as IdxSize);
+            flight_dates.push(epoch_days(date));
+            departure_dates.push(epoch_days(date) + departure_variation as i32);
+            arrival_dates.push(epoch_days(date) + arrival_variation as i32);
+        }
+    }
+
+    let mut dated = flights.take(&IdxCa::from_vec(PlSmallStr::EMPTY, rows))?;
+    for (name, dates) in [
+        (FLIGHT_DATE_COLUMN, flight_dates),
+        (DEPARTURE_DATE_COLUMN, departure_dates),
+        (ARRIVAL_DATE_COLUMN, arrival_dates),
+    ] {
+        let dates = Int32Chunked::from_vec(name.into(), dates).into_date();
+        dated.with_column(dates.into_series().into_column())?;
+    }
+    Ok(dated)
+}
+
+pub(crate) fn field_error(e: FieldError) -> PolarsError {
+    PolarsError::ComputeError(e.to_string().into())
+}
+
+/// The column `name` of `df`, or a `ColumnNotFound` error saying it is needed `to` do something.
+pub(crate) fn required_column<'a>(
+    df: &'a DataFrame,
+    name: &str,
+    to: &str,
+) -> PolarsResult<&'a Column> {
+    df.column(name)
+        .map_err(|_| PolarsError::ColumnNotFound(format!("'{}' is needed {}", name, to).into()))
+}
+
+/// The Monday of the week of `date`.
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Days::new(date.weekday().num_days_from_monday() as u64)
+}
+
+fn epoch() -> NaiveDate {
+    NaiveDate::from_ymd_opt(1970, 1, 1).expect("valid date")
+}
+
+/// `date` as the days since 1970-01-01 of a Polars `Date`.
+fn epoch_days(date: NaiveDate) -> i32 {
+    (date - epoch()).num_days() as i32
+}
+
+/// The dates of a `Date` or `DDMMMYY` text column; blanks and `00XXX00` are `None`.
+fn date_values(df: &DataFrame, name: &'static str) -> PolarsResult<Vec<Option<NaiveDate>>> {
+    let column = required_column(df, name, EXPAND)?;
+    if column.dtype() == &DataType::Date {
+        return Ok(column
+            .date()?
+            .physical()
+            .iter()
+            .map(|days| {
+                days.and_then(|days| epoch().checked_add_signed(TimeDelta::days(days.into())))
+            })
+            .collect());
+    }
+    column
+        .str()?
+        .iter()
+        .map(|value| match value.map(str::trim) {
+            Some(value) if !value.is_empty() => parse_date(name, value),
+            _ => Ok(None),
+        })
+        .collect::<Result<_, _>>()
+        .map_err(field_error)
+}
+
+/// The days of operation of a `UInt8` bitmask or SSIM text column.
+fn days_values(df: &DataFrame) -> PolarsResult<Vec<DaysOfWeek>> {
+    let column = required_column(df, "days_of_operation", EXPAND)?;
+    if column.dtype() == &DataType::UInt8 {
+        return Ok(column
+            .u8()?
+            .iter()
+            .map(|bits| DaysOfWeek::from_bits(bits.unwrap_or(0)))
+            .collect());
+    }
+    column
+        .str()?
+        .iter()
+        .map(|value| DaysOfWeek::parse("days_of_operation", value.unwrap_or("       ")))
+        .collect::<Result<_, _>>()
+        .map_err(field_error)
+}
+
+/// The week interval of each leg: 1 for blank frequency rates or no column.
+fn frequency_rates(df: &DataFrame) -> PolarsResult<Vec<i64>> {
+    let Ok(column) = df.column("frequency_rate") else {
+        return Ok(vec![1; df.height()]);
+    };
+    column
+        .cast(&DataType::String)?
+        .str()?
+        .iter()
+        .map(|value| match value.map(str::trim) {
+            None | Some("") => Ok(1),
+            Some(value) => match value.parse::<i64>() {
+                Ok(rate) if rate > 0 => Ok(rate),
+                _ => Err(FieldError {
+                    field: "frequency_rate",
+                    value: value.to_string(),
+                    reason: "expected a number of weeks",
+                }),
+            },
+        })
+        .collect::<Result<_, _>>()
+        .map_err(field_error)
+}
+
+/// The departure and arrival date variations of each leg: `(0, 0)` for blanks or no column.
+pub(crate) fn date_variations(df: &DataFrame) -> PolarsResult<Vec<(i8, i8)>> {
+    let Ok(column) = df.column("date_variation") else {
+        return Ok(vec![(0, 0); df.height()]);
+    };
+    column
+        .str()?
+        .iter()
+        .map(|value| match value {
+            Some(value) if value.len() == 2 && value.is_ascii() => {
+                let (departure, arrival) = value.split_at(1);
+                Ok((
+                    parse_date_variation("date_variation", departure)?,
+                    parse_date_variation("date_variation", arrival)?,
+                ))
+            }
+            Some(value) if !value.trim().is_empty() => Err(FieldError {
+                field: "date_variation",
+                value: value.to_string(),
+                reason: "expected 2 characters",
+            }),
+            _ => Ok((0, 0)),
+        })
+        .collect::<Result<_, _>>()
+        .map_err(field_error)
+}
@@ -1,36 +1,219 @@
+use crate::utils::ssim_csv::{CsvFileWriter, CsvOptions, format_durations};
+use crate::utils::ssim_metadata::SsimFileMetadata;
 use polars::frame::DataFrame;
+use polars::io::ipc;
+use polars::io::parquet::write::BatchedWriter;
+use polars::prelude::CompatLevel;
+use polars::prelude::IpcCompression;
+use polars::prelude::IpcWriter;
 use polars::prelude::ParquetCompression;
 use polars::prelude::ParquetWriter;
 use polars::prelude::PolarsResult;
+use polars::prelude::PolarsError;
+use polars::prelude::SchemaExt;
+use polars::prelude::SerWriter;
+use polars_arrow::io::ipc::write::default_ipc_fields;
 use std::fs::File;
+use std::sync::Arc;
+
+/// Rows per Parquet row group, unless set otherwise (Polars' default).
+pub const DEFAULT_ROW_GROUP_SIZE: usize = 512 * 512;
 
 /// Writes a Polars DataFrame to a Parquet file with the specified compression.
 ///
+/// The rusty-ssim version and schema version are written to the file's
+/// key-value metadata; see [`to_parquet_with_metadata`] to record the source too.
+///
 /// # Arguments
 /// * `dataframe` - The DataFrame to write.
 /// * `file_path` - The output file path.
 /// * `compression` - Compression type: \"snappy\", \"gzip\", \"lz4\", \"zstd\", or \"uncompressed\".
 ///
 /// # Errors
-/// Returns a `PolarsResult<()>` if writing fails.
+/// Returns an error if the file cannot be created or writing fails.
 pub fn to_parquet(
     dataframe: &mut DataFrame,
     file_path: &str,
     compression: &str,
 ) -> PolarsResult<()> {
-    let mut file = File::create(file_path).expect("could not create file");
+    to_parquet_with_metadata(dataframe, file_path, compression, &SsimFileMetadata::new())
+}
+
+/// Same as [`to_parquet`], writing `metadata` to the file's key-value metadata.
+///
+/// # Errors
+/// Returns an error if the file cannot be created or writing fails.
+pub fn to_parquet_with_metadata(
+    dataframe: &mut DataFrame,
+    file_path: &str,
+    compression: &str,
+    metadata: &SsimFileMetadata,
+) -> PolarsResult<()> {
+    let mut file = create_file(file_path)?;
+
+    ParquetWriter::new(&mut file)
+        .with_compression(parquet_compression(compression))
+        .with_key_value_metadata(Some(metadata.to_parquet_metadata()))
+        .finish(dataframe)
+        .map(|_| ())
+}
+
+/// Writes a Polars DataFrame to a CSV file with the format, compression and
+/// write mode of `options`. `per_carrier` is ignored; `Duration` columns are
+/// written as `HH:MM`.
+///
+/// # Errors
+/// Returns an error if the file cannot be written, exists with
+/// [`WriteMode::FailIfExists`](crate::WriteMode::FailIfExists), or has
+/// another header when appended to.
+pub fn to_csv(dataframe: &DataFrame, file_path: &str, options: &CsvOptions) -> PolarsResult<()> {
+    let mut writer = CsvFileWriter::open(file_path, options.write_mode, options)?;
+    writer.write(&mut format_durations(dataframe.clone())?)?;
+    writer.finish()
+}
+
+/// Create `file_path`, replacing the file if it exists.
+pub(crate) fn create_file(file_path: &str) -> PolarsResult<File> {
+    File::create(file_path).map_err(|e| PolarsError::IO {
+        error: Arc::from(e),
+        msg: Some(format!("Unable to create file {}", file_path).into()),
+    })
+}
 
-    let compression = match compression.to_lowercase().as_str() {
+fn parquet_compression(compression: &str) -> ParquetCompression {
+    match compression.to_lowercase().as_str() {
         "snappy" => ParquetCompression::Snappy,
         "gzip" => ParquetCompression::Gzip(None),
         "lz4" => ParquetCompression::Lz4Raw,
         "zstd" => ParquetCompression::Zstd(None),
         "uncompressed" | "none" => ParquetCompression::Uncompressed,
         _ => ParquetCompression::Uncompressed,
-    };
+    }
+}
 
-    ParquetWriter::new(&mut file)
-        .with_compression(compression)
-        .finish(dataframe)
-        .map(|_| ())
+fn ipc_compression(compression: &str) -> Option<IpcCompression> {
+    match compression.to_lowercase().as_str() {
+        "lz4" => Some(IpcCompression::LZ4),
+        "zstd" => Some(IpcCompression::ZSTD(Default::default())),
+        _ => None,
+    }
+}
+
+/// An Arrow IPC (Feather v2) file open for appending DataFrames of one schema.
+///
+/// The file is created up front, and its writer with the schema of the first
+/// DataFrame, as an IPC file can hold only one schema.
+pub(crate) struct IpcFileWriter {
+    file: Option<File>,
+    writer: Option<ipc::BatchedWriter<File>>,
+    compression: Option<IpcCompression>,
+}
+
+impl IpcFileWriter {
+    /// Create `file_path`, compressed with `compression` ("lz4" or "zstd";
+    /// anything else is uncompressed).
+    pub(crate) fn create(file_path: &str, compression: &str) -> PolarsResult<Self> {
+        Ok(Self {
+            file: Some(create_file(file_path)?),
+            writer: None,
+            compression: ipc_compression(compression),
+        })
+    }
+
+    /// Append `df` as one record batch per chunk.
+    pub(crate) fn write(&mut self, mut df: DataFrame) -> PolarsResult<()> {
+        df.align_chunks_par();
+        if let Some(file) = self.file.take() {
+            let schema = df.schema().to_arrow(CompatLevel::newest());
+            let ipc_fields = default_ipc_fields(schema.iter_values());
+            self.writer = Some(
+                IpcWriter::new(file)
+                    .with_compression(self.compression)
+                    .batched(df.schema(), ipc_fields)?,
+            );
+        }
+        match &mut self.writer {
+            Some(writer) => writer.write_batch(&df),
+            None => Ok(()),
+        }
+    }
+
+    /// Write the footer, or an empty IPC file if nothing was written.
+    pub(crate) fn finish(&mut self) -> PolarsResult<()> {
+        if let Some(file) = self.file.take() {
+            return IpcWriter::new(file)
+                .with_compression(self.compression)
+                .finish(&mut DataFrame::empty());
+        }
+        match self.writer.take() {
+            Some(mut writer) => writer.finish(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A Parquet file open for appending DataFrames of one schema.
+///
+/// Rows are buffered until a full row group of `row_group_size` rows can be
+/// written, so at most one row group and the last DataFrame are held in memory.
+pub(crate) struct ParquetFileWriter {
+    writer: BatchedWriter<File>,
+    pending: Option<DataFrame>,
+    row_group_size: usize,
+}
+
+impl ParquetFileWriter {
+    /// Create `file_path` for DataFrames with the schema of `first`, with
+    /// `metadata` in its footer.
+    pub(crate) fn create(
+        file_path: &str,
+        compression: &str,
+        row_group_size: usize,
+        metadata: &SsimFileMetadata,
+        first: &DataFrame,
+    ) -> PolarsResult<Self> {
+        let writer = ParquetWriter::new(create_file(file_path)?)
+            .with_compression(parquet_compression(compression))
+            .with_row_group_size(Some(row_group_size))
+            .with_key_value_metadata(Some(metadata.to_parquet_metadata()))
+            .batched(first.schema())?;
+        Ok(Self {
+            writer,
+            pending: None,
+            row_group_size: row_group_size.max(1),
+        })
+    }
+
+    /// Append `df`, writing every row group it completes.
+    pub(crate) fn write(&mut self, df: DataFrame) -> PolarsResult<()> {
+        let mut pending = match self.pending.take() {
+            Some(mut pending) => {
+                pending.vstack_mut_owned(df)?;
+                pending
+            }
+            None => df,
+        };
+        while pending.height() >= self.row_group_size {
+            self.write_row_group(pending.slice(0, self.row_group_size))?;
+            pending = pending.slice(self.row_group_size as i64, pending.height());
+        }
+        self.pending = Some(pending);
+        Ok(())
+    }
+
+    /// Write the remaining rows as a last, smaller row group and close the file.
+    pub(crate) fn finish(mut self) -> PolarsResult<()> {
+        if let Some(pending) = self.pending.take()
+            && pending.height() > 0
+        {
+            self.write_row_group(pending)?;
+        }
+        self.writer.finish().map(|_| ())
+    }
+
+    fn write_row_group(&mut self, mut row_group: DataFrame) -> PolarsResult<()> {
+        // The batched writer writes a row group per chunk.
+        row_group.rechunk_mut_par();
+        self.writer.write_batch(&row_group)
+    }
 }
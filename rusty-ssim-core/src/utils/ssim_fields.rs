@@ -0,0 +1,223 @@
+//! Typed values for the fixed-width SSIM fields.
+//!
+//! The parser keeps every field as the raw text from the line. The helpers here
+//! turn that text into dates, minutes and day-of-week sets, rejecting values
+//! that cannot occur in a valid schedule (e.g. `32JAN25` or `2460`).
+
+use chrono::{NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A field value that cannot be converted to its typed form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    /// Name of the field, as in the record structs.
+    pub field: &'static str,
+    /// The raw field text.
+    pub value: String,
+    pub reason: &'static str,
+}
+
+impl FieldError {
+    fn new(field: &'static str, value: &str, reason: &'static str) -> Self {
+        Self {
+            field,
+            value: value.to_string(),
+            reason,
+        }
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid {} {:?}: {}",
+            self.field, self.value, self.reason
+        )
+    }
+}
+
+impl std::error::Error for FieldError {}
+
+/// Days of the week on which a flight operates, as a bitset (bit 0 = Monday).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct DaysOfWeek(u8);
+
+impl DaysOfWeek {
+    pub const EMPTY: DaysOfWeek = DaysOfWeek(0);
+    pub const ALL: DaysOfWeek = DaysOfWeek(0b111_1111);
+
+    /// Build from a bitset where bit 0 is Monday; bits above Sunday are ignored.
+    pub fn from_bits(bits: u8) -> Self {
+        DaysOfWeek(bits & Self::ALL.0)
+    }
+
+    pub fn bits(&self) -> u8 {
+        self.0
+    }
+
+    pub fn contains(&self, day: Weekday) -> bool {
+        self.0 & (1 << day.num_days_from_monday()) != 0
+    }
+
+    pub fn insert(&mut self, day: Weekday) {
+        self.0 |= 1 << day.num_days_from_monday();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Number of operating days.
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Operating days from Monday to Sunday.
+    pub fn iter(&self) -> impl Iterator<Item = Weekday> + '_ {
+        (0..7u8)
+            .filter(|i| self.0 & (1 << i) != 0)
+            .filter_map(|i| Weekday::try_from(i).ok())
+    }
+
+    /// Parse the 7-character SSIM form, e.g. `"1 3 5  "`: position `n` holds
+    /// the digit `n` (Monday = 1) or a space.
+    pub fn parse(field: &'static str, value: &str) -> Result<Self, FieldError> {
+        if value.len() != 7 {
+            return Err(FieldError::new(field, value, "expected 7 characters"));
+        }
+
+        let mut days = DaysOfWeek::EMPTY;
+        for (i, c) in value.bytes().enumerate() {
+            match c {
+                b' ' => {}
+                c if c == b'1' + i as u8 => days.0 |= 1 << i,
+                _ => {
+                    return Err(FieldError::new(
+                        field,
+                        value,
+                        "each position must hold its day number or a space",
+                    ));
+                }
+            }
+        }
+        Ok(days)
+    }
+}
+
+impl fmt::Display for DaysOfWeek {
+    /// Formats back to the SSIM form, e.g. `1 3 5  `.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for i in 0..7u8 {
+            let c = if self.0 & (1 << i) != 0 {
+                (b'1' + i) as char
+            } else {
+                ' '
+            };
+            write!(f, "{}", c)?;
+        }
+        Ok(())
+    }
+}
+
+const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+
+/// Parse a `DDMMMYY` date such as `01JAN25`. Two-digit years are in the 2000s.
+///
+/// Returns `None` for `00XXX00`, which SSIM uses for "until further notice".
+pub fn parse_date(field: &'static str, value: &str) -> Result<Option<NaiveDate>, FieldError> {
+    if value == "00XXX00" {
+        return Ok(None);
+    }
+    if value.len() != 7 || !value.is_ascii() {
+        return Err(FieldError::new(field, value, "expected a DDMMMYY date"));
+    }
+
+    let day = parse_digits(&value[0..2]);
+    let month = MONTHS.iter().position(|m| *m == &value[2..5]);
+    let year = parse_digits(&value[5..7]);
+    match (day, month, year) {
+        (Some(day), Some(month), Some(year)) => {
+            NaiveDate::from_ymd_opt(2000 + year as i32, month as u32 + 1, day)
+                .map(Some)
+                .ok_or_else(|| FieldError::new(field, value, "no such calendar date"))
+        }
+        _ => Err(FieldError::new(field, value, "expected a DDMMMYY date")),
+    }
+}
+
+/// Parse an `HHMM` local time into minutes since midnight.
+///
+/// `2400` is accepted as the end of the day (1440 minutes).
+pub fn parse_time(field: &'static str, value: &str) -> Result<u16, FieldError> {
+    let (hours, minutes) =
+        parse_hhmm(value).ok_or_else(|| FieldError::new(field, value, "expected an HHMM time"))?;
+    if minutes >= 60 || hours > 24 || (hours == 24 && minutes != 0) {
+        return Err(FieldError::new(field, value, "time out of range"));
+    }
+    Ok((hours * 60 + minutes) as u16)
+}
+
+/// Parse a `±HHMM` UTC time variation into signed minutes.
+pub fn parse_utc_offset(field: &'static str, value: &str) -> Result<i16, FieldError> {
+    let sign = match value.as_bytes().first() {
+        Some(b'+') => 1,
+        Some(b'-') => -1,
+        _ => {
+            return Err(FieldError::new(field, value, "expected a ±HHMM offset"));
+        }
+    };
+    let (hours, minutes) = parse_hhmm(&value[1..])
+        .ok_or_else(|| FieldError::new(field, value, "expected a ±HHMM offset"))?;
+    if minutes >= 60 || hours > 14 {
+        return Err(FieldError::new(field, value, "offset out of range"));
+    }
+    Ok(sign * (hours * 60 + minutes) as i16)
+}
+
+/// Parse a date variation character: blank or `0` is the same day, `1`-`9`
+/// are days later and `A` is the day before.
+pub fn parse_date_variation(field: &'static str, value: &str) -> Result<i8, FieldError> {
+    match value {
+        " " | "0" => Ok(0),
+        "A" => Ok(-1),
+        v if v.len() == 1 && v.as_bytes()[0].is_ascii_digit() => Ok((v.as_bytes()[0] - b'0') as i8),
+        _ => Err(FieldError::new(
+            field,
+            value,
+            "expected a digit, 'A' or a space",
+        )),
+    }
+}
+
+/// Parse a zero- or space-padded unsigned number.
+pub fn parse_number<T: TryFrom<u32>>(field: &'static str, value: &str) -> Result<T, FieldError> {
+    let trimmed = value.trim_start_matches(' ');
+    parse_digits(trimmed)
+        .and_then(|n| T::try_from(n).ok())
+        .ok_or_else(|| FieldError::new(field, value, "expected a number"))
+}
+
+/// `None` for a blank field, otherwise the trimmed text.
+pub fn optional_text(value: &str) -> Option<String> {
+    let trimmed = value.trim();
+    (!trimmed.is_empty()).then(|| trimmed.to_string())
+}
+
+fn parse_hhmm(value: &str) -> Option<(u32, u32)> {
+    if value.len() != 4 || !value.is_ascii() {
+        return None;
+    }
+    Some((parse_digits(&value[0..2])?, parse_digits(&value[2..4])?))
+}
+
+fn parse_digits(value: &str) -> Option<u32> {
+    if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    value.parse().ok()
+}
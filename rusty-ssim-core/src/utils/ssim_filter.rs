@@ -0,0 +1,194 @@
+//! Predicate pushdown for the SSIM readers.
+//!
+//! An [`SsimFilter`] is evaluated on the raw, validated record lines before
+//! they are parsed. Carrier blocks (type 2 up to the type 5 trailer) of other
+//! airlines are skipped as a whole; flight legs (type 3) are matched on their
+//! own fields, and segment records (type 4) are kept when a leg of the same
+//! flight and itinerary variation was kept.
+
+use crate::utils::ssim_fields::parse_date;
+use chrono::NaiveDate;
+
+/// Which carrier blocks and flight legs to read.
+///
+/// Every criterion left empty matches everything; the criteria that are set
+/// must all match. Codes are compared without their trailing padding, so an
+/// airline is given as `"XX"` and not `"XX "`.
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use rusty_ssim_core::SsimFilter;
+///
+/// let filter = SsimFilter::new()
+///     .with_airlines(["XX"])
+///     .with_departure_stations(["KEF"])
+///     .with_date_from(NaiveDate::from_ymd_opt(2018, 4, 1).unwrap());
+/// assert!(!filter.is_all());
+/// assert!(SsimFilter::default().is_all());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SsimFilter {
+    airlines: Vec<String>,
+    departure_stations: Vec<String>,
+    arrival_stations: Vec<String>,
+    date_from: Option<NaiveDate>,
+    date_to: Option<NaiveDate>,
+    service_types: Vec<String>,
+    aircraft_types: Vec<String>,
+}
+
+impl SsimFilter {
+    /// A filter that keeps every record.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only read the carrier blocks and flight legs of these airline designators.
+    pub fn with_airlines<I, S>(mut self, airlines: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.airlines = codes(airlines);
+        self
+    }
+
+    /// Only keep flight legs departing from these stations.
+    pub fn with_departure_stations<I, S>(mut self, stations: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.departure_stations = codes(stations);
+        self
+    }
+
+    /// Only keep flight legs arriving at these stations.
+    pub fn with_arrival_stations<I, S>(mut self, stations: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.arrival_stations = codes(stations);
+        self
+    }
+
+    /// Only keep flight legs whose period of operation ends on or after `date`.
+    pub fn with_date_from(mut self, date: NaiveDate) -> Self {
+        self.date_from = Some(date);
+        self
+    }
+
+    /// Only keep flight legs whose period of operation starts on or before `date`.
+    pub fn with_date_to(mut self, date: NaiveDate) -> Self {
+        self.date_to = Some(date);
+        self
+    }
+
+    /// Only keep flight legs of these service types (e.g. `J`, `C`, `F`).
+    pub fn with_service_types<I, S>(mut self, service_types: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.service_types = codes(service_types);
+        self
+    }
+
+    /// Only keep flight legs flown by these aircraft types (e.g. `73H`).
+    pub fn with_aircraft_types<I, S>(mut self, aircraft_types: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.aircraft_types = codes(aircraft_types);
+        self
+    }
+
+    /// Whether the filter keeps every record.
+    pub fn is_all(&self) -> bool {
+        self.airlines.is_empty() && !self.filters_legs()
+    }
+
+    /// Whether any criterion beyond the airline applies to flight legs, so
+    /// segments have to be matched against the kept legs.
+    pub(crate) fn filters_legs(&self) -> bool {
+        !self.departure_stations.is_empty()
+            || !self.arrival_stations.is_empty()
+            || self.date_from.is_some()
+            || self.date_to.is_some()
+            || !self.service_types.is_empty()
+            || !self.aircraft_types.is_empty()
+    }
+
+    /// Whether the carrier block opened by this carrier record (type 2) is read.
+    pub(crate) fn matches_carrier(&self, line: &str) -> bool {
+        matches(&self.airlines, &line[2..5])
+    }
+
+    /// Whether this flight leg record (type 3) is kept.
+    ///
+    /// A leg whose period of operation cannot be parsed does not match a date window.
+    pub(crate) fn matches_flight_leg(&self, line: &str) -> bool {
+        if !matches(&self.airlines, &line[2..5]) {
+            return false;
+        }
+        if !self.filters_legs() {
+            return true;
+        }
+
+        matches(&self.service_types, &line[13..14])
+            && matches(&self.departure_stations, &line[36..39])
+            && matches(&self.arrival_stations, &line[54..57])
+            && matches(&self.aircraft_types, &line[72..75])
+            && self.overlaps_period(&line[14..21], &line[21..28])
+    }
+
+    /// Whether the period of operation `from`..=`to` overlaps the date window.
+    fn overlaps_period(&self, from: &str, to: &str) -> bool {
+        if self.date_from.is_none() && self.date_to.is_none() {
+            return true;
+        }
+        let (Ok(from), Ok(to)) = (
+            parse_date("period_of_operation_from", from),
+            parse_date("period_of_operation_to", to),
+        ) else {
+            return false;
+        };
+
+        // `None` is 00XXX00, an open-ended period.
+        let starts_in_time = match (from, self.date_to) {
+            (Some(from), Some(window_to)) => from <= window_to,
+            _ => true,
+        };
+        let ends_in_time = match (to, self.date_from) {
+            (Some(to), Some(window_from)) => to >= window_from,
+            _ => true,
+        };
+        starts_in_time && ends_in_time
+    }
+}
+
+/// The flight a flight leg or segment record belongs to: operational suffix,
+/// airline designator, flight number and itinerary variation identifier.
+pub(crate) fn flight_key(line: &str) -> [u8; 10] {
+    let mut key = [0; 10];
+    key.copy_from_slice(&line.as_bytes()[1..11]);
+    key
+}
+
+fn codes<I, S>(values: I) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    values
+        .into_iter()
+        .map(|value| value.as_ref().trim().to_ascii_uppercase())
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+fn matches(codes: &[String], field: &str) -> bool {
+    codes.is_empty() || codes.iter().any(|code| code == field.trim_end())
+}
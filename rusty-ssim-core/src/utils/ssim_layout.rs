@@ -0,0 +1,222 @@
+//! Output file layouts for the Parquet writer.
+//!
+//! By default every carrier block is written to a file of its own. A
+//! [`ParquetLayout`] can instead gather the whole input into a single file, or
+//! spread it over a Hive-style directory tree partitioned by column values,
+//! such as `airline_designator=XX/season=S18/ssim.parquet`.
+
+use crate::converters::ssim_polars::DATE_COLUMNS;
+use crate::utils::ssim_columns::is_known_column;
+use crate::utils::ssim_fields::parse_date;
+use polars::prelude::*;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Directory value of rows with a null or blank partition value, as in Hive.
+pub const HIVE_DEFAULT_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+
+/// Hive partition files the Parquet writer keeps open at once by default.
+pub const DEFAULT_MAX_OPEN_PARTITIONS: usize = 64;
+
+/// How the Parquet writer lays out its output files.
+///
+/// ```
+/// use rusty_ssim_core::{ParquetLayout, PartitionKey};
+///
+/// let layout = ParquetLayout::hive(["airline_designator", "period_of_operation_from:month"])
+///     .unwrap();
+/// assert_eq!(
+///     layout,
+///     ParquetLayout::Hive(vec![
+///         PartitionKey::Column("airline_designator".to_string()),
+///         PartitionKey::Month("period_of_operation_from".to_string()),
+///     ])
+/// );
+/// assert!(ParquetLayout::hive(["no_such_column"]).is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ParquetLayout {
+    /// One file per carrier block, named after its airline designator and
+    /// control duplicate indicator, e.g. `ssim_XX_.parquet`.
+    #[default]
+    PerCarrier,
+    /// One `ssim.parquet` file for the whole input.
+    SingleFile,
+    /// An `ssim.parquet` file per partition, in nested `key=value`
+    /// directories in the order of the keys.
+    Hive(Vec<PartitionKey>),
+}
+
+impl ParquetLayout {
+    /// A Hive layout partitioned by `keys`, each a column name or
+    /// `column:month` (see [`PartitionKey`]).
+    ///
+    /// # Errors
+    /// Returns a message naming the first invalid key, or an error if no key is given.
+    pub fn hive<I, S>(keys: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let keys = keys
+            .into_iter()
+            .map(|key| key.as_ref().parse())
+            .collect::<Result<Vec<PartitionKey>, _>>()?;
+        if keys.is_empty() {
+            return Err("no partition keys given".to_string());
+        }
+        Ok(Self::Hive(keys))
+    }
+
+    /// Whether each Hive partition only holds the rows of one airline.
+    pub(crate) fn partitions_by_airline(&self) -> bool {
+        matches!(self, ParquetLayout::Hive(keys)
+            if keys.contains(&PartitionKey::Column("airline_designator".to_string())))
+    }
+}
+
+/// A Hive partition key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PartitionKey {
+    /// The value of a column, e.g. `season=S18`. The column is left out of
+    /// the files, as its value is in their path.
+    Column(String),
+    /// The `YYYY-MM` month of a date column, e.g.
+    /// `period_of_operation_from_month=2018-03`. The column itself is kept.
+    Month(String),
+}
+
+impl PartitionKey {
+    /// The directory name of the key, before the `=`.
+    pub fn name(&self) -> String {
+        match self {
+            PartitionKey::Column(column) => column.clone(),
+            PartitionKey::Month(column) => format!("{}_month", column),
+        }
+    }
+
+    /// The value of the key for each row of `df`.
+    fn values(&self, df: &DataFrame) -> PolarsResult<StringChunked> {
+        let (PartitionKey::Column(name) | PartitionKey::Month(name)) = self;
+        let column = df.column(name).map_err(|_| {
+            PolarsError::ColumnNotFound(
+                format!("partition column '{}' is not in the output", name).into(),
+            )
+        })?;
+        let values = column.cast(&DataType::String)?;
+        let values = values.str()?;
+        match self {
+            PartitionKey::Column(_) => Ok(values.clone()),
+            // Native dtypes give `YYYY-MM-DD`, SSIM text `DDMMMYY`.
+            PartitionKey::Month(_) if column.dtype() == &DataType::Date => {
+                Ok(values.apply(|value| value.map(|value| value[..7].to_string().into())))
+            }
+            PartitionKey::Month(_) => values
+                .iter()
+                .map(|value| match value {
+                    Some(value) if !value.trim().is_empty() => parse_date("partition month", value)
+                        .map(|date| date.map(|date| date.format("%Y-%m").to_string())),
+                    _ => Ok(None),
+                })
+                .collect::<Result<StringChunked, _>>()
+                .map_err(|e| PolarsError::ComputeError(e.to_string().into())),
+        }
+    }
+}
+
+impl FromStr for PartitionKey {
+    type Err = String;
+
+    /// Parse a column name, or `column:month` for the month of a date column.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (column, month) = match s.split_once(':') {
+            Some((column, "month")) => (column.trim(), true),
+            Some((_, granularity)) => {
+                return Err(format!(
+                    "unknown partition granularity '{}', expected 'month'",
+                    granularity
+                ));
+            }
+            None => (s, false),
+        };
+        if !is_known_column(column) || column == "segment_data" {
+            return Err(format!("unknown partition column '{}'", column));
+        }
+        if month {
+            if !DATE_COLUMNS.contains(&column) {
+                return Err(format!("'{}' is not a date column", column));
+            }
+            Ok(PartitionKey::Month(column.to_string()))
+        } else {
+            Ok(PartitionKey::Column(column.to_string()))
+        }
+    }
+}
+
+/// Split `df` by the partition directory of each row, e.g.
+/// `airline_designator=XX/season=S18`, in order of first appearance.
+///
+/// The key columns are dropped from the parts.
+pub(crate) fn partition(
+    df: &DataFrame,
+    keys: &[PartitionKey],
+) -> PolarsResult<Vec<(String, DataFrame)>> {
+    let values = keys
+        .iter()
+        .map(|key| key.values(df))
+        .collect::<PolarsResult<Vec<_>>>()?;
+    let names: Vec<String> = keys.iter().map(PartitionKey::name).collect();
+
+    let mut groups: Vec<(String, Vec<IdxSize>)> = Vec::new();
+    let mut group_of: HashMap<String, usize> = HashMap::new();
+    let mut rows: Vec<_> = values.iter().map(|values| values.iter()).collect();
+    let mut directory = String::new();
+    for row in 0..df.height() {
+        directory.clear();
+        for (name, values) in names.iter().zip(&mut rows) {
+            if !directory.is_empty() {
+                directory.push('/');
+            }
+            directory.push_str(name);
+            directory.push('=');
+            match values.next().flatten().map(str::trim) {
+                Some(value) if !value.is_empty() => escape_path_name(&mut directory, value),
+                _ => directory.push_str(HIVE_DEFAULT_PARTITION),
+            }
+        }
+        let group = match group_of.get(&directory) {
+            Some(&group) => group,
+            None => {
+                groups.push((directory.clone(), Vec::new()));
+                group_of.insert(directory.clone(), groups.len() - 1);
+                groups.len() - 1
+            }
+        };
+        groups[group].1.push(row as IdxSize);
+    }
+
+    let data = df.drop_many(keys.iter().filter_map(|key| match key {
+        PartitionKey::Column(column) => Some(column.as_str()),
+        PartitionKey::Month(_) => None,
+    }));
+    groups
+        .into_iter()
+        .map(|(directory, rows)| {
+            let part = data.take(&IdxCa::from_vec(PlSmallStr::EMPTY, rows))?;
+            Ok((directory, part))
+        })
+        .collect()
+}
+
+/// Append `value` to a partition directory, percent-encoding the characters
+/// Hive escapes in path names.
+fn escape_path_name(directory: &mut String, value: &str) {
+    for c in value.chars() {
+        if c.is_ascii_control() || "\"#%'*/:=?\\{[]^".contains(c) {
+            directory.push_str(&format!("%{:02X}", c as u32));
+        } else {
+            directory.push(c);
+        }
+    }
+}
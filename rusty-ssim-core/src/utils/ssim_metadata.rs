@@ -0,0 +1,211 @@
+//! Provenance written into the file-level key-value metadata of Parquet output.
+//!
+//! Every Parquet file written by rusty-ssim records the version that wrote
+//! it, the version of its column layout and, when known, the source file,
+//! the header record and the carrier record it was read from. Downstream
+//! jobs can check lineage from the footer with [`read_parquet_metadata`] or
+//! any Parquet reader, without scanning rows. The keys all start with
+//! `rusty_ssim.`, e.g. `rusty_ssim.carrier.season`.
+
+use crate::records::carrier_record::CarrierRecord;
+use crate::records::header_record::HeaderRecord;
+use crate::utils::ssim_error::{SsimError, SsimResult};
+use polars::io::parquet::write::KeyValueMetadata;
+use polars::prelude::{ParquetReader, SerReader};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::Path;
+
+/// Prefix of every key written by rusty-ssim.
+pub const METADATA_KEY_PREFIX: &str = "rusty_ssim.";
+
+/// Version of the output columns, bumped whenever a column is renamed,
+/// removed or changes meaning.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// The rusty-ssim key-value metadata of a Parquet file.
+///
+/// ```
+/// use rusty_ssim_core::SsimFileMetadata;
+///
+/// let metadata = SsimFileMetadata::new().with_source_file("schedule.ssim");
+/// assert_eq!(metadata.rusty_ssim_version, env!("CARGO_PKG_VERSION"));
+/// assert_eq!(metadata.source_file.as_deref(), Some("schedule.ssim"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SsimFileMetadata {
+    /// Version of rusty-ssim that wrote the file (`rusty_ssim.version`).
+    pub rusty_ssim_version: String,
+    /// [`SCHEMA_VERSION`] of the file (`rusty_ssim.schema_version`).
+    pub schema_version: u32,
+    /// File name of the SSIM input (`rusty_ssim.source_file`). `None` for
+    /// streams and in-memory data.
+    pub source_file: Option<String>,
+    /// Header record (type 1) fields by column name, e.g. `title_of_contents`
+    /// (`rusty_ssim.header.*`).
+    pub header: BTreeMap<String, String>,
+    /// Carrier record (type 2) fields by column name, e.g. `season` and
+    /// `creation_date` (`rusty_ssim.carrier.*`). Only files holding a single
+    /// carrier block have them.
+    pub carrier: BTreeMap<String, String>,
+}
+
+impl Default for SsimFileMetadata {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SsimFileMetadata {
+    /// Metadata of this version of rusty-ssim, without any source.
+    pub fn new() -> Self {
+        Self {
+            rusty_ssim_version: env!("CARGO_PKG_VERSION").to_string(),
+            schema_version: SCHEMA_VERSION,
+            source_file: None,
+            header: BTreeMap::new(),
+            carrier: BTreeMap::new(),
+        }
+    }
+
+    /// Record `source_file` as the SSIM input.
+    pub fn with_source_file(mut self, source_file: impl Into<String>) -> Self {
+        self.source_file = Some(source_file.into());
+        self
+    }
+
+    /// Record the fields of the header record.
+    pub fn with_header(mut self, header: &HeaderRecord) -> Self {
+        self.header = [
+            ("title_of_contents", &header.title_of_contents),
+            ("number_of_seasons", &header.number_of_seasons),
+            ("data_set_serial_number", &header.data_set_serial_number),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value.clone()))
+        .collect();
+        self
+    }
+
+    /// Record the fields of the carrier record that describe the schedule.
+    pub fn with_carrier(mut self, carrier: &CarrierRecord) -> Self {
+        self.carrier = [
+            ("airline_designator", &carrier.airline_designator),
+            (
+                "control_duplicate_indicator",
+                &carrier.control_duplicate_indicator,
+            ),
+            ("time_mode", &carrier.time_mode),
+            ("season", &carrier.season),
+            (
+                "period_of_schedule_validity_from",
+                &carrier.period_of_schedule_validity_from,
+            ),
+            (
+                "period_of_schedule_validity_to",
+                &carrier.period_of_schedule_validity_to,
+            ),
+            ("creation_date", &carrier.creation_date),
+            ("creation_time", &carrier.creation_time),
+            ("title_of_data", &carrier.title_of_data),
+            ("release_date", &carrier.release_date),
+            ("schedule_status", &carrier.schedule_status),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value.clone()))
+        .collect();
+        self
+    }
+
+    /// The metadata as Parquet key-value pairs.
+    pub fn to_key_values(&self) -> Vec<(String, String)> {
+        let mut key_values = vec![
+            (key("version"), self.rusty_ssim_version.clone()),
+            (key("schema_version"), self.schema_version.to_string()),
+        ];
+        if let Some(source_file) = &self.source_file {
+            key_values.push((key("source_file"), source_file.clone()));
+        }
+        for (record, fields) in [("header", &self.header), ("carrier", &self.carrier)] {
+            key_values.extend(
+                fields
+                    .iter()
+                    .map(|(field, value)| (key(&format!("{}.{}", record, field)), value.clone())),
+            );
+        }
+        key_values
+    }
+
+    /// Read the metadata back from Parquet key-value pairs.
+    ///
+    /// Returns `None` if the pairs were not written by rusty-ssim.
+    pub fn from_key_values<'a, I>(key_values: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = (&'a str, &'a str)>,
+    {
+        let mut version = None;
+        let mut metadata = Self::new();
+        for (name, value) in key_values {
+            let Some(name) = name.strip_prefix(METADATA_KEY_PREFIX) else {
+                continue;
+            };
+            match name {
+                "version" => version = Some(value.to_string()),
+                // 0 marks a version that cannot be read.
+                "schema_version" => metadata.schema_version = value.parse().unwrap_or_default(),
+                "source_file" => metadata.source_file = Some(value.to_string()),
+                _ => {
+                    if let Some(field) = name.strip_prefix("header.") {
+                        metadata.header.insert(field.to_string(), value.to_string());
+                    } else if let Some(field) = name.strip_prefix("carrier.") {
+                        metadata
+                            .carrier
+                            .insert(field.to_string(), value.to_string());
+                    }
+                }
+            }
+        }
+        metadata.rusty_ssim_version = version?;
+        Some(metadata)
+    }
+
+    pub(crate) fn to_parquet_metadata(&self) -> KeyValueMetadata {
+        KeyValueMetadata::from_static(self.to_key_values())
+    }
+}
+
+/// `name` under [`METADATA_KEY_PREFIX`].
+fn key(name: &str) -> String {
+    format!("{}{}", METADATA_KEY_PREFIX, name)
+}
+
+/// The file name of `file_path`, as recorded in `rusty_ssim.source_file`.
+pub(crate) fn source_file_name(file_path: &str) -> String {
+    Path::new(file_path).file_name().map_or_else(
+        || file_path.to_string(),
+        |name| name.to_string_lossy().into_owned(),
+    )
+}
+
+/// Read the rusty-ssim metadata from the footer of the Parquet file at `file_path`.
+///
+/// Only the footer is read, not the rows. Returns `None` for Parquet files
+/// that were not written by rusty-ssim.
+///
+/// # Errors
+/// Returns an error if the file cannot be opened or is not a Parquet file.
+pub fn read_parquet_metadata(file_path: &str) -> SsimResult<Option<SsimFileMetadata>> {
+    let file = File::open(file_path).map_err(|source| SsimError::Io {
+        file_path: Some(file_path.to_string()),
+        source,
+    })?;
+    let mut reader = ParquetReader::new(file);
+    let metadata = reader.get_metadata()?;
+    Ok(metadata.key_value_metadata.as_ref().and_then(|key_values| {
+        SsimFileMetadata::from_key_values(
+            key_values
+                .iter()
+                .filter_map(|kv| Some((kv.key.as_str(), kv.value.as_deref()?))),
+        )
+    }))
+}
@@ -1,118 +1,459 @@
 pub use crate::records::carrier_record::CarrierRecord;
 pub use crate::records::flight_leg_records::FlightLegRecord;
+pub use crate::records::header_record::HeaderRecord;
 pub use crate::records::segment_records::SegmentRecords;
+pub use crate::records::trailer_record::TrailerRecord;
+use crate::utils::ssim_columns::{
+    ColumnSelection, FLIGHT_LEG_COLUMNS, SEGMENT_COLUMNS, column_bit,
+};
 use std::borrow::Cow;
 
+/// Fixed width of every SSIM record in bytes.
+pub const RECORD_LENGTH: usize = 200;
+
+/// Byte offsets at which the header record (type 1) is sliced into fields.
+const HEADER_FIELD_BOUNDARIES: &[usize] = &[0, 1, 35, 40, 41, 191, 194, 200];
+
+/// Byte offsets at which the carrier record (type 2) is sliced into fields.
+const CARRIER_FIELD_BOUNDARIES: &[usize] = &[
+    0, 1, 2, 5, 10, 13, 14, 21, 28, 35, 64, 71, 72, 107, 108, 169, 188, 190, 194, 200,
+];
+
+/// Byte offsets at which the flight leg record (type 3) is sliced into fields.
+const FLIGHT_LEG_FIELD_BOUNDARIES: &[usize] = &[
+    0, 1, 2, 5, 9, 11, 13, 14, 21, 28, 35, 36, 39, 43, 47, 52, 54, 57, 61, 65, 70, 72, 75, 95, 100,
+    110, 119, 120, 121, 122, 127, 128, 131, 134, 137, 140, 144, 145, 146, 147, 148, 149, 160, 161,
+    172, 192, 194, 200,
+];
+
+/// Byte offsets at which the segment data record (type 4) is sliced into fields.
+const SEGMENT_FIELD_BOUNDARIES: &[usize] = &[
+    0, 1, 2, 5, 9, 11, 13, 14, 27, 28, 29, 30, 33, 36, 39, 194, 200,
+];
+
+/// Byte offsets at which the trailer record (type 5) is sliced into fields.
+const TRAILER_FIELD_BOUNDARIES: &[usize] = &[0, 1, 2, 5, 12, 187, 193, 194, 200];
+
+/// `$value` if `$column` is set in `$mask`, otherwise an empty field.
+///
+/// The column name is resolved against `$columns` at compile time.
+macro_rules! projected {
+    ($mask:expr, $columns:expr, $column:literal, $value:expr) => {
+        if $mask & const { column_bit($columns, $column) } != 0 {
+            $value
+        } else {
+            Cow::Borrowed("")
+        }
+    };
+}
+
+/// Reasons a raw line cannot be safely sliced into fixed-width fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordDefect {
+    /// The line is shorter than [`RECORD_LENGTH`] bytes.
+    Truncated { length: usize },
+    /// A multi-byte character straddles the field boundary at this 1-based column.
+    InvalidUtf8Boundary { column: usize },
+}
+
+/// Checks that a record is long enough and that every field boundary used by
+/// its record type falls on a UTF-8 character boundary.
+///
+/// Lines that pass this check can be handed to the `parse_*` functions without
+/// any risk of an out-of-bounds or mid-character slice.
+pub fn check_record(line: &str) -> Result<(), RecordDefect> {
+    if line.len() < RECORD_LENGTH {
+        return Err(RecordDefect::Truncated { length: line.len() });
+    }
+
+    if line.is_ascii() {
+        return Ok(());
+    }
+
+    let boundaries = match line.as_bytes()[0] {
+        b'1' => HEADER_FIELD_BOUNDARIES,
+        b'2' => CARRIER_FIELD_BOUNDARIES,
+        b'3' => FLIGHT_LEG_FIELD_BOUNDARIES,
+        b'4' => SEGMENT_FIELD_BOUNDARIES,
+        b'5' => TRAILER_FIELD_BOUNDARIES,
+        _ => &[0, RECORD_LENGTH],
+    };
+
+    match boundaries.iter().find(|&&i| !line.is_char_boundary(i)) {
+        Some(&i) => Err(RecordDefect::InvalidUtf8Boundary { column: i + 1 }),
+        None => Ok(()),
+    }
+}
+
+/// Where a flight leg or segment column comes from in the raw line, for
+/// building columns straight from the lines without parsing records.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum LineField {
+    /// The bytes between these offsets, as they are.
+    Raw(usize, usize),
+    /// The bytes between these offsets without surrounding whitespace.
+    Trimmed(usize, usize),
+    /// The flight designator, see [`push_flight_designator`].
+    FlightDesignator,
+    /// The control duplicate indicator of the carrier.
+    ControlDuplicateIndicator,
+}
+
+/// Source of each of the [`FLIGHT_LEG_COLUMNS`], in the same order.
+///
+/// Must slice the same bytes as [`parse_flight_record_legs`].
+pub(crate) const FLIGHT_LEG_FIELDS: [LineField; FLIGHT_LEG_COLUMNS.len()] = {
+    use LineField::*;
+    [
+        FlightDesignator,
+        Raw(1, 2),
+        Raw(2, 5),
+        ControlDuplicateIndicator,
+        Raw(5, 9),
+        Raw(9, 11),
+        Raw(11, 13),
+        Raw(13, 14),
+        Raw(14, 21),
+        Raw(21, 28),
+        Raw(28, 35),
+        Raw(35, 36),
+        Raw(36, 39),
+        Raw(39, 43),
+        Raw(43, 47),
+        Raw(47, 52),
+        Raw(52, 54),
+        Raw(54, 57),
+        Raw(57, 61),
+        Raw(61, 65),
+        Raw(65, 70),
+        Raw(70, 72),
+        Raw(72, 75),
+        Trimmed(75, 95),
+        Raw(95, 100),
+        Raw(100, 110),
+        Raw(110, 119),
+        Raw(119, 120),
+        Raw(120, 121),
+        Raw(121, 122),
+        Raw(127, 128),
+        Trimmed(128, 131),
+        Trimmed(131, 134),
+        Trimmed(134, 137),
+        Trimmed(137, 146),
+        Raw(137, 140),
+        Raw(140, 144),
+        Raw(144, 145),
+        Raw(145, 146),
+        Raw(147, 148),
+        Raw(148, 149),
+        Raw(149, 160),
+        Raw(160, 161),
+        Raw(172, 192),
+        Raw(192, 194),
+        Raw(0, 1),
+        Raw(194, 200),
+    ]
+};
+
+/// Source of each of the [`SEGMENT_COLUMNS`], in the same order.
+///
+/// Must slice the same bytes as [`parse_segment_record`].
+pub(crate) const SEGMENT_FIELDS: [LineField; SEGMENT_COLUMNS.len()] = {
+    use LineField::*;
+    [
+        FlightDesignator,
+        Raw(1, 2),
+        Raw(2, 5),
+        ControlDuplicateIndicator,
+        Raw(5, 9),
+        Raw(9, 11),
+        Raw(11, 13),
+        Raw(13, 14),
+        Raw(27, 28),
+        Raw(28, 29),
+        Raw(29, 30),
+        Raw(30, 33),
+        Raw(33, 36),
+        Raw(36, 39),
+        Trimmed(39, 194),
+        Raw(0, 1),
+        Raw(194, 200),
+    ]
+};
+
+/// Append the flight designator of a flight leg or segment line to `out`:
+/// airline, duplicate indicator, flight number, operational suffix, itinerary
+/// variation and its overflow, e.g. `XX _   12  01  `.
+pub(crate) fn push_flight_designator(
+    out: &mut String,
+    line: &str,
+    control_duplicate_indicator: &str,
+) {
+    let field = |range: std::ops::Range<usize>| line.get(range).unwrap_or_default();
+    out.push_str(field(2..5));
+    out.push('_');
+    out.push_str(control_duplicate_indicator);
+    out.push_str(field(5..9));
+    out.push_str(field(1..2));
+    out.push(' ');
+    out.push_str(field(9..11));
+    out.push(' ');
+    out.push_str(field(127..128));
+}
+
+/// The flight designators of a batch of flight leg or segment lines, built
+/// into one buffer that is reused from batch to batch instead of allocating
+/// a `String` per record.
+#[derive(Debug, Default)]
+pub(crate) struct FlightDesignators {
+    text: String,
+    ends: Vec<usize>,
+}
+
+impl FlightDesignators {
+    /// Replace the designators with those of `lines`.
+    pub(crate) fn fill<L: AsRef<str>>(&mut self, lines: &[L], control_duplicate_indicator: &str) {
+        self.text.clear();
+        self.ends.clear();
+        for line in lines {
+            push_flight_designator(&mut self.text, line.as_ref(), control_duplicate_indicator);
+            self.ends.push(self.text.len());
+        }
+    }
+
+    /// The designator of the line at `index`.
+    pub(crate) fn get(&self, index: usize) -> &str {
+        let start = index.checked_sub(1).map_or(0, |i| self.ends[i]);
+        &self.text[start..self.ends[index]]
+    }
+}
+
+/// Parse a flight leg record (type 3).
+///
+/// Fields outside `columns` are left empty without being sliced or trimmed;
+/// the join keys are always parsed.
 pub fn parse_flight_record_legs<'a>(
     line: &'a str,
-    persistent_carriers: &CarrierRecord,
+    persistent_carriers: &'a CarrierRecord,
+    columns: &ColumnSelection,
 ) -> Option<FlightLegRecord<'a>> {
-    // These must be Owned: format! creates new string, carrier has different lifetime
-    let flight_designator = Cow::Owned(format!(
-        "{}_{}{}{} {} {}",
-        &line[2..5],
+    let mut flight_designator = String::new();
+    push_flight_designator(
+        &mut flight_designator,
+        line,
         &persistent_carriers.control_duplicate_indicator,
-        &line[5..9],
-        &line[1..2],
-        &line[9..11],
-        &line[127..128]
-    ));
-    let control_duplicate_indicator =
-        Cow::Owned(persistent_carriers.control_duplicate_indicator.clone());
+    );
+    parse_flight_leg(
+        line,
+        persistent_carriers,
+        Cow::Owned(flight_designator),
+        columns,
+    )
+}
+
+/// [`parse_flight_record_legs`] with the flight designator built by the caller.
+pub(crate) fn parse_flight_leg<'a>(
+    line: &'a str,
+    persistent_carriers: &'a CarrierRecord,
+    flight_designator: Cow<'a, str>,
+    columns: &ColumnSelection,
+) -> Option<FlightLegRecord<'a>> {
+    check_record(line).ok()?;
+
+    let mask = columns.flight_leg_mask();
+    macro_rules! field {
+        ($column:literal, $value:expr) => {
+            projected!(mask, FLIGHT_LEG_COLUMNS, $column, $value)
+        };
+    }
 
     Some(FlightLegRecord {
         flight_designator,
-        operational_suffix: Cow::Borrowed(&line[1..2]),
+        operational_suffix: field!("operational_suffix", Cow::Borrowed(&line[1..2])),
         airline_designator: Cow::Borrowed(&line[2..5]),
-        control_duplicate_indicator,
-        flight_number: Cow::Borrowed(&line[5..9]),
-        itinerary_variation_identifier: Cow::Borrowed(&line[9..11]),
+        control_duplicate_indicator: Cow::Borrowed(
+            &persistent_carriers.control_duplicate_indicator,
+        ),
+        flight_number: field!("flight_number", Cow::Borrowed(&line[5..9])),
+        itinerary_variation_identifier: field!(
+            "itinerary_variation_identifier",
+            Cow::Borrowed(&line[9..11])
+        ),
         leg_sequence_number: Cow::Borrowed(&line[11..13]),
-        service_type: Cow::Borrowed(&line[13..14]),
-        period_of_operation_from: Cow::Borrowed(&line[14..21]),
-        period_of_operation_to: Cow::Borrowed(&line[21..28]),
-        days_of_operation: Cow::Borrowed(&line[28..35]),
-        frequency_rate: Cow::Borrowed(&line[35..36]),
-        departure_station: Cow::Borrowed(&line[36..39]),
-        scheduled_time_of_passenger_departure: Cow::Borrowed(&line[39..43]),
-        scheduled_time_of_aircraft_departure: Cow::Borrowed(&line[43..47]),
-        time_variation_departure: Cow::Borrowed(&line[47..52]),
-        passenger_terminal_departure: Cow::Borrowed(&line[52..54]),
-        arrival_station: Cow::Borrowed(&line[54..57]),
-        scheduled_time_of_aircraft_arrival: Cow::Borrowed(&line[57..61]),
-        scheduled_time_of_passenger_arrival: Cow::Borrowed(&line[61..65]),
-        time_variation_arrival: Cow::Borrowed(&line[65..70]),
-        passenger_terminal_arrival: Cow::Borrowed(&line[70..72]),
-        aircraft_type: Cow::Borrowed(&line[72..75]),
+        service_type: field!("service_type", Cow::Borrowed(&line[13..14])),
+        period_of_operation_from: field!("period_of_operation_from", Cow::Borrowed(&line[14..21])),
+        period_of_operation_to: field!("period_of_operation_to", Cow::Borrowed(&line[21..28])),
+        days_of_operation: field!("days_of_operation", Cow::Borrowed(&line[28..35])),
+        frequency_rate: field!("frequency_rate", Cow::Borrowed(&line[35..36])),
+        departure_station: field!("departure_station", Cow::Borrowed(&line[36..39])),
+        scheduled_time_of_passenger_departure: field!(
+            "scheduled_time_of_passenger_departure",
+            Cow::Borrowed(&line[39..43])
+        ),
+        scheduled_time_of_aircraft_departure: field!(
+            "scheduled_time_of_aircraft_departure",
+            Cow::Borrowed(&line[43..47])
+        ),
+        time_variation_departure: field!("time_variation_departure", Cow::Borrowed(&line[47..52])),
+        passenger_terminal_departure: field!(
+            "passenger_terminal_departure",
+            Cow::Borrowed(&line[52..54])
+        ),
+        arrival_station: field!("arrival_station", Cow::Borrowed(&line[54..57])),
+        scheduled_time_of_aircraft_arrival: field!(
+            "scheduled_time_of_aircraft_arrival",
+            Cow::Borrowed(&line[57..61])
+        ),
+        scheduled_time_of_passenger_arrival: field!(
+            "scheduled_time_of_passenger_arrival",
+            Cow::Borrowed(&line[61..65])
+        ),
+        time_variation_arrival: field!("time_variation_arrival", Cow::Borrowed(&line[65..70])),
+        passenger_terminal_arrival: field!(
+            "passenger_terminal_arrival",
+            Cow::Borrowed(&line[70..72])
+        ),
+        aircraft_type: field!("aircraft_type", Cow::Borrowed(&line[72..75])),
         // trim() returns a new slice, so we need Owned for trimmed fields
-        passenger_reservations_booking_designator: Cow::Owned(line[75..95].trim().to_string()),
-        passenger_reservations_booking_modifier: Cow::Borrowed(&line[95..100]),
-        meal_service_note: Cow::Borrowed(&line[100..110]),
-        joint_operation_airline_designators: Cow::Borrowed(&line[110..119]),
-        min_connecting_time_status_departure: Cow::Borrowed(&line[119..120]),
-        min_connecting_time_status_arrival: Cow::Borrowed(&line[120..121]),
-        secure_flight_indicator: Cow::Borrowed(&line[121..122]),
-        itinerary_variation_identifier_overflow: Cow::Borrowed(&line[127..128]),
+        passenger_reservations_booking_designator: field!(
+            "passenger_reservations_booking_designator",
+            Cow::Owned(line[75..95].trim().to_string())
+        ),
+        passenger_reservations_booking_modifier: field!(
+            "passenger_reservations_booking_modifier",
+            Cow::Borrowed(&line[95..100])
+        ),
+        meal_service_note: field!("meal_service_note", Cow::Borrowed(&line[100..110])),
+        joint_operation_airline_designators: field!(
+            "joint_operation_airline_designators",
+            Cow::Borrowed(&line[110..119])
+        ),
+        min_connecting_time_status_departure: field!(
+            "min_connecting_time_status_departure",
+            Cow::Borrowed(&line[119..120])
+        ),
+        min_connecting_time_status_arrival: field!(
+            "min_connecting_time_status_arrival",
+            Cow::Borrowed(&line[120..121])
+        ),
+        secure_flight_indicator: field!("secure_flight_indicator", Cow::Borrowed(&line[121..122])),
+        itinerary_variation_identifier_overflow: field!(
+            "itinerary_variation_identifier_overflow",
+            Cow::Borrowed(&line[127..128])
+        ),
         // trim() requires Owned
-        aircraft_owner: Cow::Owned(line[128..131].trim().to_string()),
-        cockpit_crew_employer: Cow::Owned(line[131..134].trim().to_string()),
-        cabin_crew_employer: Cow::Owned(line[134..137].trim().to_string()),
-        onward_flight: Cow::Owned(line[137..146].trim().to_string()),
-        airline_designator2: Cow::Borrowed(&line[137..140]),
-        flight_number2: Cow::Borrowed(&line[140..144]),
-        aircraft_rotation_layover: Cow::Borrowed(&line[144..145]),
-        operational_suffix2: Cow::Borrowed(&line[145..146]),
-        flight_transit_layover: Cow::Borrowed(&line[147..148]),
-        operating_airline_disclosure: Cow::Borrowed(&line[148..149]),
-        traffic_restriction_code: Cow::Borrowed(&line[149..160]),
-        traffic_restriction_code_leg_overflow_indicator: Cow::Borrowed(&line[160..161]),
-        aircraft_configuration: Cow::Borrowed(&line[172..192]),
-        date_variation: Cow::Borrowed(&line[192..194]),
+        aircraft_owner: field!(
+            "aircraft_owner",
+            Cow::Owned(line[128..131].trim().to_string())
+        ),
+        cockpit_crew_employer: field!(
+            "cockpit_crew_employer",
+            Cow::Owned(line[131..134].trim().to_string())
+        ),
+        cabin_crew_employer: field!(
+            "cabin_crew_employer",
+            Cow::Owned(line[134..137].trim().to_string())
+        ),
+        onward_flight: field!(
+            "onward_flight",
+            Cow::Owned(line[137..146].trim().to_string())
+        ),
+        airline_designator2: field!("airline_designator2", Cow::Borrowed(&line[137..140])),
+        flight_number2: field!("flight_number2", Cow::Borrowed(&line[140..144])),
+        aircraft_rotation_layover: field!(
+            "aircraft_rotation_layover",
+            Cow::Borrowed(&line[144..145])
+        ),
+        operational_suffix2: field!("operational_suffix2", Cow::Borrowed(&line[145..146])),
+        flight_transit_layover: field!("flight_transit_layover", Cow::Borrowed(&line[147..148])),
+        operating_airline_disclosure: field!(
+            "operating_airline_disclosure",
+            Cow::Borrowed(&line[148..149])
+        ),
+        traffic_restriction_code: field!(
+            "traffic_restriction_code",
+            Cow::Borrowed(&line[149..160])
+        ),
+        traffic_restriction_code_leg_overflow_indicator: field!(
+            "traffic_restriction_code_leg_overflow_indicator",
+            Cow::Borrowed(&line[160..161])
+        ),
+        aircraft_configuration: field!("aircraft_configuration", Cow::Borrowed(&line[172..192])),
+        date_variation: field!("date_variation", Cow::Borrowed(&line[192..194])),
         record_serial_number: Cow::Borrowed(&line[194..200]),
         record_type: line.chars().next().unwrap(),
     })
 }
 
+/// Parse a segment data record (type 4), leaving fields outside `columns` empty.
 pub fn parse_segment_record<'a>(
     line: &'a str,
-    persistent_carriers: &CarrierRecord,
+    persistent_carriers: &'a CarrierRecord,
+    columns: &ColumnSelection,
 ) -> Option<SegmentRecords<'a>> {
-    // Must be Owned: format! and carrier lifetime
-    let flight_designator = Cow::Owned(format!(
-        "{}_{}{}{} {} {}",
-        &line[2..5],
+    let mut flight_designator = String::new();
+    push_flight_designator(
+        &mut flight_designator,
+        line,
         &persistent_carriers.control_duplicate_indicator,
-        &line[5..9],
-        &line[1..2],
-        &line[9..11],
-        &line[127..128]
-    ));
-    let control_duplicate_indicator =
-        Cow::Owned(persistent_carriers.control_duplicate_indicator.clone());
+    );
+    parse_segment(
+        line,
+        persistent_carriers,
+        Cow::Owned(flight_designator),
+        columns,
+    )
+}
+
+/// [`parse_segment_record`] with the flight designator built by the caller.
+pub(crate) fn parse_segment<'a>(
+    line: &'a str,
+    persistent_carriers: &'a CarrierRecord,
+    flight_designator: Cow<'a, str>,
+    columns: &ColumnSelection,
+) -> Option<SegmentRecords<'a>> {
+    check_record(line).ok()?;
+
+    let mask = columns.segment_mask();
+    macro_rules! field {
+        ($column:literal, $value:expr) => {
+            projected!(mask, SEGMENT_COLUMNS, $column, $value)
+        };
+    }
 
     Some(SegmentRecords {
         flight_designator,
-        operational_suffix: Cow::Borrowed(&line[1..2]),
+        operational_suffix: field!("operational_suffix", Cow::Borrowed(&line[1..2])),
         airline_designator: Cow::Borrowed(&line[2..5]),
-        control_duplicate_indicator,
-        flight_number: Cow::Borrowed(&line[5..9]),
-        itinerary_variation_identifier: Cow::Borrowed(&line[9..11]),
+        control_duplicate_indicator: Cow::Borrowed(
+            &persistent_carriers.control_duplicate_indicator,
+        ),
+        flight_number: field!("flight_number", Cow::Borrowed(&line[5..9])),
+        itinerary_variation_identifier: field!(
+            "itinerary_variation_identifier",
+            Cow::Borrowed(&line[9..11])
+        ),
         leg_sequence_number: Cow::Borrowed(&line[11..13]),
-        service_type: Cow::Borrowed(&line[13..14]),
-        itinerary_variation_identifier_overflow: Cow::Borrowed(&line[27..28]),
-        board_point_indicator: Cow::Borrowed(&line[28..29]),
-        off_point_indicator: Cow::Borrowed(&line[29..30]),
-        data_element_identifier: Cow::Borrowed(&line[30..33]),
-        board_point: Cow::Borrowed(&line[33..36]),
-        off_point: Cow::Borrowed(&line[36..39]),
+        service_type: field!("service_type", Cow::Borrowed(&line[13..14])),
+        itinerary_variation_identifier_overflow: field!(
+            "itinerary_variation_identifier_overflow",
+            Cow::Borrowed(&line[27..28])
+        ),
+        board_point_indicator: field!("board_point_indicator", Cow::Borrowed(&line[28..29])),
+        off_point_indicator: field!("off_point_indicator", Cow::Borrowed(&line[29..30])),
+        data_element_identifier: field!("data_element_identifier", Cow::Borrowed(&line[30..33])),
+        board_point: field!("board_point", Cow::Borrowed(&line[33..36])),
+        off_point: field!("off_point", Cow::Borrowed(&line[36..39])),
         // trim() requires Owned
-        data: Cow::Owned(line[39..194].trim().to_string()),
+        data: field!("data", Cow::Owned(line[39..194].trim().to_string())),
         record_serial_number: Cow::Borrowed(&line[194..200]),
         record_type: line.chars().next().unwrap(),
     })
 }
 
 pub fn parse_carrier_record(line: &str) -> Option<CarrierRecord> {
+    check_record(line).ok()?;
+
     Some(CarrierRecord {
         airline_designator: line[2..5].to_string(),
         control_duplicate_indicator: line[107..108].to_string(),
@@ -132,3 +473,28 @@ pub fn parse_carrier_record(line: &str) -> Option<CarrierRecord> {
         record_serial_number: line[194..200].to_string(),
     })
 }
+
+pub fn parse_header_record(line: &str) -> Option<HeaderRecord> {
+    check_record(line).ok()?;
+
+    Some(HeaderRecord {
+        title_of_contents: line[1..35].trim().to_string(),
+        number_of_seasons: line[40..41].to_string(),
+        data_set_serial_number: line[191..194].to_string(),
+        record_type: line.chars().next().unwrap(),
+        record_serial_number: line[194..200].to_string(),
+    })
+}
+
+pub fn parse_trailer_record(line: &str) -> Option<TrailerRecord> {
+    check_record(line).ok()?;
+
+    Some(TrailerRecord {
+        airline_designator: line[2..5].to_string(),
+        release_date: line[5..12].to_string(),
+        serial_number_check_reference: line[187..193].to_string(),
+        continuation_end_code: line[193..194].to_string(),
+        record_type: line.chars().next().unwrap(),
+        record_serial_number: line[194..200].to_string(),
+    })
+}
@@ -5,21 +5,50 @@
 //! - In-memory combined DataFrame
 //! - In-memory split DataFrames (carriers, flights, segments)
 //! - Streaming CSV output
+//! - Streaming Arrow IPC and NDJSON output
 //! - Per-carrier Parquet output
 
-use crate::converters::ssim_polars::{combine_all_dataframes, serialize_segment_data_to_json};
+use crate::converters::ssim_polars::{
+    combine_all_dataframes, serialize_segment_data_to_json, to_native_dtypes,
+};
 use crate::generators::ssim_dataframe::convert_to_dataframes;
 use crate::records::carrier_record::CarrierRecord;
 use crate::records::flight_leg_records::FlightLegRecord;
+use crate::records::header_record::HeaderRecord;
 use crate::records::segment_records::SegmentRecords;
-use crate::utils::ssim_exporters::to_parquet;
+use crate::records::ssim_record::SsimRecord;
+use crate::records::trailer_record::TrailerRecord;
+use crate::utils::ssim_batch::{
+    BatchFrames, BatchLayout, DesignatorBuffers, LineBatch, RecordSink,
+};
+use crate::utils::ssim_chunked::{ChunkedSsimReader, map_plain_file};
+use crate::utils::ssim_columns::ColumnSelection;
+use crate::utils::ssim_csv::{CsvFileWriter, CsvOptions, WriteMode, format_durations};
+use crate::utils::ssim_dei::add_dei_columns;
+use crate::utils::ssim_diagnostics::{
+    DEFAULT_MAX_REPORTED_ISSUES, Diagnostics, IssueKind, ParseMode,
+};
+use crate::utils::ssim_error::{RecordLocation, SsimError, SsimResult};
+use crate::utils::ssim_expand::expand_to_dated_flights;
+use crate::utils::ssim_exporters::{
+    DEFAULT_ROW_GROUP_SIZE, IpcFileWriter, ParquetFileWriter, create_file,
+};
+use crate::utils::ssim_filter::{SsimFilter, flight_key};
+use crate::utils::ssim_layout::{DEFAULT_MAX_OPEN_PARTITIONS, ParquetLayout, partition};
+use crate::utils::ssim_metadata::{SsimFileMetadata, source_file_name};
 use crate::utils::ssim_parser::{
-    parse_carrier_record, parse_flight_record_legs, parse_segment_record,
+    RECORD_LENGTH, RecordDefect, check_record, parse_carrier_record, parse_flight_record_legs,
+    parse_header_record, parse_segment_record, parse_trailer_record,
 };
+use crate::utils::ssim_seats::{
+    AircraftVersions, CABIN_SEATS_COLUMN, add_selected_seat_columns, seat_input_columns,
+};
+use crate::utils::ssim_source::{FileSource, LineSource, SourceLine, decompress, open_file};
+use chrono::NaiveDate;
 use polars::prelude::*;
-use rayon::prelude::*;
-use std::fs::{File, OpenOptions, create_dir_all};
-use std::io::{BufRead, BufReader};
+use std::collections::{HashMap, HashSet};
+use std::fs::{File, create_dir_all};
+use std::io::{BufRead, BufReader, Read};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
@@ -47,6 +76,58 @@ pub trait BatchProcessor {
         carrier: Option<&CarrierRecord>,
     ) -> PolarsResult<()>;
 
+    /// Process a batch of raw flight leg and segment lines.
+    ///
+    /// This is what the reader calls. Processors with a
+    /// [`batch_layout`](Self::batch_layout), like the built-in ones, get the
+    /// DataFrames built straight from the lines through
+    /// [`process_frames`](Self::process_frames); the others get the lines
+    /// parsed into records for [`process_batch`](Self::process_batch).
+    fn process_lines(&mut self, batch: LineBatch<'_>) -> PolarsResult<()> {
+        let carrier = batch.carrier();
+        if let Some(layout) = self.batch_layout() {
+            return self.process_frames(batch.to_frames(layout)?, carrier);
+        }
+        let (flight_batch, segment_batch) = batch.into_records();
+        self.process_batch(flight_batch, segment_batch, carrier)
+    }
+
+    /// The DataFrames this processor builds from each batch, if any.
+    ///
+    /// [`ChunkedSsimReader`] builds them on the thread that reads the chunk and
+    /// hands them to [`process_frames`](Self::process_frames) in file order.
+    /// Default: `None`, the processor gets the lines through
+    /// [`process_lines`](Self::process_lines).
+    fn batch_layout(&self) -> Option<BatchLayout> {
+        None
+    }
+
+    /// Process the DataFrames of a batch, built in the [`batch_layout`](Self::batch_layout)
+    /// of the processor from the lines of `carrier`.
+    fn process_frames(
+        &mut self,
+        _frames: BatchFrames,
+        _carrier: Option<&CarrierRecord>,
+    ) -> PolarsResult<()> {
+        Err(PolarsError::ComputeError(
+            "this processor has no batch layout to build DataFrames in".into(),
+        ))
+    }
+
+    /// Called for each header record (type 1).
+    fn on_header(&mut self, _header: &HeaderRecord) -> PolarsResult<()> {
+        Ok(()) // Default: no-op
+    }
+
+    /// Called for each trailer record (type 5), just before `on_carrier_complete`.
+    fn on_trailer(
+        &mut self,
+        _trailer: &TrailerRecord,
+        _carrier: Option<&CarrierRecord>,
+    ) -> PolarsResult<()> {
+        Ok(()) // Default: no-op
+    }
+
     /// Called when a carrier section ends (record type 5).
     ///
     /// Useful for Parquet output to write per-carrier files.
@@ -62,99 +143,390 @@ pub trait BatchProcessor {
 // Unified SSIM Reader
 // ============================================================================
 
+/// A raw line together with its position in the input.
+struct RawLine<L> {
+    text: L,
+    line_number: usize,
+    byte_offset: u64,
+    /// 1-based column of the first invalid UTF-8 byte; `text` is then a lossy copy.
+    invalid_utf8_column: Option<usize>,
+}
+
+/// A record line that passed validation, as returned by `SsimReader::next_event`.
+enum RecordEvent<L> {
+    Header(HeaderRecord),
+    Carrier(Box<CarrierRecord>),
+    FlightLeg(L),
+    /// A segment data record, and whether the flight leg it belongs to was
+    /// kept, as the last flight leg of its flight with its leg sequence number.
+    Segment {
+        text: L,
+        has_leg: bool,
+    },
+    /// A trailer record, or `None` if it was malformed and skipped in lenient mode.
+    /// Either way it closes the current carrier section.
+    Trailer(Option<TrailerRecord>),
+}
+
 /// Unified streaming SSIM reader that delegates batch processing to a `BatchProcessor`.
-pub struct SsimReader {
-    reader: BufReader<File>,
+///
+/// Reads from any [`BufRead`] source, see [`LineSource`]. [`SsimReader::new`] opens a file path,
+/// decompressing gzip, zstd and bzip2 files on the fly and reading every SSIM member of a
+/// zip archive; [`SsimReader::from_reader`] wraps stdin, an in-memory buffer or a network stream.
+pub struct SsimReader<R: LineSource = FileSource> {
+    reader: R,
+    file_path: Option<String>,
     batch_size: usize,
-    line_buffer: String,
-    peeked_line: Option<String>,
+    peeked_line: Option<RawLine<R::Line>>,
+    first_line_number: usize,
+    next_line_number: usize,
+    next_byte_offset: u64,
     persistent_carriers: Option<CarrierRecord>,
+    /// Serial number of the last record, for continuity and trailer checks.
+    last_record_serial: Option<String>,
+    /// End code of the last trailer, checked against what follows it.
+    pending_end_code: Option<(String, RecordLocation)>,
+    parse_mode: ParseMode,
+    columns: ColumnSelection,
+    filter: SsimFilter,
+    /// Set while inside a carrier block that the filter rejected.
+    skipping_carrier: bool,
+    /// Flight of the last flight leg kept by the filter, whose segments are kept too.
+    kept_flight: Option<[u8; 10]>,
+    /// The legs of the flight being read, which its segments belong to.
+    flight_run: FlightRun,
+    /// Set when this reader only sees one chunk of a larger input.
+    chunk: Option<ChunkEdges>,
+    /// Whether the end of the underlying reader is the end of the input.
+    at_input_end: bool,
+    diagnostics: Diagnostics,
+}
+
+/// The flight legs read since the last flight leg of another flight or the
+/// last carrier or trailer record, before filtering.
+#[derive(Debug, Default)]
+struct FlightRun {
+    flight: Option<[u8; 10]>,
+    /// Leg sequence number of each leg, and whether the filter kept it.
+    legs: Vec<([u8; 2], bool)>,
+}
+
+impl FlightRun {
+    fn push_leg(&mut self, text: &str, kept: bool) {
+        let flight = flight_key(text);
+        if self.flight != Some(flight) {
+            self.flight = Some(flight);
+            self.legs.clear();
+        }
+        self.legs.push((leg_sequence(text), kept));
+    }
+
+    /// Whether the last leg of this segment's flight and leg sequence number
+    /// was kept, or `None` if there is no such leg.
+    fn leg_kept(&self, segment: &str) -> Option<bool> {
+        if self.flight != Some(flight_key(segment)) {
+            return None;
+        }
+        let leg = leg_sequence(segment);
+        self.legs
+            .iter()
+            .rev()
+            .find(|(sequence, _)| *sequence == leg)
+            .map(|&(_, kept)| kept)
+    }
+
+    fn clear(&mut self) {
+        self.flight = None;
+        self.legs.clear();
+    }
+}
+
+/// The last of `flight_lines` with the flight and leg sequence number of `segment`.
+///
+/// The legs of a flight are never split across batches, so only the legs at
+/// the end of the batch need to be searched.
+fn owning_leg<L: AsRef<str>>(flight_lines: &[L], segment: &str) -> Option<usize> {
+    let flight = Some(flight_key(segment));
+    flight_lines
+        .iter()
+        .enumerate()
+        .rev()
+        .take_while(|(_, line)| same_flight(line.as_ref(), flight))
+        .find(|(_, line)| line.as_ref().as_bytes()[11..13] == segment.as_bytes()[11..13])
+        .map(|(i, _)| i)
+}
+
+/// Whether `text` is a leg or segment of `flight`.
+fn same_flight(text: &str, flight: Option<[u8; 10]>) -> bool {
+    flight.is_some_and(|flight| text.as_bytes().get(1..11) == Some(&flight[..]))
+}
+
+fn leg_sequence(text: &str) -> [u8; 2] {
+    [text.as_bytes()[11], text.as_bytes()[12]]
+}
+
+/// Checks that straddle the start of a chunk read by [`SsimReader::into_chunk`].
+///
+/// A chunk reader starts without the serial number and end code left by the
+/// previous chunk, so it records what it would have compared against them and
+/// the caller settles those checks once the previous chunk is known.
+#[derive(Debug, Default)]
+pub(crate) struct ChunkEdges {
+    /// The first serial number tracked in the chunk, and where it was found.
+    pub(crate) first_serial: Option<(String, RecordLocation)>,
+    /// The first trailer's check reference, if no serial number was tracked before it.
+    pub(crate) first_trailer_check: Option<(String, RecordLocation)>,
+    /// What became of the end code left by the previous chunk.
+    pub(crate) first_end_code_check: Option<EndCodeCheck>,
+    /// The serial number and end code left at the end of the chunk.
+    pub(crate) last_record_serial: Option<String>,
+    pub(crate) pending_end_code: Option<(String, RecordLocation)>,
+}
+
+/// The first end code check of a chunk, see [`ChunkEdges`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EndCodeCheck {
+    /// Checked at `line_number`, knowing whether another carrier follows.
+    Checked {
+        another_carrier_follows: bool,
+        line_number: usize,
+    },
+    /// A trailer replaced the end code before any check.
+    Replaced,
 }
 
-impl SsimReader {
+impl SsimReader<FileSource> {
+    /// Open an SSIM file, detecting gzip, zstd, bzip2 and zip compression from its magic bytes.
+    ///
+    /// Every SSIM member of a zip archive is read in archive order as one logical
+    /// input, with locations reported as `archive.zip!member.ssim`. Each member is
+    /// checked as a file of its own: line numbers, record serial numbers and trailer
+    /// end codes start afresh.
     pub fn new(
         file_path: &str,
         batch_size: Option<usize>,
         buffer_size: Option<usize>,
-    ) -> std::io::Result<Self> {
-        let file = File::open(file_path)?;
-        let reader = BufReader::with_capacity(buffer_size.unwrap_or(DEFAULT_BUFFER_SIZE), file);
+    ) -> SsimResult<Self> {
+        let source = open_file(file_path, buffer_size.unwrap_or(DEFAULT_BUFFER_SIZE))?;
+        let mut reader = SsimReader::from_reader(source, batch_size).with_file_path(file_path);
+        // A zip archive starts at its first member.
+        reader.next_member()?;
+        Ok(reader)
+    }
+}
 
-        Ok(SsimReader {
+impl<R: LineSource> SsimReader<R> {
+    /// Read SSIM data from an already-open source.
+    ///
+    /// Record locations in errors and diagnostics carry no file path.
+    pub fn from_reader(reader: R, batch_size: Option<usize>) -> Self {
+        SsimReader {
             reader,
+            file_path: None,
             batch_size: batch_size.unwrap_or(DEFAULT_BATCH_SIZE),
-            line_buffer: String::new(),
             peeked_line: None,
+            first_line_number: 1,
+            next_line_number: 1,
+            next_byte_offset: 0,
             persistent_carriers: None,
-        })
+            last_record_serial: None,
+            pending_end_code: None,
+            parse_mode: ParseMode::default(),
+            columns: ColumnSelection::all(),
+            filter: SsimFilter::default(),
+            skipping_carrier: false,
+            kept_flight: None,
+            flight_run: FlightRun::default(),
+            chunk: None,
+            at_input_end: true,
+            diagnostics: Diagnostics::default(),
+        }
+    }
+
+    /// Set the path reported in record locations.
+    pub(crate) fn with_file_path(mut self, file_path: &str) -> Self {
+        self.file_path = Some(file_path.to_string());
+        self
+    }
+
+    /// Set how malformed lines are handled (default: [`ParseMode::Strict`]).
+    pub fn with_parse_mode(mut self, parse_mode: ParseMode) -> Self {
+        self.parse_mode = parse_mode;
+        self
+    }
+
+    /// Only parse the flight leg and segment fields in `columns` (default: all).
+    ///
+    /// The other fields of the records handed to processors and returned by
+    /// [`SsimReader::records`] are left empty.
+    pub fn with_columns(mut self, columns: ColumnSelection) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    /// Only read the carrier blocks and flight legs matched by `filter` (default: all).
+    ///
+    /// The filter is applied to the raw lines before they are parsed. Lines of
+    /// skipped carrier blocks are only checked for serial number continuity.
+    pub fn with_filter(mut self, filter: SsimFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Set how many offending lines are kept in the returned [`Diagnostics`].
+    pub fn with_max_reported_issues(mut self, max_reported_issues: usize) -> Self {
+        self.diagnostics = Diagnostics::new(max_reported_issues);
+        self
+    }
+
+    /// Read one chunk of a larger input, starting at `line_number` and `byte_offset`.
+    ///
+    /// `carrier` is the carrier block the chunk starts in, if it was kept by
+    /// the filter, and `skipping_carrier` whether it was skipped. Unless the
+    /// chunk is `at_input_end`, its end does not close an open carrier block.
+    pub(crate) fn into_chunk(
+        mut self,
+        line_number: usize,
+        byte_offset: u64,
+        carrier: Option<CarrierRecord>,
+        skipping_carrier: bool,
+        at_input_end: bool,
+    ) -> Self {
+        self.first_line_number = line_number;
+        self.next_line_number = line_number;
+        self.next_byte_offset = byte_offset;
+        self.persistent_carriers = carrier;
+        self.skipping_carrier = skipping_carrier;
+        self.at_input_end = at_input_end;
+        self.chunk = Some(ChunkEdges::default());
+        self
+    }
+
+    /// Move on to the next member of a zip archive, returning whether there is one.
+    ///
+    /// Only the diagnostics carry over; the checks within a file start afresh.
+    fn next_member(&mut self) -> SsimResult<bool> {
+        let member_path = self.reader.next_member().map_err(|e| SsimError::Io {
+            file_path: self.file_path.clone(),
+            source: e,
+        })?;
+        let Some(member_path) = member_path else {
+            return Ok(false);
+        };
+
+        self.diagnostics.lines_read += self.next_line_number - self.first_line_number;
+        self.file_path = Some(member_path);
+        self.first_line_number = 1;
+        self.next_line_number = 1;
+        self.next_byte_offset = 0;
+        self.persistent_carriers = None;
+        self.last_record_serial = None;
+        self.pending_end_code = None;
+        self.skipping_carrier = false;
+        self.kept_flight = None;
+        self.flight_run.clear();
+        Ok(true)
+    }
+
+    /// Hand over the checks straddling the chunk's edges, see [`SsimReader::into_chunk`].
+    pub(crate) fn take_chunk_edges(&mut self) -> ChunkEdges {
+        let mut edges = self.chunk.take().unwrap_or_default();
+        edges.last_record_serial = self.last_record_serial.take();
+        edges.pending_end_code = self.pending_end_code.take();
+        edges
+    }
+
+    /// Fail on a record-level error in strict mode, or record it and carry on in lenient mode.
+    fn report(&mut self, error: SsimError) -> SsimResult<()> {
+        match self.parse_mode {
+            ParseMode::Strict => Err(error),
+            ParseMode::Lenient => self.diagnostics.record(error),
+        }
+    }
+
+    /// Build the location of a line for error reporting.
+    fn location(&self, line_number: usize, byte_offset: u64, text: &str) -> RecordLocation {
+        RecordLocation {
+            file_path: self.file_path.clone(),
+            line_number,
+            byte_offset,
+            record_serial_number: text
+                .get(194..RECORD_LENGTH)
+                .map(|serial| serial.to_string()),
+        }
+    }
+
+    /// Read one line from the underlying reader, stripping the line terminator.
+    ///
+    /// Lines that are not valid UTF-8 are returned as a lossy copy with
+    /// `invalid_utf8_column` set, so the caller decides whether to fail or skip.
+    fn read_raw_line(&mut self) -> SsimResult<Option<RawLine<R::Line>>> {
+        let Some(SourceLine {
+            text,
+            bytes_read,
+            invalid_utf8_column,
+        }) = self.reader.read_line().map_err(|e| SsimError::Io {
+            file_path: self.file_path.clone(),
+            source: e,
+        })?
+        else {
+            return Ok(None);
+        };
+
+        let line_number = self.next_line_number;
+        let byte_offset = self.next_byte_offset;
+        self.next_line_number += 1;
+        self.next_byte_offset += bytes_read as u64;
+
+        Ok(Some(RawLine {
+            text,
+            line_number,
+            byte_offset,
+            invalid_utf8_column,
+        }))
     }
 
-    fn peek_next_line(&mut self) -> std::io::Result<Option<&str>> {
+    fn peek_next_line(&mut self) -> SsimResult<Option<&RawLine<R::Line>>> {
         if self.peeked_line.is_none() {
-            let mut line = String::new();
-            match self.reader.read_line(&mut line) {
-                Ok(0) => return Ok(None),
-                Ok(_) => {
-                    if line.ends_with('\n') {
-                        line.pop();
-                        if line.ends_with('\r') {
-                            line.pop();
-                        }
-                    }
-                    self.peeked_line = Some(line);
-                }
-                Err(e) => return Err(e),
-            }
+            self.peeked_line = self.read_raw_line()?;
         }
-        Ok(self.peeked_line.as_deref())
+        Ok(self.peeked_line.as_ref())
     }
 
-    fn consume_peeked_line(&mut self) -> Option<String> {
+    fn consume_peeked_line(&mut self) -> Option<RawLine<R::Line>> {
         self.peeked_line.take()
     }
 
-    fn read_next_line(&mut self) -> std::io::Result<Option<String>> {
+    fn read_next_line(&mut self) -> SsimResult<Option<RawLine<R::Line>>> {
         if let Some(line) = self.consume_peeked_line() {
             return Ok(Some(line));
         }
-
-        self.line_buffer.clear();
-        match self.reader.read_line(&mut self.line_buffer) {
-            Ok(0) => Ok(None),
-            Ok(_) => {
-                if self.line_buffer.ends_with('\n') {
-                    self.line_buffer.pop();
-                    if self.line_buffer.ends_with('\r') {
-                        self.line_buffer.pop();
-                    }
-                }
-                let line = std::mem::take(&mut self.line_buffer);
-                Ok(Some(line))
-            }
-            Err(e) => Err(e),
-        }
+        self.read_raw_line()
     }
 
     fn should_continue_batch(
         &mut self,
         current_batch_size: usize,
         last_record_type: Option<char>,
-    ) -> std::io::Result<bool> {
+    ) -> SsimResult<bool> {
         if current_batch_size < self.batch_size {
             return Ok(true);
         }
 
         if last_record_type == Some('3') || last_record_type == Some('4') {
+            let flight = self.flight_run.flight;
             loop {
                 match self.peek_next_line()? {
-                    Some(line) => match line.chars().nth(0) {
-                        Some('4') if last_record_type == Some('3') => return Ok(true),
-                        Some('4') if last_record_type == Some('4') => return Ok(true),
-                        Some('3') | Some('2') | Some('1') | Some('5') => return Ok(false),
-                        _ => {
+                    Some(line) => match line.text.chars().next() {
+                        Some('4') => return Ok(true),
+                        // Keep the legs of a flight together with its segments.
+                        Some('3') if same_flight(&line.text, flight) => return Ok(true),
+                        // Filler and blank lines never end a batch.
+                        Some('0') | None => {
                             self.consume_peeked_line();
                             continue;
                         }
+                        _ => return Ok(false),
                     },
                     None => return Ok(false),
                 }
@@ -164,157 +536,615 @@ impl SsimReader {
         Ok(false)
     }
 
-    /// Main processing loop - works with any BatchProcessor
+    /// Validate that a carrier, flight leg or segment line can be sliced into fields.
+    fn check_line(&self, line: &RawLine<R::Line>, record_type: char) -> SsimResult<()> {
+        if let Some(column) = line.invalid_utf8_column {
+            return Err(SsimError::InvalidUtf8 {
+                location: self.location(line.line_number, line.byte_offset, &line.text),
+                column,
+            });
+        }
+
+        check_record(&line.text).map_err(|defect| {
+            let location = self.location(line.line_number, line.byte_offset, &line.text);
+            match defect {
+                RecordDefect::Truncated { length } => SsimError::TruncatedRecord {
+                    location,
+                    record_type,
+                    length,
+                },
+                RecordDefect::InvalidUtf8Boundary { column } => {
+                    SsimError::InvalidUtf8 { location, column }
+                }
+            }
+        })
+    }
+
+    /// Check a trailer against the carrier block it closes.
     ///
-    /// Uses parallel parsing with rayon for flight and segment records
-    /// while maintaining sequential carrier context handling.
-    pub fn process<P: BatchProcessor>(&mut self, processor: &mut P) -> PolarsResult<()> {
-        // Collect raw lines for parallel parsing
-        let mut flight_lines: Vec<String> = Vec::new();
-        let mut segment_lines: Vec<String> = Vec::new();
-        let mut last_record_type: Option<char> = None;
+    /// Mismatches are recorded as warnings; the end code is checked once the
+    /// next carrier record or the end of the input is reached.
+    fn validate_trailer(&mut self, line: &RawLine<R::Line>, trailer: &TrailerRecord) {
+        let location = self.location(line.line_number, line.byte_offset, &line.text);
+
+        match (&self.last_record_serial, &mut self.chunk) {
+            (Some(expected), _) => {
+                if let Some(reason) =
+                    trailer_check_issue(&trailer.serial_number_check_reference, expected, &location)
+                {
+                    self.diagnostics.warn(
+                        IssueKind::TrailerCheckMismatch,
+                        location.clone(),
+                        reason,
+                    );
+                }
+            }
+            (None, Some(edges)) if edges.first_trailer_check.is_none() => {
+                edges.first_trailer_check = Some((
+                    trailer.serial_number_check_reference.clone(),
+                    location.clone(),
+                ));
+            }
+            (None, _) => {}
+        }
+
+        match trailer.continuation_end_code.as_str() {
+            "E" | "C" => {
+                if let Some(edges) = &mut self.chunk
+                    && edges.first_end_code_check.is_none()
+                {
+                    edges.first_end_code_check = Some(EndCodeCheck::Replaced);
+                }
+                self.pending_end_code = Some((trailer.continuation_end_code.clone(), location));
+            }
+            other => {
+                let reason = format!(
+                    "trailer at {} has end code {:?}, expected 'E' or 'C'",
+                    location, other
+                );
+                self.diagnostics
+                    .warn(IssueKind::TrailerEndCodeMismatch, location, reason);
+            }
+        }
+    }
+
+    /// Check that a record's serial number follows the previous record's, then remember it.
+    fn track_serial_number(&mut self, line: &RawLine<R::Line>) {
+        let Some(found) = line.text.get(194..RECORD_LENGTH) else {
+            return;
+        };
+
+        if self.last_record_serial.is_none()
+            && let Some(edges) = &self.chunk
+            && edges.first_serial.is_none()
+        {
+            let location = self.location(line.line_number, line.byte_offset, &line.text);
+            if let Some(edges) = &mut self.chunk {
+                edges.first_serial = Some((found.to_string(), location));
+            }
+        }
+
+        if let Some((kind, detail)) =
+            serial_number_mismatch(self.last_record_serial.as_deref(), found)
+        {
+            let location = self.location(line.line_number, line.byte_offset, &line.text);
+            let reason = format!("record serial number {} at {} {}", found, location, detail);
+            self.diagnostics.warn(kind, location, reason);
+        }
+
+        self.last_record_serial = Some(found.to_string());
+    }
+
+    /// Check the last trailer's end code now that we know whether another carrier follows.
+    ///
+    /// `line_number` is the line at which the check is made.
+    fn check_end_code(&mut self, another_carrier_follows: bool, line_number: usize) {
+        let Some((code, location)) = self.pending_end_code.take() else {
+            if let Some(edges) = &mut self.chunk
+                && edges.first_end_code_check.is_none()
+            {
+                edges.first_end_code_check = Some(EndCodeCheck::Checked {
+                    another_carrier_follows,
+                    line_number,
+                });
+            }
+            return;
+        };
+
+        if let Some(reason) = end_code_issue(&code, &location, another_carrier_follows) {
+            self.diagnostics
+                .warn(IssueKind::TrailerEndCodeMismatch, location, reason);
+        }
+    }
+
+    /// Iterate over the parsed records one at a time, without building DataFrames.
+    ///
+    /// Filler and blank lines are skipped. Validation is the same as in
+    /// [`SsimReader::process`]: in [`ParseMode::Strict`] the first malformed line
+    /// is yielded as an error and ends the iteration; in [`ParseMode::Lenient`]
+    /// malformed lines are skipped and recorded, and can be inspected through
+    /// [`SsimReader::diagnostics`] once the iterator is dropped.
+    ///
+    /// ```no_run
+    /// use rusty_ssim_core::{SsimReader, SsimRecord};
+    ///
+    /// let mut reader = SsimReader::new("schedule.ssim", None, None)?;
+    /// for record in reader.records() {
+    ///     if let SsimRecord::FlightLeg { line_number, record } = record? {
+    ///         println!("{}: {}", line_number, record.flight_designator);
+    ///     }
+    /// }
+    /// # Ok::<(), rusty_ssim_core::SsimError>(())
+    /// ```
+    pub fn records(&mut self) -> SsimRecordIter<'_, R> {
+        SsimRecordIter {
+            reader: self,
+            done: false,
+        }
+    }
+
+    /// The diagnostics collected so far, including the number of lines read.
+    pub fn diagnostics(&self) -> Diagnostics {
+        let mut diagnostics = self.diagnostics.clone();
+        diagnostics.lines_read += self.next_line_number - self.first_line_number;
+        diagnostics
+    }
 
+    /// Parse the next validated line into an owned [`SsimRecord`].
+    fn next_record(&mut self) -> SsimResult<Option<SsimRecord>> {
         loop {
-            match self.read_next_line() {
-                Ok(Some(line)) => {
-                    let record_type = line.chars().next();
-
-                    match record_type {
-                        Some('1') | Some('0') => continue,
-                        Some('2') => {
-                            if let Some(record) = parse_carrier_record(&line) {
-                                self.persistent_carriers = Some(record);
-                                last_record_type = Some('2');
-                            }
-                        }
-                        Some('3') => {
-                            if self.persistent_carriers.is_some() {
-                                flight_lines.push(line);
-                                last_record_type = Some('3');
-                            }
+            match self.next_member_record()? {
+                Some(record) => return Ok(Some(record)),
+                None if self.next_member()? => continue,
+                None => return Ok(None),
+            }
+        }
+    }
+
+    /// Parse the next validated line of the current zip member or file.
+    fn next_member_record(&mut self) -> SsimResult<Option<SsimRecord>> {
+        while let Some((line_number, event)) = self.next_event()? {
+            let record = match event {
+                RecordEvent::Header(record) => SsimRecord::Header {
+                    line_number,
+                    record,
+                },
+                RecordEvent::Carrier(record) => {
+                    self.persistent_carriers = Some((*record).clone());
+                    SsimRecord::Carrier {
+                        line_number,
+                        record,
+                    }
+                }
+                RecordEvent::FlightLeg(text) => {
+                    let carrier = self.persistent_carriers.as_ref();
+                    match carrier.and_then(|c| parse_flight_record_legs(&text, c, &self.columns)) {
+                        Some(record) => SsimRecord::FlightLeg {
+                            line_number,
+                            record: Box::new(record.into_owned()),
+                        },
+                        None => continue,
+                    }
+                }
+                RecordEvent::Segment { text, .. } => {
+                    let carrier = self.persistent_carriers.as_ref();
+                    match carrier.and_then(|c| parse_segment_record(&text, c, &self.columns)) {
+                        Some(record) => SsimRecord::Segment {
+                            line_number,
+                            record: Box::new(record.into_owned()),
+                        },
+                        None => continue,
+                    }
+                }
+                RecordEvent::Trailer(trailer) => {
+                    self.persistent_carriers = None;
+                    match trailer {
+                        Some(record) => SsimRecord::Trailer {
+                            line_number,
+                            record,
+                        },
+                        None => continue,
+                    }
+                }
+            };
+            return Ok(Some(record));
+        }
+        Ok(None)
+    }
+
+    /// Read lines until the next record that passes validation.
+    ///
+    /// Filler and blank lines are skipped; malformed lines fail in strict mode
+    /// and are recorded and skipped in lenient mode. The caller makes each
+    /// carrier record the current carrier.
+    fn next_event(&mut self) -> SsimResult<Option<(usize, RecordEvent<R::Line>)>> {
+        while let Some(line) = self.read_next_line()? {
+            let record_type = line.text.chars().next();
+            if line.text.len() > RECORD_LENGTH {
+                let location = self.location(line.line_number, line.byte_offset, &line.text);
+                let reason = format!(
+                    "record at {} is {} bytes long, expected {}",
+                    location,
+                    line.text.len(),
+                    RECORD_LENGTH
+                );
+                self.diagnostics
+                    .warn(IssueKind::InvalidLineLength, location, reason);
+            }
+
+            let event = match record_type {
+                // Zero-filled padding and blank lines carry no schedule data.
+                None | Some('0') => continue,
+                Some('1') => {
+                    if let Err(e) = self.check_line(&line, '1') {
+                        self.report(e)?;
+                        continue;
+                    }
+                    self.track_serial_number(&line);
+                    match parse_header_record(&line.text) {
+                        Some(header) => RecordEvent::Header(header),
+                        None => continue,
+                    }
+                }
+                Some('2') => {
+                    if let Err(e) = self.check_line(&line, '2') {
+                        self.report(e)?;
+                        continue;
+                    }
+                    self.check_end_code(true, line.line_number);
+                    self.track_serial_number(&line);
+                    self.kept_flight = None;
+                    self.flight_run.clear();
+                    self.skipping_carrier = !self.filter.matches_carrier(&line.text);
+                    if self.skipping_carrier {
+                        continue;
+                    }
+                    match parse_carrier_record(&line.text) {
+                        Some(record) => RecordEvent::Carrier(Box::new(record)),
+                        None => continue,
+                    }
+                }
+                Some(rt @ ('3' | '4')) => {
+                    if self.skipping_carrier {
+                        self.track_serial_number(&line);
+                        continue;
+                    }
+                    if self.persistent_carriers.is_none() {
+                        self.report(SsimError::MissingCarrierContext {
+                            location: self.location(line.line_number, line.byte_offset, &line.text),
+                            record_type: rt,
+                        })?;
+                        continue;
+                    }
+                    if let Err(e) = self.check_line(&line, rt) {
+                        self.report(e)?;
+                        continue;
+                    }
+                    self.track_serial_number(&line);
+                    let keep = self.keeps_record(&line.text, rt);
+                    if rt == '3' {
+                        self.flight_run.push_leg(&line.text, keep);
+                        if !keep {
+                            continue;
                         }
-                        Some('4') => {
-                            if self.persistent_carriers.is_some() {
-                                segment_lines.push(line);
-                                last_record_type = Some('4');
-                            }
+                        RecordEvent::FlightLeg(line.text)
+                    } else {
+                        let leg_kept = self.flight_run.leg_kept(&line.text);
+                        if leg_kept.is_none() {
+                            let location =
+                                self.location(line.line_number, line.byte_offset, &line.text);
+                            let reason = format!(
+                                "segment at {} does not follow a flight leg of its flight with leg sequence number {}",
+                                location,
+                                &line.text[11..13]
+                            );
+                            self.diagnostics
+                                .warn(IssueKind::OrphanSegment, location, reason);
                         }
-                        Some('5') => {
-                            if !flight_lines.is_empty() || !segment_lines.is_empty() {
-                                let (flight_batch, segment_batch) =
-                                    self.parse_lines_parallel(&flight_lines, &segment_lines);
-                                processor.process_batch(
-                                    flight_batch,
-                                    segment_batch,
-                                    self.persistent_carriers.as_ref(),
-                                )?;
-                                flight_lines.clear();
-                                segment_lines.clear();
-                            }
-                            // Notify processor that carrier section is complete
-                            processor.on_carrier_complete(self.persistent_carriers.as_ref())?;
-                            self.persistent_carriers = None;
-                            last_record_type = Some('5');
+                        if !keep {
                             continue;
                         }
-                        _ => continue,
-                    }
-
-                    let current_batch_size = flight_lines.len() + segment_lines.len();
-                    match self.should_continue_batch(current_batch_size, last_record_type) {
-                        Ok(should_continue) => {
-                            if !should_continue {
-                                let (flight_batch, segment_batch) =
-                                    self.parse_lines_parallel(&flight_lines, &segment_lines);
-                                processor.process_batch(
-                                    flight_batch,
-                                    segment_batch,
-                                    self.persistent_carriers.as_ref(),
-                                )?;
-                                flight_lines.clear();
-                                segment_lines.clear();
-                            }
+                        RecordEvent::Segment {
+                            text: line.text,
+                            has_leg: leg_kept == Some(true),
                         }
+                    }
+                }
+                // A malformed trailer still closes its carrier section.
+                Some('5') => {
+                    let trailer = match self.check_line(&line, '5') {
                         Err(e) => {
-                            return Err(PolarsError::IO {
-                                error: Arc::from(e),
-                                msg: None,
-                            });
+                            self.report(e)?;
+                            None
+                        }
+                        Ok(()) => {
+                            let trailer = parse_trailer_record(&line.text);
+                            if let Some(trailer) = &trailer {
+                                self.validate_trailer(&line, trailer);
+                                self.track_serial_number(&line);
+                            }
+                            trailer
                         }
+                    };
+                    self.kept_flight = None;
+                    self.flight_run.clear();
+                    // A skipped carrier block ends without reaching the caller.
+                    if std::mem::take(&mut self.skipping_carrier) {
+                        continue;
                     }
+                    RecordEvent::Trailer(trailer)
+                }
+                Some(found) => {
+                    self.report(SsimError::InvalidRecordType {
+                        location: self.location(line.line_number, line.byte_offset, &line.text),
+                        found,
+                    })?;
+                    continue;
                 }
-                Ok(None) => break,
-                Err(e) => {
-                    return Err(PolarsError::IO {
-                        error: Arc::from(e),
-                        msg: None,
-                    });
+            };
+            return Ok(Some((line.line_number, event)));
+        }
+
+        // The end of a chunk is not the end of the input.
+        if self.at_input_end {
+            self.check_end_code(false, self.next_line_number);
+        }
+        Ok(None)
+    }
+
+    /// Apply the filter to a validated flight leg or segment line.
+    ///
+    /// Segments are kept when a leg of the same flight was kept, so a leg
+    /// filter never leaves segments without their leg.
+    fn keeps_record(&mut self, text: &str, record_type: char) -> bool {
+        if self.filter.is_all() {
+            return true;
+        }
+        if record_type == '3' {
+            let keep = self.filter.matches_flight_leg(text);
+            if keep {
+                self.kept_flight = Some(flight_key(text));
+            }
+            keep
+        } else {
+            !self.filter.filters_legs() || self.kept_flight == Some(flight_key(text))
+        }
+    }
+
+    /// Feed every record to `processor` without finalizing it, so several
+    /// inputs (e.g. the chunks of a memory-mapped file) can share one processor.
+    pub(crate) fn read_records<S: RecordSink<R::Line> + ?Sized>(
+        &mut self,
+        processor: &mut S,
+    ) -> SsimResult<()> {
+        self.read_member_records(processor)?;
+        while self.next_member()? {
+            self.read_member_records(processor)?;
+        }
+        Ok(())
+    }
+
+    /// Feed the records of the current zip member or file to `processor`.
+    fn read_member_records<S: RecordSink<R::Line> + ?Sized>(
+        &mut self,
+        processor: &mut S,
+    ) -> SsimResult<()> {
+        // Collect raw lines for parallel parsing
+        let mut flight_lines: Vec<R::Line> = Vec::new();
+        let mut segment_lines: Vec<R::Line> = Vec::new();
+        // Index of each segment's flight leg in `flight_lines`.
+        let mut segment_legs: Vec<Option<usize>> = Vec::new();
+        let mut designators = DesignatorBuffers::default();
+
+        while let Some((_, event)) = self.next_event()? {
+            let last_record_type = match event {
+                RecordEvent::Header(header) => {
+                    processor.header(&header)?;
+                    continue;
+                }
+                RecordEvent::Carrier(record) => {
+                    self.persistent_carriers = Some(*record);
+                    '2'
+                }
+                RecordEvent::FlightLeg(text) => {
+                    flight_lines.push(text);
+                    '3'
+                }
+                RecordEvent::Segment { text, has_leg } => {
+                    segment_legs.push(has_leg.then(|| owning_leg(&flight_lines, &text)).flatten());
+                    segment_lines.push(text);
+                    '4'
+                }
+                RecordEvent::Trailer(trailer) => {
+                    if !flight_lines.is_empty() || !segment_lines.is_empty() {
+                        processor.lines(LineBatch::new(
+                            &flight_lines,
+                            &segment_lines,
+                            &segment_legs,
+                            self.persistent_carriers.as_ref(),
+                            &self.columns,
+                            &mut designators,
+                        ))?;
+                        flight_lines.clear();
+                        segment_lines.clear();
+                        segment_legs.clear();
+                    }
+                    if let Some(trailer) = trailer {
+                        processor.trailer(&trailer, self.persistent_carriers.as_ref())?;
+                    }
+                    // Notify processor that carrier section is complete
+                    processor.carrier_complete(self.persistent_carriers.as_ref())?;
+                    self.persistent_carriers = None;
+                    continue;
                 }
+            };
+
+            let current_batch_size = flight_lines.len() + segment_lines.len();
+            if !self.should_continue_batch(current_batch_size, Some(last_record_type))? {
+                processor.lines(LineBatch::new(
+                    &flight_lines,
+                    &segment_lines,
+                    &segment_legs,
+                    self.persistent_carriers.as_ref(),
+                    &self.columns,
+                    &mut designators,
+                ))?;
+                flight_lines.clear();
+                segment_lines.clear();
+                segment_legs.clear();
             }
         }
 
         if !flight_lines.is_empty() || !segment_lines.is_empty() {
-            let (flight_batch, segment_batch) =
-                self.parse_lines_parallel(&flight_lines, &segment_lines);
-            processor.process_batch(
-                flight_batch,
-                segment_batch,
+            processor.lines(LineBatch::new(
+                &flight_lines,
+                &segment_lines,
+                &segment_legs,
                 self.persistent_carriers.as_ref(),
-            )?;
+                &self.columns,
+                &mut designators,
+            ))?;
+        }
+
+        // Close a carrier section left open by a missing trailer record.
+        if self.persistent_carriers.is_some() && self.at_input_end {
+            processor.carrier_complete(self.persistent_carriers.as_ref())?;
+            self.persistent_carriers = None;
         }
 
-        processor.finalize()?;
         Ok(())
     }
 
-    /// Parse flight and segment lines in parallel using rayon.
+    /// Hand over the collected diagnostics, adding the lines read by this reader.
+    pub(crate) fn take_diagnostics(&mut self) -> Diagnostics {
+        let mut diagnostics = std::mem::take(&mut self.diagnostics);
+        diagnostics.lines_read += self.next_line_number - self.first_line_number;
+        diagnostics
+    }
+}
+
+impl<R: LineSource<Line = String>> SsimReader<R> {
+    /// Main processing loop - works with any BatchProcessor
     ///
-    /// This is the hot path - parsing string slices into records is CPU-bound
-    /// and benefits significantly from parallelization.
-    fn parse_lines_parallel<'a>(
-        &self,
-        flight_lines: &'a [String],
-        segment_lines: &'a [String],
-    ) -> (Vec<FlightLegRecord<'a>>, Vec<SegmentRecords<'a>>) {
-        let carrier = self.persistent_carriers.as_ref();
-
-        // Parse flights and segments in parallel
-        let (flights, segments) = rayon::join(
-            || {
-                if let Some(c) = carrier {
-                    flight_lines
-                        .par_iter()
-                        .filter_map(|line| parse_flight_record_legs(line, c))
-                        .collect()
-                } else {
-                    Vec::new()
-                }
-            },
-            || {
-                if let Some(c) = carrier {
-                    segment_lines
-                        .par_iter()
-                        .filter_map(|line| parse_segment_record(line, c))
-                        .collect()
-                } else {
-                    Vec::new()
-                }
-            },
-        );
+    /// Uses parallel parsing with rayon for flight and segment records
+    /// while maintaining sequential carrier context handling.
+    ///
+    /// Returns [`Diagnostics`] describing the lines that were skipped.
+    ///
+    /// # Errors
+    /// In [`ParseMode::Strict`], returns an [`SsimError`] carrying the line number
+    /// and byte offset of the first record that is truncated, not valid UTF-8, of an
+    /// unknown record type, or a flight/segment record without a preceding carrier
+    /// record. In [`ParseMode::Lenient`] those lines are skipped and counted instead.
+    /// I/O and processor errors are always returned.
+    pub fn process<P: BatchProcessor>(&mut self, processor: &mut P) -> SsimResult<Diagnostics> {
+        self.read_records(processor)?;
+        processor.finalize()?;
+        Ok(self.take_diagnostics())
+    }
+}
+
+/// Pull-based iterator over the records of an [`SsimReader`].
+///
+/// Created by [`SsimReader::records`].
+pub struct SsimRecordIter<'r, R: LineSource> {
+    reader: &'r mut SsimReader<R>,
+    done: bool,
+}
 
-        (flights, segments)
+impl<R: LineSource> Iterator for SsimRecordIter<'_, R> {
+    type Item = SsimResult<SsimRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.reader.next_record() {
+            Ok(Some(record)) => Some(Ok(record)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
     }
 }
 
+impl<R: LineSource> std::iter::FusedIterator for SsimRecordIter<'_, R> {}
+
 // ============================================================================
 // Utility Functions
 // ============================================================================
 
-/// Concatenate a vector of DataFrames into a single DataFrame.
-/// Uses polars concat which is more efficient than repeated vstack_mut.
-fn concat_dataframes(dfs: Vec<DataFrame>) -> PolarsResult<DataFrame> {
-    if dfs.is_empty() {
-        return Ok(DataFrame::empty());
+/// How a record serial number fails to follow the previous one, if it does.
+///
+/// Serial numbers run from 000001 to 999999 and wrap back to 000001. A
+/// non-numeric previous serial number gives nothing to compare against.
+pub(crate) fn serial_number_mismatch(
+    previous: Option<&str>,
+    found: &str,
+) -> Option<(IssueKind, String)> {
+    let previous = previous?.parse::<u32>().ok()?;
+    let expected = if previous >= 999_999 { 1 } else { previous + 1 };
+    match found.parse::<u32>() {
+        Ok(number) if number == expected => None,
+        Ok(number) if number == previous => Some((
+            IssueKind::SerialNumberDuplicate,
+            "repeats the previous record's serial number".to_string(),
+        )),
+        Ok(number) if number < previous => Some((
+            IssueKind::SerialNumberReset,
+            format!("goes back from {:06}", previous),
+        )),
+        _ => Some((
+            IssueKind::SerialNumberGap,
+            format!("follows {:06}, expected {:06}", previous, expected),
+        )),
+    }
+}
+
+/// The warning for a trailer whose serial number check reference does not
+/// match the serial number of the record before it.
+pub(crate) fn trailer_check_issue(
+    check_reference: &str,
+    expected: &str,
+    location: &RecordLocation,
+) -> Option<String> {
+    (check_reference != expected).then(|| {
+        format!(
+            "trailer at {} has serial number check reference {}, expected {}",
+            location, check_reference, expected
+        )
+    })
+}
+
+/// The warning for a trailer end code that contradicts whether another carrier follows.
+pub(crate) fn end_code_issue(
+    code: &str,
+    location: &RecordLocation,
+    another_carrier_follows: bool,
+) -> Option<String> {
+    match (code, another_carrier_follows) {
+        ("E", true) => Some(format!(
+            "trailer at {} has end code 'E' but another carrier follows",
+            location
+        )),
+        ("C", false) => Some(format!(
+            "trailer at {} has end code 'C' but no carrier follows",
+            location
+        )),
+        _ => None,
+    }
+}
+
+/// Concatenate a vector of DataFrames into a single DataFrame.
+/// Uses polars concat which is more efficient than repeated vstack_mut.
+fn concat_dataframes(dfs: Vec<DataFrame>) -> PolarsResult<DataFrame> {
+    if dfs.is_empty() {
+        return Ok(DataFrame::empty());
     }
     if dfs.len() == 1 {
         return dfs.into_iter().next().ok_or_else(|| {
@@ -336,6 +1166,12 @@ pub struct CombinedDataFrameProcessor {
     result: Option<DataFrame>,
     condense_segments: bool,
     serialize_segments: bool,
+    native_dtypes: bool,
+    seat_columns: bool,
+    aircraft_versions: Option<AircraftVersions>,
+    columns: ColumnSelection,
+    /// `columns` and the inputs of the seat columns.
+    built_columns: ColumnSelection,
 }
 
 impl CombinedDataFrameProcessor {
@@ -345,12 +1181,63 @@ impl CombinedDataFrameProcessor {
             result: None,
             condense_segments,
             serialize_segments,
+            native_dtypes: false,
+            seat_columns: false,
+            aircraft_versions: None,
+            columns: ColumnSelection::all(),
+            built_columns: ColumnSelection::all(),
         }
     }
 
+    /// Convert dates, times, offsets and codes to native Polars dtypes.
+    pub fn with_native_dtypes(mut self, native_dtypes: bool) -> Self {
+        self.native_dtypes = native_dtypes;
+        self
+    }
+
+    /// Add the seats per cabin of each flight leg, see [`add_seat_columns`](crate::add_seat_columns).
+    pub fn with_seat_columns(mut self, seat_columns: bool) -> Self {
+        self.seat_columns = seat_columns;
+        self.built_columns = seat_input_columns(&self.columns, seat_columns);
+        self
+    }
+
+    /// Seats of aircraft version codes, used by [`Self::with_seat_columns`].
+    pub fn with_aircraft_versions(mut self, aircraft_versions: Option<AircraftVersions>) -> Self {
+        self.aircraft_versions = aircraft_versions;
+        self
+    }
+
+    /// Only build and output the columns in `columns` (default: all).
+    pub fn with_columns(mut self, columns: ColumnSelection) -> Self {
+        self.built_columns = seat_input_columns(&columns, self.seat_columns);
+        self.columns = columns;
+        self
+    }
+
     pub fn into_result(self) -> DataFrame {
         self.result.unwrap_or_else(DataFrame::empty)
     }
+
+    /// Keep the combined DataFrame of a batch.
+    fn push_combined(&mut self, batch_df: DataFrame) -> PolarsResult<()> {
+        let mut batch_df = self.built_columns.project(batch_df)?;
+        if self.native_dtypes {
+            batch_df = to_native_dtypes(batch_df)?;
+        }
+        if batch_df.height() > 0 {
+            // TEMP WILL DEPRECATIED SOON - serialize_segments will be removed in next major release condense segments will be only option 
+            // and will NOT serialize segments into json column as it is not needed when condensing segments into list of struct column. 
+            // This is only needed for backwards compatibility.
+            if self.condense_segments && self.serialize_segments {
+                let batch_df = serialize_segment_data_to_json(batch_df)?;
+                self.batches.push(batch_df);
+            } else {
+                self.batches.push(batch_df);
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Default for CombinedDataFrameProcessor {
@@ -367,27 +1254,40 @@ impl BatchProcessor for CombinedDataFrameProcessor {
         carrier: Option<&CarrierRecord>,
     ) -> PolarsResult<()> {
         let (carrier_df, flight_df, segment_df) =
-            convert_to_dataframes(carrier, flight_batch, segment_batch)?;
+            convert_to_dataframes(carrier, flight_batch, segment_batch, &self.built_columns)?;
+        self.push_combined(combine_all_dataframes(
+            carrier_df,
+            flight_df,
+            segment_df,
+            self.condense_segments,
+        )?)
+    }
 
-        let batch_df =
-            combine_all_dataframes(carrier_df, flight_df, segment_df, self.condense_segments)?;
-        if batch_df.height() > 0 {
-            // TEMP WILL DEPRECATIED SOON - serialize_segments will be removed in next major release condense segments will be only option 
-            // and will NOT serialize segments into json column as it is not needed when condensing segments into list of struct column. 
-            // This is only needed for backwards compatibility.
-            if self.condense_segments && self.serialize_segments {
-                let batch_df = serialize_segment_data_to_json(batch_df)?;
-                self.batches.push(batch_df);
-            } else {
-                self.batches.push(batch_df);
-            }
-        }
-        Ok(())
+    fn batch_layout(&self) -> Option<BatchLayout> {
+        Some(BatchLayout::Combined {
+            condense_segments: self.condense_segments,
+        })
+    }
+
+    fn process_frames(
+        &mut self,
+        frames: BatchFrames,
+        _carrier: Option<&CarrierRecord>,
+    ) -> PolarsResult<()> {
+        self.push_combined(frames.into_combined()?)
     }
 
     fn finalize(&mut self) -> PolarsResult<()> {
         let batches = std::mem::take(&mut self.batches);
-        self.result = Some(concat_dataframes(batches)?);
+        let mut combined = concat_dataframes(batches)?;
+        if self.seat_columns && combined.width() > 0 {
+            combined = add_selected_seat_columns(
+                &combined,
+                self.aircraft_versions.as_ref(),
+                &self.columns,
+            )?;
+        }
+        self.result = Some(combined);
         Ok(())
     }
 }
@@ -396,11 +1296,31 @@ impl BatchProcessor for CombinedDataFrameProcessor {
 // Processor: Split DataFrames (in-memory)
 // ============================================================================
 
+/// Header and trailer fields of one carrier block, joined onto the carriers DataFrame.
+struct CarrierBlock {
+    carrier: CarrierRecord,
+    header: Option<HeaderRecord>,
+    trailer: Option<TrailerRecord>,
+}
+
 /// Processor that accumulates into three separate DataFrames.
+///
+/// The carriers DataFrame also carries the fields of the enclosing header
+/// record and of the trailer record that closes each carrier block.
 pub struct SplitDataFrameProcessor {
     carrier_batches: Vec<DataFrame>,
     flight_batches: Vec<DataFrame>,
     segment_batches: Vec<DataFrame>,
+    current_header: Option<HeaderRecord>,
+    current_trailer: Option<TrailerRecord>,
+    carrier_blocks: Vec<CarrierBlock>,
+    native_dtypes: bool,
+    decode_deis: bool,
+    seat_columns: bool,
+    aircraft_versions: Option<AircraftVersions>,
+    columns: ColumnSelection,
+    /// `columns` and the inputs of the seat columns.
+    built_columns: ColumnSelection,
     result: Option<(DataFrame, DataFrame, DataFrame)>,
 }
 
@@ -410,10 +1330,117 @@ impl SplitDataFrameProcessor {
             carrier_batches: Vec::new(),
             flight_batches: Vec::new(),
             segment_batches: Vec::new(),
+            current_header: None,
+            current_trailer: None,
+            carrier_blocks: Vec::new(),
+            native_dtypes: false,
+            decode_deis: false,
+            seat_columns: false,
+            aircraft_versions: None,
+            columns: ColumnSelection::all(),
+            built_columns: ColumnSelection::all(),
             result: None,
         }
     }
 
+    /// Convert dates, times, offsets and codes to native Polars dtypes.
+    pub fn with_native_dtypes(mut self, native_dtypes: bool) -> Self {
+        self.native_dtypes = native_dtypes;
+        self
+    }
+
+    /// Decode the DEI of each segment data record, see [`add_dei_columns`].
+    pub fn with_decode_deis(mut self, decode_deis: bool) -> Self {
+        self.decode_deis = decode_deis;
+        self
+    }
+
+    /// Add the seats per cabin of each flight leg, see [`add_seat_columns`](crate::add_seat_columns).
+    pub fn with_seat_columns(mut self, seat_columns: bool) -> Self {
+        self.seat_columns = seat_columns;
+        self.built_columns = seat_input_columns(&self.columns, seat_columns);
+        self
+    }
+
+    /// Seats of aircraft version codes, used by [`Self::with_seat_columns`].
+    pub fn with_aircraft_versions(mut self, aircraft_versions: Option<AircraftVersions>) -> Self {
+        self.aircraft_versions = aircraft_versions;
+        self
+    }
+
+    /// Only build and output the columns in `columns` (default: all).
+    pub fn with_columns(mut self, columns: ColumnSelection) -> Self {
+        self.built_columns = seat_input_columns(&columns, self.seat_columns);
+        self.columns = columns;
+        self
+    }
+
+    /// Left-join the header and trailer fields of each carrier block onto `carriers`.
+    fn join_carrier_blocks(&mut self, carriers: DataFrame) -> PolarsResult<DataFrame> {
+        if carriers.width() == 0 {
+            return Ok(carriers);
+        }
+
+        let blocks = std::mem::take(&mut self.carrier_blocks);
+        let carrier_field = |f: fn(&CarrierRecord) -> &str| -> Vec<&str> {
+            blocks.iter().map(|b| f(&b.carrier)).collect()
+        };
+        let header_field = |f: fn(&HeaderRecord) -> &str| -> Vec<Option<&str>> {
+            blocks.iter().map(|b| b.header.as_ref().map(f)).collect()
+        };
+        let trailer_field = |f: fn(&TrailerRecord) -> &str| -> Vec<Option<&str>> {
+            blocks.iter().map(|b| b.trailer.as_ref().map(f)).collect()
+        };
+
+        let keys = [
+            "airline_designator",
+            "control_duplicate_indicator",
+            "record_serial_number",
+        ];
+        let block_df = df!(
+            keys[0] => carrier_field(|c| &c.airline_designator),
+            keys[1] => carrier_field(|c| &c.control_duplicate_indicator),
+            keys[2] => carrier_field(|c| &c.record_serial_number),
+            "title_of_contents" => header_field(|h| &h.title_of_contents),
+            "number_of_seasons" => header_field(|h| &h.number_of_seasons),
+            "data_set_serial_number" => header_field(|h| &h.data_set_serial_number),
+            "serial_number_check_reference" => trailer_field(|t| &t.serial_number_check_reference),
+            "continuation_end_code" => trailer_field(|t| &t.continuation_end_code),
+        )?;
+        let block_df = block_df.unique_stable(
+            Some(&keys.map(String::from)),
+            UniqueKeepStrategy::First,
+            None,
+        )?;
+
+        let on: Vec<Expr> = keys.iter().map(|k| col(*k)).collect();
+        carriers
+            .lazy()
+            .join(
+                block_df.lazy(),
+                on.clone(),
+                on,
+                JoinArgs::new(JoinType::Left).with_coalesce(JoinCoalesce::CoalesceColumns),
+            )
+            .collect()
+    }
+
+    /// Keep the non-empty DataFrames of a batch.
+    fn push_dataframes(
+        &mut self,
+        (carrier_df, flight_df, segment_df): (DataFrame, DataFrame, DataFrame),
+    ) {
+        if carrier_df.height() > 0 {
+            self.carrier_batches.push(carrier_df);
+        }
+        if flight_df.height() > 0 {
+            self.flight_batches.push(flight_df);
+        }
+        if segment_df.height() > 0 {
+            self.segment_batches.push(segment_df);
+        }
+    }
+
     pub fn into_result(self) -> PolarsResult<(DataFrame, DataFrame, DataFrame)> {
         self.result
             .ok_or_else(|| PolarsError::ComputeError("Processor not finalized".into()))
@@ -433,17 +1460,47 @@ impl BatchProcessor for SplitDataFrameProcessor {
         segment_batch: Vec<SegmentRecords<'_>>,
         carrier: Option<&CarrierRecord>,
     ) -> PolarsResult<()> {
-        let (carrier_df, flight_df, segment_df) =
-            convert_to_dataframes(carrier, flight_batch, segment_batch)?;
+        let dataframes =
+            convert_to_dataframes(carrier, flight_batch, segment_batch, &self.built_columns)?;
+        self.push_dataframes(dataframes);
+        Ok(())
+    }
 
-        if carrier_df.height() > 0 {
-            self.carrier_batches.push(carrier_df);
-        }
-        if flight_df.height() > 0 {
-            self.flight_batches.push(flight_df);
-        }
-        if segment_df.height() > 0 {
-            self.segment_batches.push(segment_df);
+    fn batch_layout(&self) -> Option<BatchLayout> {
+        Some(BatchLayout::Split)
+    }
+
+    fn process_frames(
+        &mut self,
+        frames: BatchFrames,
+        _carrier: Option<&CarrierRecord>,
+    ) -> PolarsResult<()> {
+        self.push_dataframes(frames.into_split()?);
+        Ok(())
+    }
+
+    fn on_header(&mut self, header: &HeaderRecord) -> PolarsResult<()> {
+        self.current_header = Some(header.clone());
+        Ok(())
+    }
+
+    fn on_trailer(
+        &mut self,
+        trailer: &TrailerRecord,
+        _carrier: Option<&CarrierRecord>,
+    ) -> PolarsResult<()> {
+        self.current_trailer = Some(trailer.clone());
+        Ok(())
+    }
+
+    fn on_carrier_complete(&mut self, carrier: Option<&CarrierRecord>) -> PolarsResult<()> {
+        let trailer = self.current_trailer.take();
+        if let Some(carrier) = carrier {
+            self.carrier_blocks.push(CarrierBlock {
+                carrier: carrier.clone(),
+                header: self.current_header.clone(),
+                trailer,
+            });
         }
         Ok(())
     }
@@ -455,73 +1512,220 @@ impl BatchProcessor for SplitDataFrameProcessor {
 
         // Deduplicate carriers
         let carriers = carriers.unique_stable(None, UniqueKeepStrategy::First, None)?;
+        let carriers = self.join_carrier_blocks(carriers)?;
+
+        let carriers = self.columns.project(carriers)?;
+        let mut flights = self.built_columns.project(flights)?;
+        let mut segments = self.columns.project(segments)?;
+        if self.seat_columns && flights.width() > 0 {
+            flights = add_selected_seat_columns(
+                &flights,
+                self.aircraft_versions.as_ref(),
+                &self.columns,
+            )?;
+        }
+        if self.decode_deis && segments.width() > 0 {
+            segments = add_dei_columns(&segments)?;
+        }
 
-        self.result = Some((carriers, flights, segments));
+        self.result = Some(if self.native_dtypes {
+            (
+                to_native_dtypes(carriers)?,
+                to_native_dtypes(flights)?,
+                to_native_dtypes(segments)?,
+            )
+        } else {
+            (carriers, flights, segments)
+        });
         Ok(())
     }
 }
 
 // ============================================================================
-// Processor: CSV Writer (streaming append)
+// Processor: CSV Writer (streaming)
 // ============================================================================
 
-/// Processor that writes batches to CSV incrementally using Polars directly.
+/// Create the parent directory of `file_path` if it does not exist.
+fn ensure_directory_exists(file_path: &str) -> PolarsResult<()> {
+    let path = Path::new(file_path);
+    if let Some(parent) = path.parent()
+        && !parent.exists()
+    {
+        create_dir_all(parent).map_err(|e| PolarsError::IO {
+            error: Arc::from(e),
+            msg: Some("Failed to create directory".into()),
+        })?;
+    }
+    Ok(())
+}
+
+/// File name of a carrier's output without extension, e.g. `ssim_XX_` or `ssim_YY_X`.
+fn carrier_file_stem(airline: &str, control: &str) -> String {
+    let airline = airline.trim();
+    let control = control.trim();
+
+    if !airline.is_empty() && !control.is_empty() {
+        format!("ssim_{}_{}", airline, control)
+    } else {
+        format!("ssim_{}_", airline)
+    }
+}
+
+/// Processor that streams batches into a CSV file, or one file per carrier.
+///
+/// The output is opened with its first batch, as [`CsvOptions::write_mode`]
+/// says: by default an existing file is replaced. A carrier block whose
+/// file was already written by this processor is appended to it.
 pub struct CsvWriterProcessor {
-    file: File,
-    headers_written: bool,
+    output_path: String,
+    options: CsvOptions,
+    /// The single file, or the file of the current carrier.
+    file: Option<CsvFileWriter>,
+    /// Per-carrier files written so far.
+    written_files: HashSet<String>,
+    /// The carrier of the current block, for per-carrier file names.
+    current_carrier: Option<CarrierRecord>,
     condense_segments: bool,
+    native_dtypes: bool,
+    seat_columns: bool,
+    aircraft_versions: Option<AircraftVersions>,
+    columns: ColumnSelection,
+    /// `columns` and the inputs of the seat columns.
+    built_columns: ColumnSelection,
 }
 
 impl CsvWriterProcessor {
+    /// Write to the file `output_path`, or into the directory `output_path`
+    /// with [`CsvOptions::per_carrier`].
     pub fn new(output_path: &str, condense_segments: bool) -> PolarsResult<Self> {
-        Self::ensure_directory_exists(output_path)?;
-
-        let file_exists = Path::new(output_path).exists();
-
-        let file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(output_path)
-            .map_err(|e| PolarsError::IO {
-                error: Arc::from(e),
-                msg: Some(format!("Unable to open file {}", output_path).into()),
-            })?;
+        ensure_directory_exists(output_path)?;
 
         Ok(Self {
-            file,
-            headers_written: file_exists,
+            output_path: output_path.to_string(),
+            options: CsvOptions::default(),
+            file: None,
+            written_files: HashSet::new(),
+            current_carrier: None,
             condense_segments,
+            native_dtypes: false,
+            seat_columns: false,
+            aircraft_versions: None,
+            columns: ColumnSelection::all(),
+            built_columns: ColumnSelection::all(),
         })
     }
 
-    fn ensure_directory_exists(file_path: &str) -> PolarsResult<()> {
-        let path = Path::new(file_path);
-        if let Some(parent) = path.parent()
-            && !parent.exists()
-        {
-            create_dir_all(parent).map_err(|e| PolarsError::IO {
-                error: Arc::from(e),
-                msg: Some("Failed to create directory".into()),
-            })?;
+    /// Write mode, format, compression and file layout (default: overwrite
+    /// a single comma-separated file).
+    pub fn with_options(mut self, options: CsvOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Convert dates, times, offsets and codes to native types. Dates are
+    /// written in [`CsvOptions::date_format`] and scheduled times as `HH:MM`.
+    pub fn with_native_dtypes(mut self, native_dtypes: bool) -> Self {
+        self.native_dtypes = native_dtypes;
+        self
+    }
+
+    /// Add the seats per cabin of each flight leg, see [`add_seat_columns`](crate::add_seat_columns).
+    pub fn with_seat_columns(mut self, seat_columns: bool) -> Self {
+        self.seat_columns = seat_columns;
+        self.built_columns = seat_input_columns(&self.columns, seat_columns);
+        self
+    }
+
+    /// Seats of aircraft version codes, used by [`Self::with_seat_columns`].
+    pub fn with_aircraft_versions(mut self, aircraft_versions: Option<AircraftVersions>) -> Self {
+        self.aircraft_versions = aircraft_versions;
+        self
+    }
+
+    /// Only build and output the columns in `columns` (default: all).
+    pub fn with_columns(mut self, columns: ColumnSelection) -> Self {
+        self.built_columns = seat_input_columns(&columns, self.seat_columns);
+        self.columns = columns;
+        self
+    }
+
+    /// Open the file of the current batch.
+    fn open_file(&mut self) -> PolarsResult<CsvFileWriter> {
+        if !self.options.per_carrier {
+            return CsvFileWriter::open(&self.output_path, self.options.write_mode, &self.options);
         }
-        Ok(())
+
+        let carrier = self
+            .current_carrier
+            .as_ref()
+            .ok_or_else(|| PolarsError::ComputeError("No carrier info for CSV file".into()))?;
+        let stem = carrier_file_stem(
+            &carrier.airline_designator,
+            &carrier.control_duplicate_indicator,
+        );
+        create_dir_all(&self.output_path).map_err(|e| PolarsError::IO {
+            error: Arc::from(e),
+            msg: Some(format!("Failed to create directory: {}", self.output_path).into()),
+        })?;
+        let file_path = Path::new(&self.output_path).join(self.options.file_name(&stem));
+        let file_path = file_path
+            .to_str()
+            .ok_or_else(|| PolarsError::IO {
+                error: Arc::from(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "CSV file path is not valid UTF-8",
+                )),
+                msg: Some(format!("Invalid file path: {}", file_path.display()).into()),
+            })?
+            .to_string();
+        // A carrier seen again continues its file.
+        let mode = if self.written_files.insert(file_path.clone()) {
+            self.options.write_mode
+        } else {
+            WriteMode::Append
+        };
+        CsvFileWriter::open(&file_path, mode, &self.options)
     }
 
-    fn write_dataframe(&mut self, df: DataFrame) -> PolarsResult<()> {
+    fn write_dataframe(
+        &mut self,
+        carrier: Option<&CarrierRecord>,
+        df: DataFrame,
+    ) -> PolarsResult<()> {
+        if let Some(c) = carrier {
+            self.current_carrier = Some(c.clone());
+        }
 
-        let mut df = if self.condense_segments {
-                serialize_segment_data_to_json(df)?
-            } else {
-                df
-            };
+        let mut df = self.built_columns.project(df)?;
+        if self.native_dtypes {
+            df = format_durations(to_native_dtypes(df)?)?;
+        }
+        if self.seat_columns && df.width() > 0 {
+            // CSV has no nested columns: only the per-cabin totals are written.
+            df = add_selected_seat_columns(&df, self.aircraft_versions.as_ref(), &self.columns)?
+                .drop(CABIN_SEATS_COLUMN)?;
+        }
+        if self.condense_segments {
+            df = serialize_segment_data_to_json(df)?;
+        }
+        if df.height() == 0 && self.options.per_carrier {
+            return Ok(());
+        }
 
-        // Write directly to file using Polars CsvWriter - no intermediate buffer
-        CsvWriter::new(&mut self.file)
-            .include_header(!self.headers_written)
-            .finish(&mut df)?;
+        if self.file.is_none() {
+            self.file = Some(self.open_file()?);
+        }
+        match &mut self.file {
+            Some(file) => file.write(&mut df),
+            None => Ok(()),
+        }
+    }
 
-        self.headers_written = true;
-        Ok(())
+    fn close_file(&mut self) -> PolarsResult<()> {
+        match self.file.take() {
+            Some(file) => file.finish(),
+            None => Ok(()),
+        }
     }
 }
 
@@ -533,13 +1737,258 @@ impl BatchProcessor for CsvWriterProcessor {
         carrier: Option<&CarrierRecord>,
     ) -> PolarsResult<()> {
         let (carrier_df, flight_df, segment_df) =
-            convert_to_dataframes(carrier, flight_batch, segment_batch)?;
+            convert_to_dataframes(carrier, flight_batch, segment_batch, &self.built_columns)?;
+        let batch_df =
+            combine_all_dataframes(carrier_df, flight_df, segment_df, self.condense_segments)?;
+        self.write_dataframe(carrier, batch_df)
+    }
+
+    fn batch_layout(&self) -> Option<BatchLayout> {
+        Some(BatchLayout::Combined {
+            condense_segments: self.condense_segments,
+        })
+    }
+
+    fn process_frames(
+        &mut self,
+        frames: BatchFrames,
+        carrier: Option<&CarrierRecord>,
+    ) -> PolarsResult<()> {
+        self.write_dataframe(carrier, frames.into_combined()?)
+    }
+
+    fn on_carrier_complete(&mut self, _carrier: Option<&CarrierRecord>) -> PolarsResult<()> {
+        if self.options.per_carrier {
+            self.close_file()?;
+        }
+        self.current_carrier = None;
+        Ok(())
+    }
+
+    fn finalize(&mut self) -> PolarsResult<()> {
+        // An input without rows still gives an (empty) single file.
+        if self.file.is_none() && !self.options.per_carrier {
+            self.file = Some(self.open_file()?);
+        }
+        self.close_file()
+    }
+}
+
+// ============================================================================
+// Processor: Arrow IPC Writer (streaming record batches)
+// ============================================================================
+
+/// Processor that streams batches into one Arrow IPC (Feather v2) file.
+///
+/// Each batch is appended as record batches, so memory is bounded by the
+/// batch size. Condensed segments stay a native `List<Struct>` column.
+pub struct IpcWriterProcessor {
+    file: IpcFileWriter,
+    condense_segments: bool,
+    native_dtypes: bool,
+    seat_columns: bool,
+    aircraft_versions: Option<AircraftVersions>,
+    columns: ColumnSelection,
+    /// `columns` and the inputs of the seat columns.
+    built_columns: ColumnSelection,
+}
+
+impl IpcWriterProcessor {
+    /// Create the IPC file at `output_path`, replacing an existing file.
+    /// `compression` is "lz4", "zstd" or "uncompressed" (default).
+    pub fn new(
+        output_path: &str,
+        compression: Option<&str>,
+        condense_segments: bool,
+    ) -> PolarsResult<Self> {
+        ensure_directory_exists(output_path)?;
+
+        Ok(Self {
+            file: IpcFileWriter::create(output_path, compression.unwrap_or("uncompressed"))?,
+            condense_segments,
+            native_dtypes: false,
+            seat_columns: false,
+            aircraft_versions: None,
+            columns: ColumnSelection::all(),
+            built_columns: ColumnSelection::all(),
+        })
+    }
+
+    /// Convert dates, times, offsets and codes to native Arrow types.
+    pub fn with_native_dtypes(mut self, native_dtypes: bool) -> Self {
+        self.native_dtypes = native_dtypes;
+        self
+    }
+
+    /// Add the seats per cabin of each flight leg, see [`add_seat_columns`](crate::add_seat_columns).
+    pub fn with_seat_columns(mut self, seat_columns: bool) -> Self {
+        self.seat_columns = seat_columns;
+        self.built_columns = seat_input_columns(&self.columns, seat_columns);
+        self
+    }
+
+    /// Seats of aircraft version codes, used by [`Self::with_seat_columns`].
+    pub fn with_aircraft_versions(mut self, aircraft_versions: Option<AircraftVersions>) -> Self {
+        self.aircraft_versions = aircraft_versions;
+        self
+    }
+
+    /// Only build and output the columns in `columns` (default: all).
+    pub fn with_columns(mut self, columns: ColumnSelection) -> Self {
+        self.built_columns = seat_input_columns(&columns, self.seat_columns);
+        self.columns = columns;
+        self
+    }
+
+    fn write_dataframe(&mut self, df: DataFrame) -> PolarsResult<()> {
+        let mut df = self.built_columns.project(df)?;
+        if self.native_dtypes {
+            df = to_native_dtypes(df)?;
+        }
+        if self.seat_columns && df.width() > 0 {
+            df = add_selected_seat_columns(&df, self.aircraft_versions.as_ref(), &self.columns)?;
+        }
+        self.file.write(df)
+    }
+}
+
+impl BatchProcessor for IpcWriterProcessor {
+    fn process_batch(
+        &mut self,
+        flight_batch: Vec<FlightLegRecord<'_>>,
+        segment_batch: Vec<SegmentRecords<'_>>,
+        carrier: Option<&CarrierRecord>,
+    ) -> PolarsResult<()> {
+        let (carrier_df, flight_df, segment_df) =
+            convert_to_dataframes(carrier, flight_batch, segment_batch, &self.built_columns)?;
+        let batch_df =
+            combine_all_dataframes(carrier_df, flight_df, segment_df, self.condense_segments)?;
+        self.write_dataframe(batch_df)
+    }
+
+    fn batch_layout(&self) -> Option<BatchLayout> {
+        Some(BatchLayout::Combined {
+            condense_segments: self.condense_segments,
+        })
+    }
+
+    fn process_frames(
+        &mut self,
+        frames: BatchFrames,
+        _carrier: Option<&CarrierRecord>,
+    ) -> PolarsResult<()> {
+        self.write_dataframe(frames.into_combined()?)
+    }
+
+    fn finalize(&mut self) -> PolarsResult<()> {
+        self.file.finish()
+    }
+}
+
+// ============================================================================
+// Processor: NDJSON Writer (streaming)
+// ============================================================================
 
+/// Processor that streams batches into a newline-delimited JSON file, one
+/// object per row.
+///
+/// Condensed segments are written as a nested `segment_data` array of objects.
+pub struct NdjsonWriterProcessor {
+    file: File,
+    condense_segments: bool,
+    native_dtypes: bool,
+    seat_columns: bool,
+    aircraft_versions: Option<AircraftVersions>,
+    columns: ColumnSelection,
+    /// `columns` and the inputs of the seat columns.
+    built_columns: ColumnSelection,
+}
+
+impl NdjsonWriterProcessor {
+    /// Create the NDJSON file at `output_path`, replacing an existing file.
+    pub fn new(output_path: &str, condense_segments: bool) -> PolarsResult<Self> {
+        ensure_directory_exists(output_path)?;
+
+        Ok(Self {
+            file: create_file(output_path)?,
+            condense_segments,
+            native_dtypes: false,
+            seat_columns: false,
+            aircraft_versions: None,
+            columns: ColumnSelection::all(),
+            built_columns: ColumnSelection::all(),
+        })
+    }
+
+    /// Convert dates, times, offsets and codes to native types, written as
+    /// ISO 8601 dates and durations and as numbers.
+    pub fn with_native_dtypes(mut self, native_dtypes: bool) -> Self {
+        self.native_dtypes = native_dtypes;
+        self
+    }
+
+    /// Add the seats per cabin of each flight leg, see [`add_seat_columns`](crate::add_seat_columns).
+    pub fn with_seat_columns(mut self, seat_columns: bool) -> Self {
+        self.seat_columns = seat_columns;
+        self.built_columns = seat_input_columns(&self.columns, seat_columns);
+        self
+    }
+
+    /// Seats of aircraft version codes, used by [`Self::with_seat_columns`].
+    pub fn with_aircraft_versions(mut self, aircraft_versions: Option<AircraftVersions>) -> Self {
+        self.aircraft_versions = aircraft_versions;
+        self
+    }
+
+    /// Only build and output the columns in `columns` (default: all).
+    pub fn with_columns(mut self, columns: ColumnSelection) -> Self {
+        self.built_columns = seat_input_columns(&columns, self.seat_columns);
+        self.columns = columns;
+        self
+    }
+
+    fn write_dataframe(&mut self, df: DataFrame) -> PolarsResult<()> {
+        let mut df = self.built_columns.project(df)?;
+        if self.native_dtypes {
+            df = to_native_dtypes(df)?;
+        }
+        if self.seat_columns && df.width() > 0 {
+            df = add_selected_seat_columns(&df, self.aircraft_versions.as_ref(), &self.columns)?;
+        }
+        JsonWriter::new(&mut self.file)
+            .with_json_format(JsonFormat::JsonLines)
+            .finish(&mut df)
+    }
+}
+
+impl BatchProcessor for NdjsonWriterProcessor {
+    fn process_batch(
+        &mut self,
+        flight_batch: Vec<FlightLegRecord<'_>>,
+        segment_batch: Vec<SegmentRecords<'_>>,
+        carrier: Option<&CarrierRecord>,
+    ) -> PolarsResult<()> {
+        let (carrier_df, flight_df, segment_df) =
+            convert_to_dataframes(carrier, flight_batch, segment_batch, &self.built_columns)?;
         let batch_df =
             combine_all_dataframes(carrier_df, flight_df, segment_df, self.condense_segments)?;
         self.write_dataframe(batch_df)
     }
 
+    fn batch_layout(&self) -> Option<BatchLayout> {
+        Some(BatchLayout::Combined {
+            condense_segments: self.condense_segments,
+        })
+    }
+
+    fn process_frames(
+        &mut self,
+        frames: BatchFrames,
+        _carrier: Option<&CarrierRecord>,
+    ) -> PolarsResult<()> {
+        self.write_dataframe(frames.into_combined()?)
+    }
+
     fn finalize(&mut self) -> PolarsResult<()> {
         Ok(())
     }
@@ -549,15 +1998,46 @@ impl BatchProcessor for CsvWriterProcessor {
 // Processor: Parquet Writer (per-carrier files)
 // ============================================================================
 
-/// Processor that accumulates per carrier and writes Parquet files.
+/// Processor that streams batches into Parquet files, by default one per carrier.
+///
+/// Batches are appended to their open file as row groups, and a carrier's
+/// file is closed when the carrier block ends, so memory is bounded by the
+/// batch and row group sizes rather than by the size of a carrier. With a
+/// [`ParquetLayout`] the whole input goes to a single file or to Hive
+/// partitions instead. The single file stays open until the end of the input.
+///
+/// At most [`with_max_open_partitions`](Self::with_max_open_partitions) Hive
+/// partition files are open at once: the least recently written one is closed
+/// to make room, and rows for its directory later go to a new `part-N` file
+/// next to its `ssim.parquet`. Partitions by `airline_designator` are closed
+/// when their carrier block ends.
 pub struct ParquetWriterProcessor {
     output_path: String,
     compression: String,
-    accumulated_batches: Vec<DataFrame>,
-    /// Stores (airline_designator, control_duplicate_indicator) for filename generation
-    current_carrier_info: Option<(String, String)>,
+    row_group_size: usize,
+    layout: ParquetLayout,
+    /// The file of the current carrier, or the single file, opened with its first rows.
+    file: Option<ParquetFileWriter>,
+    /// The open file of each Hive partition directory being written.
+    partitions: HashMap<String, OpenPartition>,
+    /// Number of files created in each Hive partition directory.
+    partition_files: HashMap<String, usize>,
+    max_open_partitions: usize,
+    /// Number of partition writes so far, see [`OpenPartition::last_write`].
+    partition_writes: u64,
+    /// The carrier of the current block, for file names and metadata.
+    current_carrier: Option<CarrierRecord>,
+    /// The last header record, for metadata.
+    header: Option<HeaderRecord>,
+    source_file: Option<String>,
     condense_segments: bool,
     serialize_segments: bool,
+    native_dtypes: bool,
+    seat_columns: bool,
+    aircraft_versions: Option<AircraftVersions>,
+    columns: ColumnSelection,
+    /// `columns` and the inputs of the seat columns.
+    built_columns: ColumnSelection,
 }
 
 impl ParquetWriterProcessor {
@@ -589,59 +2069,254 @@ impl ParquetWriterProcessor {
         Ok(Self {
             output_path: output_path.to_string(),
             compression: compression.unwrap_or("uncompressed").to_string(),
-            accumulated_batches: Vec::new(),
-            current_carrier_info: None,
+            row_group_size: DEFAULT_ROW_GROUP_SIZE,
+            layout: ParquetLayout::PerCarrier,
+            file: None,
+            partitions: HashMap::new(),
+            partition_files: HashMap::new(),
+            max_open_partitions: DEFAULT_MAX_OPEN_PARTITIONS,
+            partition_writes: 0,
+            current_carrier: None,
+            header: None,
+            source_file: None,
             condense_segments,
             serialize_segments,
+            native_dtypes: false,
+            seat_columns: false,
+            aircraft_versions: None,
+            columns: ColumnSelection::all(),
+            built_columns: ColumnSelection::all(),
         })
     }
 
-    fn build_filename(&self, airline: &str, control: &str) -> String {
-        let airline = airline.trim();
-        let control = control.trim();
+    /// Convert dates, times, offsets and codes to native Parquet types.
+    pub fn with_native_dtypes(mut self, native_dtypes: bool) -> Self {
+        self.native_dtypes = native_dtypes;
+        self
+    }
 
-        let carrier_name = if !airline.is_empty() && !control.is_empty() {
-            format!("{}_{}", airline, control)
-        } else {
-            format!("{}_", airline)
-        };
+    /// Write row groups of `row_group_size` rows (default: 262,144).
+    pub fn with_row_group_size(mut self, row_group_size: usize) -> Self {
+        self.row_group_size = row_group_size;
+        self
+    }
+
+    /// Lay out the output files as `layout` (default: one file per carrier).
+    pub fn with_layout(mut self, layout: ParquetLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Keep at most `max_open_partitions` Hive partition files open (default: 64).
+    pub fn with_max_open_partitions(mut self, max_open_partitions: usize) -> Self {
+        self.max_open_partitions = max_open_partitions.max(1);
+        self
+    }
+
+    /// Add the seats per cabin of each flight leg, see [`add_seat_columns`](crate::add_seat_columns).
+    pub fn with_seat_columns(mut self, seat_columns: bool) -> Self {
+        self.seat_columns = seat_columns;
+        self.built_columns = seat_input_columns(&self.columns, seat_columns);
+        self
+    }
 
+    /// Seats of aircraft version codes, used by [`Self::with_seat_columns`].
+    pub fn with_aircraft_versions(mut self, aircraft_versions: Option<AircraftVersions>) -> Self {
+        self.aircraft_versions = aircraft_versions;
+        self
+    }
+
+    /// Only build and output the columns in `columns` (default: all).
+    pub fn with_columns(mut self, columns: ColumnSelection) -> Self {
+        self.built_columns = seat_input_columns(&columns, self.seat_columns);
+        self.columns = columns;
+        self
+    }
+
+    /// Record `source_file` as the SSIM input in the files' metadata.
+    pub fn with_source_file(mut self, source_file: impl Into<String>) -> Self {
+        self.source_file = Some(source_file.into());
+        self
+    }
+
+    /// The key-value metadata of a new file. Only per-carrier files get the
+    /// carrier fields, as the other layouts mix carriers.
+    fn file_metadata(&self) -> SsimFileMetadata {
+        let mut metadata = SsimFileMetadata::new();
+        if let Some(source_file) = &self.source_file {
+            metadata = metadata.with_source_file(source_file);
+        }
+        if let Some(header) = &self.header {
+            metadata = metadata.with_header(header);
+        }
+        if let (ParquetLayout::PerCarrier, Some(carrier)) = (&self.layout, &self.current_carrier) {
+            metadata = metadata.with_carrier(carrier);
+        }
+        metadata
+    }
+
+    fn build_filename(&self, airline: &str, control: &str) -> String {
+        self.file_name(&carrier_file_stem(airline, control))
+    }
+
+    /// `stem` with the extension of the compression.
+    fn file_name(&self, stem: &str) -> String {
         match self.compression.as_str() {
-            "uncompressed" => format!("ssim_{}.parquet", carrier_name),
-            "gzip" => format!("ssim_{}.parquet.gz", carrier_name),
+            "uncompressed" => format!("{}.parquet", stem),
+            "gzip" => format!("{}.parquet.gz", stem),
             comp @ ("snappy" | "lz4" | "zstd" | "brotli" | "lzo") => {
-                format!("ssim_{}.{}.parquet", carrier_name, comp)
+                format!("{}.{}.parquet", stem, comp)
             }
-            _ => format!("ssim_{}.parquet", carrier_name),
+            _ => format!("{}.parquet", stem),
         }
     }
 
-    fn write_accumulated(&mut self) -> PolarsResult<()> {
-        if self.accumulated_batches.is_empty() {
+    /// Create the file `file_name` in `directory` for DataFrames like `first`.
+    fn create_file(
+        &self,
+        directory: &Path,
+        file_name: &str,
+        first: &DataFrame,
+    ) -> PolarsResult<ParquetFileWriter> {
+        let file_path: PathBuf = directory.join(file_name);
+        ParquetFileWriter::create(
+            file_path.to_str().expect("Invalid file path"),
+            &self.compression,
+            self.row_group_size,
+            &self.file_metadata(),
+            first,
+        )
+    }
+
+    /// Append a batch to the file of the current carrier, creating it first
+    /// if this is the carrier's first batch.
+    fn write_batch(&mut self, batch_df: DataFrame) -> PolarsResult<()> {
+        let batch_df = if self.condense_segments && self.serialize_segments {
+            serialize_segment_data_to_json(batch_df)?
+        } else {
+            batch_df
+        };
+
+        if let ParquetLayout::Hive(keys) = &self.layout {
+            for (directory, part) in partition(&batch_df, keys)? {
+                if !self.partitions.contains_key(&directory) {
+                    self.open_partition(&directory, &part)?;
+                }
+                self.partition_writes += 1;
+                if let Some(open) = self.partitions.get_mut(&directory) {
+                    open.last_write = self.partition_writes;
+                    open.file.write(part)?;
+                }
+            }
             return Ok(());
         }
 
-        let (airline, control) = self
-            .current_carrier_info
-            .as_ref()
-            .ok_or_else(|| PolarsError::ComputeError("No carrier info for parquet file".into()))?;
+        if self.file.is_none() {
+            let file_name = match self.layout {
+                ParquetLayout::SingleFile => self.file_name("ssim"),
+                _ => {
+                    let carrier = self.current_carrier.as_ref().ok_or_else(|| {
+                        PolarsError::ComputeError("No carrier info for parquet file".into())
+                    })?;
+                    self.build_filename(
+                        &carrier.airline_designator,
+                        &carrier.control_duplicate_indicator,
+                    )
+                }
+            };
+            let file = self.create_file(Path::new(&self.output_path), &file_name, &batch_df)?;
+            self.file = Some(file);
+        }
+        match &mut self.file {
+            Some(file) => file.write(batch_df),
+            None => Ok(()),
+        }
+    }
+
+    /// Flush the last row group of the open file and close it.
+    fn close_file(&mut self) -> PolarsResult<()> {
+        match self.file.take() {
+            Some(file) => file.finish(),
+            None => Ok(()),
+        }
+    }
 
-        let filename = self.build_filename(airline, control);
-        let file_path: PathBuf = Path::new(&self.output_path).join(filename);
+    /// Open a file in the Hive partition `directory` for DataFrames like `first`,
+    /// closing the least recently written partition if too many are open.
+    ///
+    /// The first file of a directory is `ssim.parquet`; a directory written to
+    /// again after its file was closed gets `part-1.parquet`, `part-2.parquet`, ...
+    fn open_partition(&mut self, directory: &str, first: &DataFrame) -> PolarsResult<()> {
+        if self.partitions.len() >= self.max_open_partitions {
+            let least_recent = self
+                .partitions
+                .iter()
+                .min_by_key(|(_, open)| open.last_write)
+                .map(|(directory, _)| directory.clone());
+            if let Some(least_recent) = least_recent
+                && let Some(open) = self.partitions.remove(&least_recent)
+            {
+                open.file.finish()?;
+            }
+        }
 
-        // Concat all batches for this carrier
-        let mut combined_df = concat_dataframes(std::mem::take(&mut self.accumulated_batches))?;
+        let path = Path::new(&self.output_path).join(directory);
+        create_dir_all(&path).map_err(|e| PolarsError::IO {
+            error: Arc::from(e),
+            msg: Some(format!("Failed to create directory: {}", path.display()).into()),
+        })?;
+        let files = self
+            .partition_files
+            .entry(directory.to_string())
+            .or_insert(0);
+        let stem = match *files {
+            0 => "ssim".to_string(),
+            n => format!("part-{}", n),
+        };
+        *files += 1;
+        let file = self.create_file(&path, &self.file_name(&stem), first)?;
+        self.partitions.insert(
+            directory.to_string(),
+            OpenPartition {
+                file,
+                last_write: self.partition_writes,
+            },
+        );
+        Ok(())
+    }
 
-        if self.condense_segments && self.serialize_segments {
-            combined_df = serialize_segment_data_to_json(combined_df)?;
+    /// Flush and close every open Hive partition file.
+    fn close_partitions(&mut self) -> PolarsResult<()> {
+        for (_, open) in self.partitions.drain() {
+            open.file.finish()?;
         }
+        Ok(())
+    }
 
-        to_parquet(
-            &mut combined_df,
-            file_path.to_str().expect("Invalid file path"),
-            &self.compression,
-        )?;
+    /// Write the combined DataFrame of a batch of `carrier` to its file.
+    fn push_combined(
+        &mut self,
+        carrier: Option<&CarrierRecord>,
+        batch_df: DataFrame,
+    ) -> PolarsResult<()> {
+        if let Some(c) = carrier {
+            self.current_carrier = Some(c.clone());
+        }
 
+        let mut batch_df = self.built_columns.project(batch_df)?;
+        if self.native_dtypes {
+            batch_df = to_native_dtypes(batch_df)?;
+        }
+        if self.seat_columns && batch_df.width() > 0 {
+            batch_df = add_selected_seat_columns(
+                &batch_df,
+                self.aircraft_versions.as_ref(),
+                &self.columns,
+            )?;
+        }
+        if batch_df.height() > 0 {
+            self.write_batch(batch_df)?;
+        }
         Ok(())
     }
 }
@@ -653,40 +2328,163 @@ impl BatchProcessor for ParquetWriterProcessor {
         segment_batch: Vec<SegmentRecords<'_>>,
         carrier: Option<&CarrierRecord>,
     ) -> PolarsResult<()> {
-        // Store only the fields needed for filename generation (avoids cloning entire CarrierRecord)
-        if let Some(c) = carrier {
-            self.current_carrier_info = Some((
-                c.airline_designator.clone(),
-                c.control_duplicate_indicator.clone(),
-            ));
-        }
-
         let (carrier_df, flight_df, segment_df) =
-            convert_to_dataframes(carrier, flight_batch, segment_batch)?;
-
+            convert_to_dataframes(carrier, flight_batch, segment_batch, &self.built_columns)?;
         let batch_df =
             combine_all_dataframes(carrier_df, flight_df, segment_df, self.condense_segments)?;
-        if batch_df.height() > 0 {
-            self.accumulated_batches.push(batch_df);
-        }
+        self.push_combined(carrier, batch_df)
+    }
+
+    fn batch_layout(&self) -> Option<BatchLayout> {
+        Some(BatchLayout::Combined {
+            condense_segments: self.condense_segments,
+        })
+    }
+
+    fn process_frames(
+        &mut self,
+        frames: BatchFrames,
+        carrier: Option<&CarrierRecord>,
+    ) -> PolarsResult<()> {
+        self.push_combined(carrier, frames.into_combined()?)
+    }
+
+    fn on_header(&mut self, header: &HeaderRecord) -> PolarsResult<()> {
+        self.header = Some(header.clone());
         Ok(())
     }
 
     fn on_carrier_complete(&mut self, _carrier: Option<&CarrierRecord>) -> PolarsResult<()> {
-        self.write_accumulated()?;
-        self.current_carrier_info = None;
+        if self.layout == ParquetLayout::PerCarrier {
+            self.close_file()?;
+        } else if self.layout.partitions_by_airline() {
+            // Every open partition holds rows of the carrier that just ended.
+            self.close_partitions()?;
+        }
+        self.current_carrier = None;
         Ok(())
     }
 
     fn finalize(&mut self) -> PolarsResult<()> {
-        self.write_accumulated()
+        self.close_file()?;
+        self.close_partitions()
     }
 }
 
+/// A Hive partition file of [`ParquetWriterProcessor`].
+struct OpenPartition {
+    file: ParquetFileWriter,
+    /// The partition write that last went to this file, to find the least recently written.
+    last_write: u64,
+}
+
 // ============================================================================
 // Public API Functions
 // ============================================================================
 
+/// Reader settings shared by the `ssim_to_*_with_options` functions.
+///
+/// # Example
+/// ```ignore
+/// let options = SsimOptions {
+///     parse_mode: ParseMode::Lenient,
+///     ..Default::default()
+/// };
+/// let (df, diagnostics) = ssim_to_dataframe_with_options("./ssim.dat", None, None, &options)?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SsimOptions {
+    /// Number of records per batch. Default: 10,000.
+    pub batch_size: Option<usize>,
+    /// Read buffer size in bytes. Default: 8 KB.
+    pub buffer_size: Option<usize>,
+    /// Whether malformed lines fail the read or are skipped and reported.
+    pub parse_mode: ParseMode,
+    /// Number of offending lines kept in the returned [`Diagnostics`]. Default: 100.
+    pub max_reported_issues: Option<usize>,
+    /// Convert dates, times, UTC offsets, leg sequence numbers, days of
+    /// operation and station/aircraft/service codes to native Polars dtypes
+    /// instead of keeping the raw SSIM text.
+    pub native_dtypes: bool,
+    /// Output columns, in order. Unselected flight leg and segment fields are
+    /// never parsed. Default: every column.
+    pub columns: ColumnSelection,
+    /// Carrier blocks and flight legs to read, matched before parsing. Default: all.
+    pub filter: SsimFilter,
+    /// Memory-map plain files and parse chunks of about this many bytes on
+    /// all cores, see [`ChunkedSsimReader`]. Compressed files and zip archives
+    /// are read sequentially. Default: `None`, read sequentially.
+    pub chunk_size: Option<usize>,
+    /// Rows per Parquet row group. Only used for Parquet output. Default: 262,144.
+    pub row_group_size: Option<usize>,
+    /// Parquet output files. Default: one file per carrier.
+    pub parquet_layout: ParquetLayout,
+    /// Write mode, format and files of CSV output. Default: overwrite a single
+    /// comma-separated file.
+    pub csv: CsvOptions,
+    /// Add the name, level and decoded data of each segment's DEI to the
+    /// segments DataFrame, see [`add_dei_columns`]. Only used for split output.
+    pub decode_deis: bool,
+    /// Add the seats per cabin and in total of each flight leg, see
+    /// [`add_seat_columns`](crate::add_seat_columns). CSV output only gets the per-cabin
+    /// totals, without the nested `cabin_seats` column.
+    pub seat_columns: bool,
+    /// Seats of the aircraft version codes named by flight legs, used with
+    /// `seat_columns`. Default: none, only configurations listing their seats count.
+    pub aircraft_versions: Option<AircraftVersions>,
+}
+
+impl SsimOptions {
+    pub(crate) fn configure<R: LineSource>(&self, reader: SsimReader<R>) -> SsimReader<R> {
+        let max_reported_issues = self
+            .max_reported_issues
+            .unwrap_or(DEFAULT_MAX_REPORTED_ISSUES);
+        reader
+            .with_parse_mode(self.parse_mode)
+            .with_max_reported_issues(max_reported_issues)
+            .with_columns(self.parsed_columns())
+            .with_filter(self.filter.clone())
+    }
+
+    /// The columns to parse: the selection and the inputs of derived columns.
+    pub(crate) fn parsed_columns(&self) -> ColumnSelection {
+        seat_input_columns(&self.columns, self.seat_columns)
+    }
+
+    fn buffer_size(&self) -> usize {
+        self.buffer_size.unwrap_or(DEFAULT_BUFFER_SIZE)
+    }
+
+    /// Wrap an arbitrary source, decompressing gzip, zstd and bzip2 streams.
+    fn wrap_reader<'a, R: Read + 'a>(
+        &self,
+        source: R,
+    ) -> SsimResult<SsimReader<Box<dyn BufRead + 'a>>> {
+        let reader = BufReader::with_capacity(self.buffer_size(), source);
+        let reader = decompress(reader, self.buffer_size()).map_err(|e| SsimError::Io {
+            file_path: None,
+            source: e,
+        })?;
+        Ok(self.configure(SsimReader::from_reader(reader, self.batch_size)))
+    }
+
+    /// Read an SSIM file into `processor`, decompressing it if needed, see [`SsimReader::new`].
+    fn process_file<P: BatchProcessor>(
+        &self,
+        file_path: &str,
+        processor: &mut P,
+    ) -> SsimResult<Diagnostics> {
+        if self.chunk_size.is_some()
+            && let Some(mmap) = map_plain_file(file_path)?
+        {
+            return ChunkedSsimReader::from_mmap(mmap, file_path, self).process(processor);
+        }
+
+        let reader = SsimReader::new(file_path, self.batch_size, Some(self.buffer_size()))?;
+        self.configure(reader).process(processor)
+    }
+}
+
 /// Parse SSIM file into a single combined DataFrame. Record Types 2, 3, and 4 are merged into one DataFrame with appropriate joins.
 ///
 /// # Arguments
@@ -711,16 +2509,61 @@ pub fn ssim_to_dataframe(
     buffer_size: Option<usize>,
     condense_segments: Option<bool>,
     serialize_segments: Option<bool>,
-) -> PolarsResult<DataFrame> {
-    let mut reader =
-        SsimReader::new(file_path, batch_size, buffer_size).map_err(|e| PolarsError::IO {
-            error: Arc::from(e),
-            msg: None,
-        })?;
+) -> SsimResult<DataFrame> {
+    let options = SsimOptions {
+        batch_size,
+        buffer_size,
+        ..Default::default()
+    };
+    ssim_to_dataframe_with_options(file_path, condense_segments, serialize_segments, &options)
+        .map(|(df, _)| df)
+}
+
+/// Same as [`ssim_to_dataframe`], with reader settings taken from `options` and the
+/// read [`Diagnostics`] returned alongside the DataFrame.
+pub fn ssim_to_dataframe_with_options(
+    file_path: &str,
+    condense_segments: Option<bool>,
+    serialize_segments: Option<bool>,
+    options: &SsimOptions,
+) -> SsimResult<(DataFrame, Diagnostics)> {
+    let mut processor = CombinedDataFrameProcessor::new(
+        condense_segments.unwrap_or(false),
+        serialize_segments.unwrap_or(false),
+    )
+    .with_native_dtypes(options.native_dtypes)
+    .with_seat_columns(options.seat_columns)
+    .with_aircraft_versions(options.aircraft_versions.clone())
+    .with_columns(options.columns.clone());
+    let diagnostics = options.process_file(file_path, &mut processor)?;
+    Ok((processor.into_result(), diagnostics))
+}
 
-    let mut processor = CombinedDataFrameProcessor::new(condense_segments.unwrap_or(false), serialize_segments.unwrap_or(false));
-    reader.process(&mut processor)?;
-    Ok(processor.into_result())
+/// Same as [`ssim_to_dataframe_with_options`], reading from any [`Read`] source
+/// (stdin, an in-memory buffer, a network stream) instead of a file path.
+///
+/// # Example
+/// ```ignore
+/// let bytes = std::fs::read("./ssim.dat")?;
+/// let (df, diagnostics) =
+///     ssim_to_dataframe_from_reader(bytes.as_slice(), None, None, &SsimOptions::default())?;
+/// ```
+pub fn ssim_to_dataframe_from_reader<R: Read>(
+    source: R,
+    condense_segments: Option<bool>,
+    serialize_segments: Option<bool>,
+    options: &SsimOptions,
+) -> SsimResult<(DataFrame, Diagnostics)> {
+    let mut processor = CombinedDataFrameProcessor::new(
+        condense_segments.unwrap_or(false),
+        serialize_segments.unwrap_or(false),
+    )
+    .with_native_dtypes(options.native_dtypes)
+    .with_seat_columns(options.seat_columns)
+    .with_aircraft_versions(options.aircraft_versions.clone())
+    .with_columns(options.columns.clone());
+    let diagnostics = options.wrap_reader(source)?.process(&mut processor)?;
+    Ok((processor.into_result(), diagnostics))
 }
 
 /// Parse SSIM file into three separate DataFrames based on record types i.e. 2, 3, and 4 from the SSIM file.
@@ -739,28 +2582,167 @@ pub fn ssim_to_dataframes(
     file_path: &str,
     batch_size: Option<usize>,
     buffer_size: Option<usize>,
-) -> PolarsResult<(DataFrame, DataFrame, DataFrame)> {
-    let mut reader =
-        SsimReader::new(file_path, batch_size, buffer_size).map_err(|e| PolarsError::IO {
-            error: Arc::from(e),
-            msg: None,
-        })?;
+) -> SsimResult<(DataFrame, DataFrame, DataFrame)> {
+    let options = SsimOptions {
+        batch_size,
+        buffer_size,
+        ..Default::default()
+    };
+    ssim_to_dataframes_with_options(file_path, &options).map(|(dfs, _)| dfs)
+}
+
+/// Same as [`ssim_to_dataframes`], with reader settings taken from `options` and the
+/// read [`Diagnostics`] returned alongside the DataFrames.
+pub fn ssim_to_dataframes_with_options(
+    file_path: &str,
+    options: &SsimOptions,
+) -> SsimResult<((DataFrame, DataFrame, DataFrame), Diagnostics)> {
+    let mut processor = SplitDataFrameProcessor::new()
+        .with_native_dtypes(options.native_dtypes)
+        .with_decode_deis(options.decode_deis)
+        .with_seat_columns(options.seat_columns)
+        .with_aircraft_versions(options.aircraft_versions.clone())
+        .with_columns(options.columns.clone());
+    let diagnostics = options.process_file(file_path, &mut processor)?;
+    Ok((processor.into_result()?, diagnostics))
+}
+
+/// Same as [`ssim_to_dataframes_with_options`], reading from any [`Read`] source.
+pub fn ssim_to_dataframes_from_reader<R: Read>(
+    source: R,
+    options: &SsimOptions,
+) -> SsimResult<((DataFrame, DataFrame, DataFrame), Diagnostics)> {
+    let mut processor = SplitDataFrameProcessor::new()
+        .with_native_dtypes(options.native_dtypes)
+        .with_decode_deis(options.decode_deis)
+        .with_seat_columns(options.seat_columns)
+        .with_aircraft_versions(options.aircraft_versions.clone())
+        .with_columns(options.columns.clone());
+    let diagnostics = options.wrap_reader(source)?.process(&mut processor)?;
+    Ok((processor.into_result()?, diagnostics))
+}
+
+/// Parse SSIM file into dated flight legs: the flights of [`ssim_to_dataframes`]
+/// expanded into one row per operating date by [`expand_to_dated_flights`].
+/// Each leg carries the `time_mode` of its carrier, which [`add_leg_times`](crate::add_leg_times)
+/// needs to tell UTC from local times. It is read even if a column selection leaves it out.
+///
+/// # Arguments
+/// * `file_path` - Path to the SSIM file
+/// * `date_from` - Optional first flight date to keep
+/// * `date_to` - Optional last flight date to keep. Needed if a period runs until further notice.
+/// * `batch_size` - Optional batch size for processing
+/// * `buffer_size` - Optional buffer size for reading
+///
+/// # Example
+/// ```ignore
+/// let dated = ssim_to_dated_flights("./ssim.dat", NaiveDate::from_ymd_opt(2025, 3, 30), NaiveDate::from_ymd_opt(2025, 10, 25), None, None)?;
+/// ```
+pub fn ssim_to_dated_flights(
+    file_path: &str,
+    date_from: Option<NaiveDate>,
+    date_to: Option<NaiveDate>,
+    batch_size: Option<usize>,
+    buffer_size: Option<usize>,
+) -> SsimResult<DataFrame> {
+    let options = SsimOptions {
+        batch_size,
+        buffer_size,
+        ..Default::default()
+    };
+    ssim_to_dated_flights_with_options(file_path, date_from, date_to, &options).map(|(df, _)| df)
+}
+
+/// Same as [`ssim_to_dated_flights`], with reader settings taken from `options` and the
+/// read [`Diagnostics`] returned alongside the DataFrame.
+pub fn ssim_to_dated_flights_with_options(
+    file_path: &str,
+    date_from: Option<NaiveDate>,
+    date_to: Option<NaiveDate>,
+    options: &SsimOptions,
+) -> SsimResult<(DataFrame, Diagnostics)> {
+    let ((carriers, flights, _), diagnostics) =
+        ssim_to_dataframes_with_options(file_path, &dated_flight_options(options))?;
+    Ok((
+        dated_flights(carriers, flights, date_from, date_to, &options.columns)?,
+        diagnostics,
+    ))
+}
+
+/// Same as [`ssim_to_dated_flights_with_options`], reading from any [`Read`] source.
+pub fn ssim_to_dated_flights_from_reader<R: Read>(
+    source: R,
+    date_from: Option<NaiveDate>,
+    date_to: Option<NaiveDate>,
+    options: &SsimOptions,
+) -> SsimResult<(DataFrame, Diagnostics)> {
+    let ((carriers, flights, _), diagnostics) =
+        ssim_to_dataframes_from_reader(source, &dated_flight_options(options))?;
+    Ok((
+        dated_flights(carriers, flights, date_from, date_to, &options.columns)?,
+        diagnostics,
+    ))
+}
 
-    let mut processor = SplitDataFrameProcessor::new();
-    reader.process(&mut processor)?;
-    processor.into_result()
+/// Carrier columns that [`dated_flights`] joins the `time_mode` onto the legs by.
+const TIME_MODE_KEYS: &[&str] = &["airline_designator", "control_duplicate_indicator"];
+
+/// `options` with the inputs of [`dated_flights`] added to its column selection.
+fn dated_flight_options(options: &SsimOptions) -> SsimOptions {
+    SsimOptions {
+        columns: options
+            .columns
+            .with_inputs(TIME_MODE_KEYS)
+            .with_inputs(&["time_mode"]),
+        ..options.clone()
+    }
+}
+
+/// `flights` expanded into dated flight legs, with the `time_mode` of their
+/// carrier for [`add_leg_times`](crate::add_leg_times). The join keys are
+/// dropped again if `columns` does not select them.
+fn dated_flights(
+    carriers: DataFrame,
+    flights: DataFrame,
+    date_from: Option<NaiveDate>,
+    date_to: Option<NaiveDate>,
+    columns: &ColumnSelection,
+) -> PolarsResult<DataFrame> {
+    let keys = [
+        col("airline_designator"),
+        col("control_duplicate_indicator"),
+    ];
+    let time_modes = carriers
+        .lazy()
+        .select([keys[0].clone(), keys[1].clone(), col("time_mode")])
+        .unique_stable_generic(Some(keys.to_vec()), UniqueKeepStrategy::First);
+    let flights = flights
+        .lazy()
+        .join(
+            time_modes,
+            keys.clone(),
+            keys,
+            JoinArgs {
+                maintain_order: MaintainOrderJoin::Left,
+                ..JoinArgs::new(JoinType::Left)
+            },
+        )
+        .collect()?;
+    let dated = expand_to_dated_flights(&flights, date_from, date_to)?;
+    Ok(columns.drop_inputs(dated, TIME_MODE_KEYS))
 }
 
 /// Parse SSIM file and write to CSV (streaming).
 ///
 /// # Arguments
 /// * `file_path` - Path to the SSIM file
-/// * `output_path` - Path for the output CSV file
+/// * `output_path` - Path for the output CSV file, which is replaced if it exists
 /// * `batch_size` - Optional batch size for processing
 /// * `buffer_size` - Optional buffer size for reading
 /// * `condense_segments` - If true, aggregates segments into a JSON string column (smaller file).
 ///   If false (default), each segment is a separate row.
-/// 
+///
+/// See [`SsimOptions::csv`] to append, change the format, compress or write a file per carrier.
 ///
 ///  # Example
 /// ```ignore
@@ -776,15 +2758,178 @@ pub fn ssim_to_csv(
     batch_size: Option<usize>,
     buffer_size: Option<usize>,
     condense_segments: Option<bool>,
-) -> PolarsResult<()> {
-    let mut reader =
-        SsimReader::new(file_path, batch_size, buffer_size).map_err(|e| PolarsError::IO {
-            error: Arc::from(e),
-            msg: None,
-        })?;
+) -> SsimResult<()> {
+    let options = SsimOptions {
+        batch_size,
+        buffer_size,
+        ..Default::default()
+    };
+    ssim_to_csv_with_options(file_path, output_path, condense_segments, &options).map(|_| ())
+}
+
+/// Same as [`ssim_to_csv`], with reader settings taken from `options`. Returns the read [`Diagnostics`].
+pub fn ssim_to_csv_with_options(
+    file_path: &str,
+    output_path: &str,
+    condense_segments: Option<bool>,
+    options: &SsimOptions,
+) -> SsimResult<Diagnostics> {
+    let mut processor = CsvWriterProcessor::new(output_path, condense_segments.unwrap_or(false))?
+        .with_options(options.csv.clone())
+        .with_native_dtypes(options.native_dtypes)
+        .with_seat_columns(options.seat_columns)
+        .with_aircraft_versions(options.aircraft_versions.clone())
+        .with_columns(options.columns.clone());
+    options.process_file(file_path, &mut processor)
+}
+
+/// Same as [`ssim_to_csv_with_options`], reading from any [`Read`] source.
+pub fn ssim_to_csv_from_reader<R: Read>(
+    source: R,
+    output_path: &str,
+    condense_segments: Option<bool>,
+    options: &SsimOptions,
+) -> SsimResult<Diagnostics> {
+    let mut processor = CsvWriterProcessor::new(output_path, condense_segments.unwrap_or(false))?
+        .with_options(options.csv.clone())
+        .with_native_dtypes(options.native_dtypes)
+        .with_seat_columns(options.seat_columns)
+        .with_aircraft_versions(options.aircraft_versions.clone())
+        .with_columns(options.columns.clone());
+    options.wrap_reader(source)?.process(&mut processor)
+}
+
+/// Parse SSIM file and write to an Arrow IPC (Feather v2) file (streaming).
+///
+/// # Arguments
+/// * `file_path` - Path to the SSIM file
+/// * `output_path` - Path for the output IPC file
+/// * `compression` - Optional compression: "lz4", "zstd" or "uncompressed" (default)
+/// * `batch_size` - Optional batch size for processing
+/// * `buffer_size` - Optional buffer size for reading
+/// * `condense_segments` - If true, nests segments in a `List<Struct>` column per flight leg.
+///   If false (default), each segment is a separate row.
+///
+/// # Example
+/// ```ignore
+/// ssim_to_ipc("./ssim.dat", "./ssim.arrow", Some("zstd"), Some(10000), Some(51200), Some(false));
+/// ```
+pub fn ssim_to_ipc(
+    file_path: &str,
+    output_path: &str,
+    compression: Option<&str>,
+    batch_size: Option<usize>,
+    buffer_size: Option<usize>,
+    condense_segments: Option<bool>,
+) -> SsimResult<()> {
+    let options = SsimOptions {
+        batch_size,
+        buffer_size,
+        ..Default::default()
+    };
+    ssim_to_ipc_with_options(
+        file_path,
+        output_path,
+        compression,
+        condense_segments,
+        &options,
+    )
+    .map(|_| ())
+}
+
+/// Same as [`ssim_to_ipc`], with reader settings taken from `options`. Returns the read [`Diagnostics`].
+pub fn ssim_to_ipc_with_options(
+    file_path: &str,
+    output_path: &str,
+    compression: Option<&str>,
+    condense_segments: Option<bool>,
+    options: &SsimOptions,
+) -> SsimResult<Diagnostics> {
+    let mut processor =
+        IpcWriterProcessor::new(output_path, compression, condense_segments.unwrap_or(false))?
+            .with_native_dtypes(options.native_dtypes)
+            .with_seat_columns(options.seat_columns)
+            .with_aircraft_versions(options.aircraft_versions.clone())
+            .with_columns(options.columns.clone());
+    options.process_file(file_path, &mut processor)
+}
+
+/// Same as [`ssim_to_ipc_with_options`], reading from any [`Read`] source.
+pub fn ssim_to_ipc_from_reader<R: Read>(
+    source: R,
+    output_path: &str,
+    compression: Option<&str>,
+    condense_segments: Option<bool>,
+    options: &SsimOptions,
+) -> SsimResult<Diagnostics> {
+    let mut processor =
+        IpcWriterProcessor::new(output_path, compression, condense_segments.unwrap_or(false))?
+            .with_native_dtypes(options.native_dtypes)
+            .with_seat_columns(options.seat_columns)
+            .with_aircraft_versions(options.aircraft_versions.clone())
+            .with_columns(options.columns.clone());
+    options.wrap_reader(source)?.process(&mut processor)
+}
+
+/// Parse SSIM file and write to newline-delimited JSON (streaming).
+///
+/// # Arguments
+/// * `file_path` - Path to the SSIM file
+/// * `output_path` - Path for the output NDJSON file
+/// * `batch_size` - Optional batch size for processing
+/// * `buffer_size` - Optional buffer size for reading
+/// * `condense_segments` - If true, nests segments in a `segment_data` array per flight leg.
+///   If false (default), each segment is a separate row.
+///
+/// # Example
+/// ```ignore
+/// ssim_to_ndjson("./ssim.dat", "./ssim.ndjson", Some(10000), Some(51200), Some(true));
+/// ```
+pub fn ssim_to_ndjson(
+    file_path: &str,
+    output_path: &str,
+    batch_size: Option<usize>,
+    buffer_size: Option<usize>,
+    condense_segments: Option<bool>,
+) -> SsimResult<()> {
+    let options = SsimOptions {
+        batch_size,
+        buffer_size,
+        ..Default::default()
+    };
+    ssim_to_ndjson_with_options(file_path, output_path, condense_segments, &options).map(|_| ())
+}
+
+/// Same as [`ssim_to_ndjson`], with reader settings taken from `options`. Returns the read [`Diagnostics`].
+pub fn ssim_to_ndjson_with_options(
+    file_path: &str,
+    output_path: &str,
+    condense_segments: Option<bool>,
+    options: &SsimOptions,
+) -> SsimResult<Diagnostics> {
+    let mut processor =
+        NdjsonWriterProcessor::new(output_path, condense_segments.unwrap_or(false))?
+            .with_native_dtypes(options.native_dtypes)
+            .with_seat_columns(options.seat_columns)
+            .with_aircraft_versions(options.aircraft_versions.clone())
+            .with_columns(options.columns.clone());
+    options.process_file(file_path, &mut processor)
+}
 
-    let mut processor = CsvWriterProcessor::new(output_path, condense_segments.unwrap_or(false))?;
-    reader.process(&mut processor)
+/// Same as [`ssim_to_ndjson_with_options`], reading from any [`Read`] source.
+pub fn ssim_to_ndjson_from_reader<R: Read>(
+    source: R,
+    output_path: &str,
+    condense_segments: Option<bool>,
+    options: &SsimOptions,
+) -> SsimResult<Diagnostics> {
+    let mut processor =
+        NdjsonWriterProcessor::new(output_path, condense_segments.unwrap_or(false))?
+            .with_native_dtypes(options.native_dtypes)
+            .with_seat_columns(options.seat_columns)
+            .with_aircraft_versions(options.aircraft_versions.clone())
+            .with_columns(options.columns.clone());
+    options.wrap_reader(source)?.process(&mut processor)
 }
 
 /// Parse SSIM file and write to Parquet files (one per carrier).
@@ -815,18 +2960,68 @@ pub fn ssim_to_parquets(
     buffer_size: Option<usize>,
     condense_segments: Option<bool>,
     serialize_segments: Option<bool>,
-) -> PolarsResult<()> {
-    let mut reader =
-        SsimReader::new(file_path, batch_size, buffer_size).map_err(|e| PolarsError::IO {
-            error: Arc::from(e),
-            msg: None,
-        })?;
+) -> SsimResult<()> {
+    let options = SsimOptions {
+        batch_size,
+        buffer_size,
+        ..Default::default()
+    };
+    ssim_to_parquets_with_options(
+        file_path,
+        output_path,
+        compression,
+        condense_segments,
+        serialize_segments,
+        &options,
+    )
+    .map(|_| ())
+}
 
+/// Same as [`ssim_to_parquets`], with reader settings taken from `options`. Returns the read [`Diagnostics`].
+pub fn ssim_to_parquets_with_options(
+    file_path: &str,
+    output_path: Option<&str>,
+    compression: Option<&str>,
+    condense_segments: Option<bool>,
+    serialize_segments: Option<bool>,
+    options: &SsimOptions,
+) -> SsimResult<Diagnostics> {
+    let mut processor = ParquetWriterProcessor::new(
+        output_path.unwrap_or("."),
+        compression,
+        condense_segments.unwrap_or(false),
+        serialize_segments.unwrap_or(false),
+    )?
+    .with_native_dtypes(options.native_dtypes)
+    .with_row_group_size(options.row_group_size.unwrap_or(DEFAULT_ROW_GROUP_SIZE))
+    .with_layout(options.parquet_layout.clone())
+    .with_seat_columns(options.seat_columns)
+    .with_aircraft_versions(options.aircraft_versions.clone())
+    .with_columns(options.columns.clone())
+    .with_source_file(source_file_name(file_path));
+    options.process_file(file_path, &mut processor)
+}
+
+/// Same as [`ssim_to_parquets_with_options`], reading from any [`Read`] source.
+pub fn ssim_to_parquets_from_reader<R: Read>(
+    source: R,
+    output_path: Option<&str>,
+    compression: Option<&str>,
+    condense_segments: Option<bool>,
+    serialize_segments: Option<bool>,
+    options: &SsimOptions,
+) -> SsimResult<Diagnostics> {
     let mut processor = ParquetWriterProcessor::new(
         output_path.unwrap_or("."),
         compression,
         condense_segments.unwrap_or(false),
         serialize_segments.unwrap_or(false),
-    )?;
-    reader.process(&mut processor)
+    )?
+    .with_native_dtypes(options.native_dtypes)
+    .with_row_group_size(options.row_group_size.unwrap_or(DEFAULT_ROW_GROUP_SIZE))
+    .with_layout(options.parquet_layout.clone())
+    .with_seat_columns(options.seat_columns)
+    .with_aircraft_versions(options.aircraft_versions.clone())
+    .with_columns(options.columns.clone());
+    options.wrap_reader(source)?.process(&mut processor)
 }
@@ -0,0 +1,336 @@
+//! Seats per cabin from the aircraft configuration/version of flight legs.
+//!
+//! The `aircraft_configuration` field of a flight leg holds the seats per
+//! cabin as codes and counts, e.g. `J30W24Y250`, and may name a carrier's
+//! aircraft version after `VV`, e.g. `VV738H189` or `Y189VV738H189`.
+//! [`AircraftConfiguration::parse`] decodes the field; legs that only carry a
+//! version code get their seats from an [`AircraftVersions`] lookup table,
+//! and [`add_seat_columns`] does both for a flights DataFrame.
+
+use crate::utils::ssim_columns::ColumnSelection;
+use crate::utils::ssim_expand::{field_error, required_column};
+use crate::utils::ssim_fields::FieldError;
+use polars::prelude::*;
+use std::collections::HashMap;
+
+/// Name of the `List<Struct>` cabin seats column added by [`add_seat_columns`].
+pub const CABIN_SEATS_COLUMN: &str = "cabin_seats";
+/// Name of the total seats column added by [`add_seat_columns`].
+pub const TOTAL_SEATS_COLUMN: &str = "total_seats";
+
+/// Columns read by [`add_seat_columns`].
+pub const SEAT_INPUT_COLUMNS: &[&str] = &["airline_designator", "aircraft_configuration"];
+
+const VERSION_MARKER: &str = "VV";
+const TO: &str = "to count seats";
+
+/// A cabin of the per-cabin seat totals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Cabin {
+    /// `F`, also counting `A` and `P`.
+    First,
+    /// `C`, also counting `J`, `D`, `I` and `Z`.
+    Business,
+    /// `W`.
+    PremiumEconomy,
+    /// `Y` and every other code.
+    Economy,
+}
+
+impl Cabin {
+    pub const ALL: [Cabin; 4] = [
+        Cabin::First,
+        Cabin::Business,
+        Cabin::PremiumEconomy,
+        Cabin::Economy,
+    ];
+
+    /// The cabin a compartment or booking designator code counts towards.
+    pub fn from_code(code: char) -> Self {
+        match code {
+            'F' | 'A' | 'P' => Cabin::First,
+            'C' | 'J' | 'D' | 'I' | 'Z' => Cabin::Business,
+            'W' => Cabin::PremiumEconomy,
+            _ => Cabin::Economy,
+        }
+    }
+
+    pub fn code(&self) -> char {
+        match self {
+            Cabin::First => 'F',
+            Cabin::Business => 'C',
+            Cabin::PremiumEconomy => 'W',
+            Cabin::Economy => 'Y',
+        }
+    }
+
+    /// Name of the per-cabin seats column added by [`add_seat_columns`], e.g. `seats_f`.
+    pub fn column_name(&self) -> String {
+        format!("seats_{}", self.code().to_ascii_lowercase())
+    }
+}
+
+/// A decoded aircraft configuration/version field.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AircraftConfiguration {
+    /// Cabin code and seats, in field order, e.g. `[('J', 30), ('Y', 250)]`.
+    pub cabins: Vec<(char, u16)>,
+    /// The aircraft version code after `VV`, if any.
+    pub version: Option<String>,
+}
+
+impl AircraftConfiguration {
+    /// Decode a configuration such as `J30W24Y250`, `VV738H189` or `Y189VV738H189`.
+    ///
+    /// # Errors
+    /// Returns a [`FieldError`] if the part before `VV` is not a sequence of
+    /// cabin codes, each followed by its seat count.
+    pub fn parse(value: &str) -> Result<Self, FieldError> {
+        let trimmed = value.trim();
+        let (cabins, version) = match trimmed.split_once(VERSION_MARKER) {
+            Some((cabins, version)) => (cabins, Some(version.trim()).filter(|v| !v.is_empty())),
+            None => (trimmed, None),
+        };
+        let error = || FieldError {
+            field: "aircraft_configuration",
+            value: value.to_string(),
+            reason: "expected cabin codes with seat counts such as 'J30W24Y250'",
+        };
+
+        let mut parsed = Vec::new();
+        let mut rest = cabins;
+        while let Some(code) = rest.chars().next() {
+            if !code.is_ascii_uppercase() {
+                return Err(error());
+            }
+            let digits = rest[1..]
+                .find(|c: char| !c.is_ascii_digit())
+                .map_or(rest.len(), |end| end + 1);
+            let seats = rest[1..digits].parse().map_err(|_| error())?;
+            parsed.push((code, seats));
+            rest = &rest[digits..];
+        }
+        Ok(AircraftConfiguration {
+            cabins: parsed,
+            version: version.map(String::from),
+        })
+    }
+
+    /// Seats of every cabin.
+    pub fn total_seats(&self) -> u32 {
+        self.cabins.iter().map(|(_, seats)| *seats as u32).sum()
+    }
+
+    /// Seats of the codes that count towards `cabin`.
+    pub fn seats(&self, cabin: Cabin) -> u32 {
+        self.cabins
+            .iter()
+            .filter(|(code, _)| Cabin::from_code(*code) == cabin)
+            .map(|(_, seats)| *seats as u32)
+            .sum()
+    }
+}
+
+/// Seat configurations of aircraft version codes, for legs whose
+/// `aircraft_configuration` names a version instead of its seats.
+///
+/// Entries can be limited to one airline; those apply before entries for
+/// every airline.
+#[derive(Debug, Clone, Default)]
+pub struct AircraftVersions {
+    versions: HashMap<(String, String), AircraftConfiguration>,
+}
+
+impl AircraftVersions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read a version table from a CSV file with a header row and the
+    /// columns `aircraft_version` and `aircraft_configuration`, and optionally
+    /// `airline_designator`:
+    ///
+    /// ```text
+    /// airline_designator,aircraft_version,aircraft_configuration
+    /// XX,738H189,Y189
+    /// ,32A,C12Y150
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read, a column is missing or a
+    /// configuration does not decode.
+    pub fn from_csv(file_path: &str) -> PolarsResult<Self> {
+        let table = CsvReadOptions::default()
+            .with_has_header(true)
+            .with_infer_schema_length(Some(0))
+            .try_into_reader_with_file_path(Some(file_path.into()))?
+            .finish()?;
+        let versions = required_column(&table, "aircraft_version", "in an aircraft version table")?;
+        let configurations = required_column(
+            &table,
+            "aircraft_configuration",
+            "in an aircraft version table",
+        )?;
+        let airlines = table.column("airline_designator").ok();
+
+        let mut lookup = Self::new();
+        for row in 0..table.height() {
+            let text = |column: &Column| -> PolarsResult<String> {
+                Ok(column.str()?.get(row).unwrap_or_default().to_string())
+            };
+            let airline = airlines.map(text).transpose()?.unwrap_or_default();
+            let configuration =
+                AircraftConfiguration::parse(&text(configurations)?).map_err(field_error)?;
+            lookup.insert(&airline, &text(versions)?, configuration);
+        }
+        Ok(lookup)
+    }
+
+    /// Add the configuration of `version`, for `airline` or, if blank, for every airline.
+    pub fn insert(&mut self, airline: &str, version: &str, configuration: AircraftConfiguration) {
+        let key = (airline.trim().to_string(), version.trim().to_string());
+        self.versions.insert(key, configuration);
+    }
+
+    /// The configuration of `version` for `airline`.
+    pub fn get(&self, airline: &str, version: &str) -> Option<&AircraftConfiguration> {
+        let version = version.trim().to_string();
+        self.versions
+            .get(&(airline.trim().to_string(), version.clone()))
+            .or_else(|| self.versions.get(&(String::new(), version)))
+    }
+
+    pub fn len(&self) -> usize {
+        self.versions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.versions.is_empty()
+    }
+}
+
+/// Add the seats per cabin and in total of every flight leg of `legs`.
+///
+/// `legs` needs the `aircraft_configuration` column; its optional
+/// `airline_designator` selects airline entries of `versions`. A leg whose
+/// configuration lists no seats, only a version code, or does not decode at
+/// all gets the seats of the version in `versions`. The added columns are
+/// null for legs whose seats are still unknown:
+///
+/// * `cabin_seats` — `List<Struct{cabin, seats}>` of the cabin codes and seats
+/// * `seats_f`, `seats_c`, `seats_w`, `seats_y` — `UInt32` seats per [`Cabin`]
+/// * `total_seats` — `UInt32` seats of every cabin
+///
+/// # Errors
+/// Returns a `ColumnNotFound` error if `aircraft_configuration` is missing.
+///
+/// # Example
+/// ```no_run
+/// use rusty_ssim_core::{AircraftVersions, add_seat_columns, ssim_to_dataframes};
+///
+/// let (_, flights, _) = ssim_to_dataframes("schedule.ssim", None, None)?;
+/// let versions = AircraftVersions::from_csv("versions.csv")?;
+/// let seats = add_seat_columns(&flights, Some(&versions))?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn add_seat_columns(
+    legs: &DataFrame,
+    versions: Option<&AircraftVersions>,
+) -> PolarsResult<DataFrame> {
+    let configurations = required_column(legs, "aircraft_configuration", TO)?;
+    let configurations = configurations.cast(&DataType::String)?;
+    let airlines = match legs.column("airline_designator") {
+        Ok(column) => Some(column.cast(&DataType::String)?),
+        Err(_) => None,
+    };
+
+    let mut rows = Vec::new();
+    let mut codes = Vec::new();
+    let mut seats = Vec::new();
+    let mut totals: [Vec<Option<u32>>; 5] = Default::default();
+    for (row, value) in configurations.str()?.iter().enumerate() {
+        let airline = match &airlines {
+            Some(airlines) => airlines.str()?.get(row).unwrap_or_default(),
+            None => "",
+        };
+        let resolved = value.and_then(|value| resolve(value, airline, versions));
+        let configuration = resolved.as_deref();
+        for (cabin, values) in Cabin::ALL.iter().zip(totals.iter_mut()) {
+            values.push(configuration.map(|c| c.seats(*cabin)));
+        }
+        totals[4].push(configuration.map(AircraftConfiguration::total_seats));
+        for (code, count) in configuration.into_iter().flat_map(|c| &c.cabins) {
+            rows.push(row as IdxSize);
+            codes.push(code.to_string());
+            seats.push(*count);
+        }
+    }
+
+    const ROW: &str = "__seat_row";
+    let cabins = df!(ROW => rows, "cabin" => codes, "seats" => seats)?
+        .lazy()
+        .group_by_stable([col(ROW)])
+        .agg([as_struct(vec![col("cabin"), col("seats")]).alias(CABIN_SEATS_COLUMN)]);
+    let mut seated = legs
+        .clone()
+        .with_row_index(ROW.into(), None)?
+        .lazy()
+        .join(
+            cabins,
+            [col(ROW)],
+            [col(ROW)],
+            JoinArgs {
+                maintain_order: MaintainOrderJoin::Left,
+                ..JoinArgs::new(JoinType::Left)
+            },
+        )
+        .collect()?
+        .drop(ROW)?;
+
+    let names = Cabin::ALL
+        .iter()
+        .map(Cabin::column_name)
+        .chain([TOTAL_SEATS_COLUMN.to_string()]);
+    for (name, values) in names.zip(totals) {
+        seated.with_column(Column::new(name.into(), values))?;
+    }
+    Ok(seated)
+}
+
+/// The columns to parse for `columns`, with the inputs of [`add_seat_columns`]
+/// if `seat_columns` is set.
+pub(crate) fn seat_input_columns(columns: &ColumnSelection, seat_columns: bool) -> ColumnSelection {
+    if seat_columns {
+        columns.with_inputs(SEAT_INPUT_COLUMNS)
+    } else {
+        columns.clone()
+    }
+}
+
+/// [`add_seat_columns`] for legs parsed with [`seat_input_columns`], dropping
+/// the inputs that `columns` does not select.
+pub(crate) fn add_selected_seat_columns(
+    legs: &DataFrame,
+    versions: Option<&AircraftVersions>,
+    columns: &ColumnSelection,
+) -> PolarsResult<DataFrame> {
+    Ok(columns.drop_inputs(add_seat_columns(legs, versions)?, SEAT_INPUT_COLUMNS))
+}
+
+/// The configuration of a leg: its own seats, else those of its version.
+fn resolve<'a>(
+    value: &str,
+    airline: &str,
+    versions: Option<&'a AircraftVersions>,
+) -> Option<std::borrow::Cow<'a, AircraftConfiguration>> {
+    use std::borrow::Cow;
+
+    let version = match AircraftConfiguration::parse(value) {
+        Ok(configuration) if !configuration.cabins.is_empty() => {
+            return Some(Cow::Owned(configuration));
+        }
+        Ok(configuration) => configuration.version?,
+        Err(_) => value.trim().to_string(),
+    };
+    versions?.get(airline, &version).map(Cow::Borrowed)
+}
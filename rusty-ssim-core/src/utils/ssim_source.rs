@@ -0,0 +1,382 @@
+//! Opening SSIM inputs, with transparent decompression.
+//!
+//! Feeds are often delivered as `.gz`, `.zst`, `.bz2` or `.zip` files. The
+//! compression is detected from the leading magic bytes, so a misnamed file
+//! still opens correctly and plain SSIM is never mistaken for an archive
+//! (SSIM lines always start with a record type digit).
+
+use crate::utils::ssim_error::{SsimError, SsimResult};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Cursor, Read};
+use std::str::FromStr;
+use std::sync::mpsc::{Receiver, SyncSender, sync_channel};
+use std::thread::JoinHandle;
+use zip::ZipArchive;
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+const BZIP2_MAGIC: &[u8] = b"BZh";
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+const EMPTY_ZIP_MAGIC: &[u8] = b"PK\x05\x06";
+
+/// Decompressed chunks of zip member data buffered ahead of the reader.
+const ZIP_CHUNKS_IN_FLIGHT: usize = 16;
+
+/// Container format of an SSIM input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+    Bzip2,
+    /// A zip archive; every file member is read as part of the same input.
+    Zip,
+}
+
+impl Compression {
+    /// Detect the compression from the first bytes of the input.
+    pub fn detect(magic: &[u8]) -> Self {
+        if magic.starts_with(GZIP_MAGIC) {
+            Compression::Gzip
+        } else if magic.starts_with(ZSTD_MAGIC) {
+            Compression::Zstd
+        } else if magic.starts_with(BZIP2_MAGIC) {
+            Compression::Bzip2
+        } else if magic.starts_with(ZIP_MAGIC) || magic.starts_with(EMPTY_ZIP_MAGIC) {
+            Compression::Zip
+        } else {
+            Compression::None
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Compression::None => "none",
+            Compression::Gzip => "gzip",
+            Compression::Zstd => "zstd",
+            Compression::Bzip2 => "bzip2",
+            Compression::Zip => "zip",
+        }
+    }
+}
+
+impl FromStr for Compression {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" | "uncompressed" => Ok(Compression::None),
+            "gzip" | "gz" => Ok(Compression::Gzip),
+            "zstd" | "zst" => Ok(Compression::Zstd),
+            "bzip2" | "bz2" => Ok(Compression::Bzip2),
+            "zip" => Ok(Compression::Zip),
+            other => Err(format!("unknown compression '{}'", other)),
+        }
+    }
+}
+
+/// Wrap a stream in the decoder matching its magic bytes.
+///
+/// Zip archives need random access to their central directory and are only
+/// supported through [`open_file`].
+pub(crate) fn decompress<'a, R: BufRead + 'a>(
+    mut reader: R,
+    buffer_size: usize,
+) -> io::Result<Box<dyn BufRead + 'a>> {
+    let compression = Compression::detect(reader.fill_buf()?);
+
+    Ok(match compression {
+        Compression::None => Box::new(reader),
+        Compression::Gzip => Box::new(BufReader::with_capacity(
+            buffer_size,
+            flate2::bufread::MultiGzDecoder::new(reader),
+        )),
+        Compression::Zstd => Box::new(BufReader::with_capacity(
+            buffer_size,
+            zstd::stream::read::Decoder::with_buffer(reader)?,
+        )),
+        Compression::Bzip2 => Box::new(BufReader::with_capacity(
+            buffer_size,
+            bzip2::bufread::MultiBzDecoder::new(reader),
+        )),
+        Compression::Zip => {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "zip archives can only be read from a file path",
+            ));
+        }
+    })
+}
+
+/// Where [`SsimReader`](crate::SsimReader) reads its lines from: any [`BufRead`].
+///
+/// Sealed; [`ChunkedSsimReader`](crate::ChunkedSsimReader) also reads lines borrowed from a
+/// memory-mapped file through it.
+pub trait LineSource: sealed::Sealed {
+    /// A line of text, without its terminator.
+    type Line: std::ops::Deref<Target = str> + AsRef<str> + Send + Sync;
+
+    #[doc(hidden)]
+    fn read_line(&mut self) -> std::io::Result<Option<SourceLine<Self::Line>>>;
+
+    /// Move on to the next file of a source made of several, once `read_line`
+    /// has reached the end of the current one, and return its path.
+    #[doc(hidden)]
+    fn next_member(&mut self) -> std::io::Result<Option<String>> {
+        Ok(None)
+    }
+}
+
+/// A line read by a [`LineSource`].
+#[doc(hidden)]
+pub struct SourceLine<L> {
+    pub text: L,
+    /// Bytes the line takes up in the input, terminator included.
+    pub bytes_read: usize,
+    /// 1-based column of the first invalid UTF-8 byte; `text` is then a lossy copy.
+    pub invalid_utf8_column: Option<usize>,
+}
+
+pub(crate) mod sealed {
+    pub trait Sealed {}
+
+    impl<R: std::io::BufRead> Sealed for R {}
+}
+
+impl<R: BufRead> LineSource for R {
+    type Line = String;
+
+    fn read_line(&mut self) -> std::io::Result<Option<SourceLine<String>>> {
+        let mut buffer = Vec::new();
+        let bytes_read = self.read_until(b'\n', &mut buffer)?;
+        if bytes_read == 0 {
+            return Ok(None);
+        }
+
+        if buffer.ends_with(b"\n") {
+            buffer.pop();
+            if buffer.ends_with(b"\r") {
+                buffer.pop();
+            }
+        }
+
+        let (text, invalid_utf8_column) = match String::from_utf8(buffer) {
+            Ok(text) => (text, None),
+            Err(e) => {
+                let column = e.utf8_error().valid_up_to() + 1;
+                (
+                    String::from_utf8_lossy(e.as_bytes()).into_owned(),
+                    Some(column),
+                )
+            }
+        };
+        Ok(Some(SourceLine {
+            text,
+            bytes_read,
+            invalid_utf8_column,
+        }))
+    }
+}
+
+/// The lines of an SSIM file opened by [`SsimReader::new`](crate::SsimReader::new).
+///
+/// A zip archive is read as one input: its SSIM members follow one another in
+/// archive order, and the reader starts its line numbers and record checks
+/// afresh at each member.
+pub struct FileSource(FileLines);
+
+enum FileLines {
+    Stream(Box<dyn BufRead>),
+    Zip(ZipMembers),
+}
+
+impl sealed::Sealed for FileSource {}
+
+impl LineSource for FileSource {
+    type Line = String;
+
+    fn read_line(&mut self) -> io::Result<Option<SourceLine<String>>> {
+        match &mut self.0 {
+            FileLines::Stream(reader) => LineSource::read_line(reader),
+            FileLines::Zip(members) => LineSource::read_line(members),
+        }
+    }
+
+    fn next_member(&mut self) -> io::Result<Option<String>> {
+        match &mut self.0 {
+            FileLines::Stream(_) => Ok(None),
+            FileLines::Zip(members) => members.next_member(),
+        }
+    }
+}
+
+/// Open `file_path`, detecting its compression.
+pub(crate) fn open_file(file_path: &str, buffer_size: usize) -> SsimResult<FileSource> {
+    let io_error = |source: io::Error| SsimError::Io {
+        file_path: Some(file_path.to_string()),
+        source,
+    };
+
+    let file = File::open(file_path).map_err(io_error)?;
+    let mut reader = BufReader::with_capacity(buffer_size, file);
+
+    if Compression::detect(reader.fill_buf().map_err(io_error)?) == Compression::Zip {
+        let archive = ZipArchive::new(reader).map_err(|e| io_error(e.into()))?;
+        let members = ZipMembers::spawn(archive, file_path, buffer_size).map_err(io_error)?;
+        return Ok(FileSource(FileLines::Zip(members)));
+    }
+
+    decompress(reader, buffer_size)
+        .map(|reader| FileSource(FileLines::Stream(reader)))
+        .map_err(io_error)
+}
+
+/// What the thread reading a zip archive hands over to [`ZipMembers`].
+enum MemberChunk {
+    /// A member starts, at the given `archive.zip!member.ssim` path.
+    Start(String),
+    /// Decompressed data of the current member.
+    Data(Vec<u8>),
+}
+
+/// The SSIM members of a zip archive, decompressed on a background thread.
+///
+/// A member borrows its archive while it is read, so the archive is moved to
+/// a thread of its own that sends the member data over a bounded channel.
+/// Reading stops at the end of each member until [`ZipMembers::next_member`].
+struct ZipMembers {
+    receiver: Receiver<io::Result<MemberChunk>>,
+    chunk: Cursor<Vec<u8>>,
+    /// Path of the member after the current one, once its start is reached.
+    next_member: Option<String>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ZipMembers {
+    fn spawn(
+        archive: ZipArchive<BufReader<File>>,
+        file_path: &str,
+        buffer_size: usize,
+    ) -> io::Result<Self> {
+        let (sender, receiver) = sync_channel(ZIP_CHUNKS_IN_FLIGHT);
+        let file_path = file_path.to_string();
+        let thread = std::thread::Builder::new()
+            .name("ssim-zip-reader".to_string())
+            .spawn(move || send_members(archive, &file_path, buffer_size, &sender))?;
+        Ok(ZipMembers {
+            receiver,
+            chunk: Cursor::default(),
+            next_member: None,
+            thread: Some(thread),
+        })
+    }
+
+    /// Skip the rest of the current member and return the path of the next one.
+    fn next_member(&mut self) -> io::Result<Option<String>> {
+        loop {
+            let unread = self.fill_buf()?.len();
+            if unread == 0 {
+                return Ok(self.next_member.take());
+            }
+            self.consume(unread);
+        }
+    }
+}
+
+impl Read for ZipMembers {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.fill_buf()?.read(buf)?;
+        self.consume(read);
+        Ok(read)
+    }
+}
+
+impl BufRead for ZipMembers {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        while self.chunk.position() == self.chunk.get_ref().len() as u64
+            && self.next_member.is_none()
+        {
+            match self.receiver.recv() {
+                Ok(Ok(MemberChunk::Data(data))) => self.chunk = Cursor::new(data),
+                Ok(Ok(MemberChunk::Start(member_path))) => self.next_member = Some(member_path),
+                Ok(Err(e)) => return Err(e),
+                // The thread is done with the archive.
+                Err(_) => {
+                    if let Some(thread) = self.thread.take()
+                        && thread.join().is_err()
+                    {
+                        return Err(io::Error::other("reading the zip archive panicked"));
+                    }
+                    break;
+                }
+            }
+        }
+        self.chunk.fill_buf()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.chunk.consume(amount);
+    }
+}
+
+/// Send the SSIM members of `archive` to [`ZipMembers`], stopping at the
+/// first error or once the receiving reader is dropped.
+fn send_members(
+    mut archive: ZipArchive<BufReader<File>>,
+    file_path: &str,
+    buffer_size: usize,
+    sender: &SyncSender<io::Result<MemberChunk>>,
+) {
+    for index in zip_ssim_members(&archive) {
+        let name = archive.name_for_index(index).unwrap_or_default();
+        let member_path = format!("{}!{}", file_path, name);
+        if sender.send(Ok(MemberChunk::Start(member_path))).is_err() {
+            return;
+        }
+        match send_member(&mut archive, index, buffer_size, sender) {
+            Ok(true) => {}
+            Ok(false) => return,
+            Err(e) => {
+                let _ = sender.send(Err(e));
+                return;
+            }
+        }
+    }
+}
+
+/// Send the decompressed data of one member, returning whether the reader is still there.
+fn send_member(
+    archive: &mut ZipArchive<BufReader<File>>,
+    index: usize,
+    buffer_size: usize,
+    sender: &SyncSender<io::Result<MemberChunk>>,
+) -> io::Result<bool> {
+    let member = BufReader::with_capacity(buffer_size, archive.by_index(index)?);
+    let mut member = decompress(member, buffer_size)?;
+    loop {
+        let data = member.fill_buf()?.to_vec();
+        if data.is_empty() {
+            return Ok(true);
+        }
+        member.consume(data.len());
+        if sender.send(Ok(MemberChunk::Data(data))).is_err() {
+            return Ok(false);
+        }
+    }
+}
+
+/// Indices of the archive members holding SSIM data, in archive order.
+///
+/// Directories and macOS resource forks (`__MACOSX/`, `._*`) are skipped.
+fn zip_ssim_members<R: io::Read + io::Seek>(archive: &ZipArchive<R>) -> Vec<usize> {
+    (0..archive.len())
+        .filter(|&index| {
+            archive.name_for_index(index).is_some_and(|name| {
+                let file_name = name.rsplit('/').next().unwrap_or(name);
+                !name.ends_with('/')
+                    && !name.starts_with("__MACOSX/")
+                    && !file_name.starts_with("._")
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,229 @@
+//! Local and UTC departure and arrival times of flight legs, and block times.
+//!
+//! A flight leg carries its scheduled aircraft departure and arrival as
+//! `HHMM` times, each with the `±HHMM` UTC time variation of its station, and
+//! a `date_variation` of the departure and arrival days relative to the
+//! flight date. The carrier's `time_mode` says whether the scheduled times
+//! (and their date variations) are local (`L`) or UTC (`U`).
+//! [`add_leg_times`] combines the three into comparable instants, so
+//! overnight and date-line arrivals get the right day and a block time.
+
+use crate::utils::ssim_expand::{
+    FLIGHT_DATE_COLUMN, date_variations, field_error, required_column,
+};
+use crate::utils::ssim_fields::{parse_time, parse_utc_offset};
+use polars::prelude::*;
+
+/// Name of the local departure column added by [`add_leg_times`].
+pub const DEPARTURE_LOCAL_COLUMN: &str = "departure_local";
+/// Name of the UTC departure column added by [`add_leg_times`].
+pub const DEPARTURE_UTC_COLUMN: &str = "departure_utc";
+/// Name of the local arrival column added by [`add_leg_times`].
+pub const ARRIVAL_LOCAL_COLUMN: &str = "arrival_local";
+/// Name of the UTC arrival column added by [`add_leg_times`].
+pub const ARRIVAL_UTC_COLUMN: &str = "arrival_utc";
+/// Name of the block time column added by [`add_leg_times`].
+pub const BLOCK_TIME_COLUMN: &str = "block_time_minutes";
+/// Name of the implausible block time flag added by [`add_leg_times`].
+pub const BLOCK_TIME_SUSPECT_COLUMN: &str = "block_time_suspect";
+
+/// Block times above this many minutes are flagged as implausible. The
+/// longest scheduled flights take a little under 19 hours.
+pub const MAX_PLAUSIBLE_BLOCK_MINUTES: i64 = 20 * 60;
+
+const MINUTES_PER_DAY: i64 = 24 * 60;
+const MS_PER_MINUTE: i64 = 60_000;
+
+/// Add the local and UTC departure and arrival times and the block time of
+/// every flight leg of `legs`.
+///
+/// `legs` needs the `scheduled_time_of_aircraft_departure`,
+/// `time_variation_departure`, `scheduled_time_of_aircraft_arrival` and
+/// `time_variation_arrival` columns, as SSIM text or native dtypes. The
+/// optional `date_variation` column defaults to no variation and the optional
+/// `time_mode` column to local times; the flights of
+/// [`ssim_to_dataframes`](crate::ssim_to_dataframes) have no `time_mode`,
+/// the combined DataFrame of [`ssim_to_dataframe`](crate::ssim_to_dataframe)
+/// does.
+///
+/// With the `flight_date` column of
+/// [`expand_to_dated_flights`](crate::expand_to_dated_flights), the four time
+/// columns are `Datetime(ms)` instants. Without it, for flight leg patterns,
+/// they are `Duration(ms)` offsets from midnight of the flight date, e.g.
+/// `1d 01:30` for an arrival at 01:30 the next day. Two more columns are added:
+///
+/// * `block_time_minutes` — `Int64` minutes from the UTC departure to the UTC arrival
+/// * `block_time_suspect` — `true` for negative block times and those above
+///   [`MAX_PLAUSIBLE_BLOCK_MINUTES`]
+///
+/// Legs with a blank time or time variation get nulls.
+///
+/// # Errors
+/// Returns a `ColumnNotFound` error if a required column is missing, and a
+/// `ComputeError` naming the field for impossible values such as `2460`.
+///
+/// # Example
+/// ```no_run
+/// use rusty_ssim_core::{add_leg_times, expand_to_dated_flights, ssim_to_dataframe};
+///
+/// let legs = ssim_to_dataframe("schedule.ssim", None, None, Some(true), None)?;
+/// let timed = add_leg_times(&expand_to_dated_flights(&legs, None, None)?)?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn add_leg_times(legs: &DataFrame) -> PolarsResult<DataFrame> {
+    let departure_times = time_values(legs, "scheduled_time_of_aircraft_departure")?;
+    let departure_offsets = offset_values(legs, "time_variation_departure")?;
+    let arrival_times = time_values(legs, "scheduled_time_of_aircraft_arrival")?;
+    let arrival_offsets = offset_values(legs, "time_variation_arrival")?;
+    let variations = date_variations(legs)?;
+    let utc_mode = utc_time_modes(legs)?;
+    let flight_dates = flight_date_minutes(legs)?;
+
+    let mut columns: [Vec<Option<i64>>; 4] = Default::default();
+    let mut block_times = Vec::with_capacity(legs.height());
+    for row in 0..legs.height() {
+        let (departure_variation, arrival_variation) = variations[row];
+        let departure = leg_instant(
+            departure_times[row],
+            departure_offsets[row],
+            departure_variation,
+            utc_mode[row],
+        );
+        let arrival = leg_instant(
+            arrival_times[row],
+            arrival_offsets[row],
+            arrival_variation,
+            utc_mode[row],
+        );
+        let base = flight_dates.as_ref().map_or(Some(0), |dates| dates[row]);
+        let at = |minutes: Option<i64>| Some((base? + minutes?) * MS_PER_MINUTE);
+
+        columns[0].push(at(departure.map(|(local, _)| local)));
+        columns[1].push(at(departure.map(|(_, utc)| utc)));
+        columns[2].push(at(arrival.map(|(local, _)| local)));
+        columns[3].push(at(arrival.map(|(_, utc)| utc)));
+        block_times.push(departure.zip(arrival).map(|((_, from), (_, to))| to - from));
+    }
+
+    let mut timed = legs.clone();
+    let names = [
+        DEPARTURE_LOCAL_COLUMN,
+        DEPARTURE_UTC_COLUMN,
+        ARRIVAL_LOCAL_COLUMN,
+        ARRIVAL_UTC_COLUMN,
+    ];
+    for (name, values) in names.into_iter().zip(columns) {
+        let values = Int64Chunked::from_iter_options(name.into(), values.into_iter());
+        let series = match flight_dates {
+            Some(_) => values
+                .into_datetime(TimeUnit::Milliseconds, None)
+                .into_series(),
+            None => values.into_duration(TimeUnit::Milliseconds).into_series(),
+        };
+        timed.with_column(series.into_column())?;
+    }
+    let suspect: BooleanChunked = block_times
+        .iter()
+        .map(|minutes| minutes.map(|m| !(0..=MAX_PLAUSIBLE_BLOCK_MINUTES).contains(&m)))
+        .collect();
+    timed.with_column(
+        Int64Chunked::from_iter_options(BLOCK_TIME_COLUMN.into(), block_times.into_iter())
+            .into_column(),
+    )?;
+    timed.with_column(
+        suspect
+            .with_name(BLOCK_TIME_SUSPECT_COLUMN.into())
+            .into_column(),
+    )?;
+    Ok(timed)
+}
+
+/// The local and UTC minutes of a scheduled time from the flight date's midnight.
+fn leg_instant(
+    time: Option<i64>,
+    offset: Option<i64>,
+    date_variation: i8,
+    utc_mode: bool,
+) -> Option<(i64, i64)> {
+    let scheduled = date_variation as i64 * MINUTES_PER_DAY + time?;
+    let offset = offset?;
+    Some(if utc_mode {
+        (scheduled + offset, scheduled)
+    } else {
+        (scheduled, scheduled - offset)
+    })
+}
+
+/// Minutes since midnight of a `Duration` or `HHMM` text column.
+fn time_values(df: &DataFrame, name: &'static str) -> PolarsResult<Vec<Option<i64>>> {
+    let column = required_column(df, name, "to compute leg times")?;
+    if let DataType::Duration(unit) = column.dtype() {
+        let per_minute = match unit {
+            TimeUnit::Nanoseconds => 60_000_000_000,
+            TimeUnit::Microseconds => 60_000_000,
+            TimeUnit::Milliseconds => MS_PER_MINUTE,
+        };
+        return Ok(column
+            .duration()?
+            .physical()
+            .iter()
+            .map(|value| value.map(|value| value / per_minute))
+            .collect());
+    }
+    column
+        .str()?
+        .iter()
+        .map(|value| match value.map(str::trim) {
+            Some(value) if !value.is_empty() => parse_time(name, value).map(|m| Some(m.into())),
+            _ => Ok(None),
+        })
+        .collect::<Result<_, _>>()
+        .map_err(field_error)
+}
+
+/// Signed minutes of an `Int16` or `±HHMM` text UTC time variation column.
+fn offset_values(df: &DataFrame, name: &'static str) -> PolarsResult<Vec<Option<i64>>> {
+    let column = required_column(df, name, "to compute leg times")?;
+    if column.dtype().is_integer() {
+        return Ok(column.cast(&DataType::Int64)?.i64()?.iter().collect());
+    }
+    column
+        .str()?
+        .iter()
+        .map(|value| match value.map(str::trim) {
+            Some(value) if !value.is_empty() => {
+                parse_utc_offset(name, value).map(|m| Some(m.into()))
+            }
+            _ => Ok(None),
+        })
+        .collect::<Result<_, _>>()
+        .map_err(field_error)
+}
+
+/// Whether each leg's times are UTC: `time_mode` `U`. Local without the column.
+fn utc_time_modes(df: &DataFrame) -> PolarsResult<Vec<bool>> {
+    let Ok(column) = df.column("time_mode") else {
+        return Ok(vec![false; df.height()]);
+    };
+    Ok(column
+        .cast(&DataType::String)?
+        .str()?
+        .iter()
+        .map(|mode| mode.map(str::trim) == Some("U"))
+        .collect())
+}
+
+/// Minutes since 1970-01-01 of the `flight_date` column, if there is one.
+fn flight_date_minutes(df: &DataFrame) -> PolarsResult<Option<Vec<Option<i64>>>> {
+    let Ok(column) = df.column(FLIGHT_DATE_COLUMN) else {
+        return Ok(None);
+    };
+    Ok(Some(
+        column
+            .date()?
+            .physical()
+            .iter()
+            .map(|days| days.map(|days| days as i64 * MINUTES_PER_DAY))
+            .collect(),
+    ))
+}
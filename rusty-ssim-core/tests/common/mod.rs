@@ -0,0 +1,41 @@
+//! Test schedules shared by the integration tests and the benchmarks.
+
+/// One carrier with `flights_count` flights of `ivi_count` single-leg
+/// variations, each with two segment records.
+pub fn ssim_file_generator(flights_count: i16, ivi_count: i8) -> String {
+    let mut lines = vec![
+        "1AIRLINE STANDARD SCHEDULE DATA SET                                                                                                                                                            001000001".to_string(),
+    ];
+    lines.push("2UXX  0008S18 25MAR1827OCT1813OCT17                                    P                                                                                                                      1301000002".to_string());
+    for flight_idx in 0..flights_count {
+        let flight_number = format!("{:04}", 1000 + flight_idx);
+        for ivi in 0..ivi_count {
+            let padded_ivi = format!("{:02}", ivi);
+            let leg = "01";
+            lines.push(format!(
+                "3 XX {flight}{ivi_info}{leg_info}J28MAR1803APR18 2      KEF05100510+0000  AMS08000800+0200  73HY                                                             XY   13                            Y189VV738H189         000003",
+                flight = flight_number,
+                ivi_info = padded_ivi,
+                leg_info = leg
+            ));
+
+            lines.push(format!(
+                "4 XX {flight}{ivi_info}{leg_info}J              AB050AMSGRQKL 2562                                                                                                                                                    000006",
+                flight = flight_number,
+                ivi_info = padded_ivi,
+                leg_info = leg
+            ));
+
+            lines.push(format!(
+                "4 XX {flight}{ivi_info}{leg_info}J              AB127AMSGRQKLM DBA FLYFREE                                                                                                                                            000006",
+                flight = flight_number,
+                ivi_info = padded_ivi,
+                leg_info = leg
+            ));
+        }
+    }
+
+    lines.push(String::from("5 XX                                                                                                                                                                                       000011E000012"));
+
+    lines.join("\n")
+}
@@ -1,9 +1,30 @@
+mod common;
+
+use chrono::NaiveDate;
+use common::ssim_file_generator;
 use polars::prelude::*;
 use polars_testing::assert_dataframe_equal;
 use polars_testing::asserts::DataFrameEqualOptions;
 use rand::RngExt;
-use rusty_ssim_core::{ssim_to_csv, ssim_to_dataframe, ssim_to_dataframes, ssim_to_parquets};
+use rusty_ssim_core::utils::ssim_metadata::SCHEMA_VERSION;
+use rusty_ssim_core::utils::ssim_parser::{
+    CarrierRecord, FlightLegRecord, HeaderRecord, SegmentRecords, TrailerRecord,
+};
+use rusty_ssim_core::{
+    AircraftConfiguration, AircraftVersions, BatchFrames, BatchLayout, BatchProcessor, Cabin,
+    ChunkedSsimReader, ColumnSelection, CombinedDataFrameProcessor, Compression, CsvOptions,
+    IssueKind, ParquetLayout, ParquetWriterProcessor, ParseMode, SplitDataFrameProcessor,
+    SsimError, SsimFilter, SsimOptions, SsimReader, SsimRecord, WriteMode, add_dei_columns,
+    add_leg_times, add_operating_flights, add_seat_columns, codeshare_table, collapse_codeshares,
+    decode_dei, expand_to_dated_flights, read_parquet_metadata, ssim_to_csv,
+    ssim_to_csv_with_options, ssim_to_dataframe, ssim_to_dataframe_from_reader,
+    ssim_to_dataframe_with_options, ssim_to_dataframes, ssim_to_dataframes_from_reader,
+    ssim_to_dataframes_with_options, ssim_to_dated_flights, ssim_to_dated_flights_with_options,
+    ssim_to_ipc_with_options, ssim_to_ndjson_with_options, ssim_to_parquets,
+    ssim_to_parquets_with_options,
+};
 use std::fs;
+use std::io::{Read, Write};
 use tempfile::TempDir;
 
 fn multi_legged_ssim_file_generator(flights_count: i16, ivi_count: i8) -> String {
@@ -85,44 +106,6 @@ fn multi_legged_ssim_file_generator(flights_count: i16, ivi_count: i8) -> String
     lines.join("\n")
 }
 
-fn ssim_file_generator(flights_count: i16, ivi_count: i8) -> String {
-    let mut lines = vec![
-        "1AIRLINE STANDARD SCHEDULE DATA SET                                                                                                                                                            001000001".to_string(),
-    ];
-    lines.push("2UXX  0008S18 25MAR1827OCT1813OCT17                                    P                                                                                                                      1301000002".to_string());
-    for flight_idx in 0..flights_count {
-        let flight_number = format!("{:04}", 1000 + flight_idx);
-        for ivi in 0..ivi_count {
-            let padded_ivi = format!("{:02}", ivi);
-            let leg = "01";
-            lines.push(format!(
-                "3 XX {flight}{ivi_info}{leg_info}J28MAR1803APR18 2      KEF05100510+0000  AMS08000800+0200  73HY                                                             XY   13                            Y189VV738H189         000003",
-                flight = flight_number,
-                ivi_info = padded_ivi,
-                leg_info = leg
-            ));
-
-            lines.push(format!(
-                "4 XX {flight}{ivi_info}{leg_info}J              AB050AMSGRQKL 2562                                                                                                                                                    000006",
-                flight = flight_number,
-                ivi_info = padded_ivi,
-                leg_info = leg
-            ));
-
-            lines.push(format!(
-                "4 XX {flight}{ivi_info}{leg_info}J              AB127AMSGRQKLM DBA FLYFREE                                                                                                                                            000006",
-                flight = flight_number,
-                ivi_info = padded_ivi,
-                leg_info = leg
-            ));
-        }
-    }
-
-    lines.push(String::from("5 XX                                                                                                                                                                                       000011E000012"));
-
-    lines.join("\n")
-}
-
 fn multi_carrier_ssim_file_generator(flights_count: i16, ivi_count: i8) -> String {
     let mut lines = vec![
         "1AIRLINE STANDARD SCHEDULE DATA SET                                                                                                                                                            001000001".to_string(),
@@ -212,7 +195,7 @@ fn create_temp_ssim_file(flights_count: i16, ivi_count: i8, multi_leg: bool) ->
         let temp_dir = TempDir::new().expect("Failed to create temp directory");
         let file_path = temp_dir.path().join("test.ssim");
         fs::write(&file_path, content).expect("Failed to write SSIM file");
-        return (file_path.to_string_lossy().to_string(), temp_dir);
+        (file_path.to_string_lossy().to_string(), temp_dir)
     } else {
         let content = ssim_file_generator(flights_count, ivi_count);
         let temp_dir = TempDir::new().expect("Failed to create temp directory");
@@ -220,10 +203,23 @@ fn create_temp_ssim_file(flights_count: i16, ivi_count: i8, multi_leg: bool) ->
 
         fs::write(&file_path, content).expect("Failed to write SSIM file");
 
-        return (file_path.to_string_lossy().to_string(), temp_dir);
+        (file_path.to_string_lossy().to_string(), temp_dir)
     }
 }
 
+fn create_temp_ssim_file_from_lines(lines: &[&str]) -> (String, TempDir) {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let file_path = temp_dir.path().join("test.ssim");
+    fs::write(&file_path, lines.join("\n")).expect("Failed to write SSIM file");
+    (file_path.to_string_lossy().to_string(), temp_dir)
+}
+
+const HEADER_LINE: &str = "1AIRLINE STANDARD SCHEDULE DATA SET                                                                                                                                                            001000001";
+const CARRIER_LINE: &str = "2UXX  0008S18 25MAR1827OCT1813OCT17                                    P                                                                                                                      1301000002";
+const FLIGHT_LINE: &str = "3 XX   120102P28MAR1803APR18 2      KEF05100510+0000  AMS08000800+0200  73HY                                                             XY   13                            Y189VV738H189         000003";
+const SEGMENT_LINE: &str = "4 XX   120102P              AB050AMSGRQKL 2562                                                                                                                                                    000004";
+const TRAILER_LINE: &str = "5 XX                                                                                                                                                                                       000011E000012";
+
 #[cfg(test)]
 mod integration_tests {
     use super::*;
@@ -268,7 +264,7 @@ mod integration_tests {
             carriers_df.height() > 0,
             "Carriers DataFrame should not be empty"
         );
-        assert_eq!(carriers_df.get_column_names().len(), 16);
+        assert_eq!(carriers_df.get_column_names().len(), 21);
         assert_eq!(carriers_df.height(), 1);
 
         // Check flights DataFrame
@@ -325,6 +321,31 @@ mod integration_tests {
         );
     }
 
+    #[test]
+    fn test_flight_designators_across_batches() {
+        // Batches of 3 legs split every carrier's 10 legs, so the designator
+        // buffer is refilled and the duplicate indicator broadcast per batch.
+        let (file_path, _temp_dir) = create_temp_multi_ssim_file(5, 2);
+        let df = ssim_to_dataframe(&file_path, Some(3), None, Some(true), Some(false)).unwrap();
+
+        let mut expected_designators = Vec::new();
+        let mut expected_indicators = Vec::new();
+        for (airline, indicator) in [("XX", " "), ("YY", "X"), ("YY", " "), ("ZZ", " ")] {
+            for flight in 1000..1005 {
+                for ivi in ["00", "01"] {
+                    expected_designators.push(format!("{airline} _{indicator}{flight}  {ivi}  "));
+                    expected_indicators.push(indicator.to_string());
+                }
+            }
+        }
+        assert_eq!(df.height(), 40);
+        assert_eq!(date_strings(&df, "flight_designator"), expected_designators);
+        assert_eq!(
+            date_strings(&df, "control_duplicate_indicator"),
+            expected_indicators
+        );
+    }
+
     #[test]
     fn test_ssim_to_csv_success() {
         let (file_path, temp_dir) = create_temp_ssim_file(10, 2, false);
@@ -386,7 +407,7 @@ mod integration_tests {
                     .path()
                     .extension()
                     .and_then(|ext| ext.to_str())
-                    .map_or(false, |ext| ext == "parquet")
+                    .is_some_and(|ext| ext == "parquet")
             })
             .collect();
 
@@ -427,10 +448,1034 @@ mod integration_tests {
         assert!(result.is_err(), "Should fail when file doesn't exist");
 
         if let Err(e) = result {
+            assert!(matches!(e, SsimError::Io { .. }), "Expected Io error, got {:?}", e);
             println!("Expected error for nonexistent file: {:?}", e);
         }
     }
 
+    #[test]
+    fn test_truncated_record_error() {
+        let (file_path, _temp_dir) = create_temp_ssim_file_from_lines(&[
+            HEADER_LINE,
+            CARRIER_LINE,
+            FLIGHT_LINE,
+            &FLIGHT_LINE[..120],
+            TRAILER_LINE,
+        ]);
+
+        let result = ssim_to_dataframe(&file_path, Some(100), Some(8192), Some(false), Some(false));
+        match result {
+            Err(SsimError::TruncatedRecord {
+                location,
+                record_type,
+                length,
+            }) => {
+                assert_eq!(record_type, '3');
+                assert_eq!(length, 120);
+                assert_eq!(location.line_number, 4);
+                assert_eq!(location.byte_offset, 3 * 201);
+                assert_eq!(location.file_path.as_deref(), Some(file_path.as_str()));
+                assert_eq!(location.record_serial_number, None);
+            }
+            other => panic!("Expected TruncatedRecord error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_invalid_record_type_error() {
+        let (file_path, _temp_dir) = create_temp_ssim_file_from_lines(&[
+            HEADER_LINE,
+            CARRIER_LINE,
+            &FLIGHT_LINE.replacen('3', "9", 1),
+            TRAILER_LINE,
+        ]);
+
+        match ssim_to_dataframes(&file_path, Some(100), Some(8192)) {
+            Err(SsimError::InvalidRecordType { location, found }) => {
+                assert_eq!(found, '9');
+                assert_eq!(location.line_number, 3);
+                assert_eq!(location.record_serial_number.as_deref(), Some("000003"));
+            }
+            other => panic!("Expected InvalidRecordType error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_missing_carrier_context_error() {
+        let (file_path, _temp_dir) =
+            create_temp_ssim_file_from_lines(&[HEADER_LINE, FLIGHT_LINE, TRAILER_LINE]);
+
+        match ssim_to_dataframes(&file_path, Some(100), Some(8192)) {
+            Err(SsimError::MissingCarrierContext {
+                location,
+                record_type,
+            }) => {
+                assert_eq!(record_type, '3');
+                assert_eq!(location.line_number, 2);
+            }
+            other => panic!("Expected MissingCarrierContext error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_invalid_utf8_error() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let file_path = temp_dir.path().join("test.ssim");
+        let mut content = [HEADER_LINE, CARRIER_LINE, FLIGHT_LINE]
+            .join("\n")
+            .into_bytes();
+        content[2 * 201 + 40] = 0xFF;
+        fs::write(&file_path, content).expect("Failed to write SSIM file");
+
+        match ssim_to_dataframes(file_path.to_str().unwrap(), Some(100), Some(8192)) {
+            Err(SsimError::InvalidUtf8 { location, column }) => {
+                assert_eq!(location.line_number, 3);
+                assert_eq!(column, 41);
+            }
+            other => panic!("Expected InvalidUtf8 error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_lenient_mode_collects_diagnostics() {
+        let invalid_type = FLIGHT_LINE.replacen('3', "9", 1);
+        let trailer = TRAILER_LINE.replace("000011E000012", "000003E000004");
+        let (file_path, _temp_dir) = create_temp_ssim_file_from_lines(&[
+            HEADER_LINE,
+            FLIGHT_LINE,
+            CARRIER_LINE,
+            FLIGHT_LINE,
+            &FLIGHT_LINE[..120],
+            &invalid_type,
+            &FLIGHT_LINE[..80],
+            &trailer,
+        ]);
+
+        let options = SsimOptions {
+            parse_mode: ParseMode::Lenient,
+            max_reported_issues: Some(3),
+            ..Default::default()
+        };
+        let ((carriers, flights, _segments), diagnostics) =
+            ssim_to_dataframes_with_options(&file_path, &options)
+                .expect("Lenient mode should not fail on malformed lines");
+
+        assert_eq!(carriers.height(), 1);
+        assert_eq!(
+            flights.height(),
+            1,
+            "Only the valid flight leg should be kept"
+        );
+
+        assert_eq!(diagnostics.lines_read, 8);
+        assert_eq!(diagnostics.total_issues(), 4);
+        assert_eq!(diagnostics.count(IssueKind::MissingCarrierContext), 1);
+        assert_eq!(diagnostics.count(IssueKind::TruncatedRecord), 2);
+        assert_eq!(diagnostics.count(IssueKind::InvalidRecordType), 1);
+
+        let reported: Vec<(IssueKind, usize)> = diagnostics
+            .issues
+            .iter()
+            .map(|issue| (issue.kind, issue.location.line_number))
+            .collect();
+        assert_eq!(
+            reported,
+            vec![
+                (IssueKind::MissingCarrierContext, 2),
+                (IssueKind::TruncatedRecord, 5),
+                (IssueKind::InvalidRecordType, 6),
+            ],
+            "Only the first max_reported_issues lines should be listed"
+        );
+
+        let strict = ssim_to_dataframes_with_options(&file_path, &SsimOptions::default());
+        assert!(matches!(
+            strict,
+            Err(SsimError::MissingCarrierContext { .. })
+        ));
+    }
+
+    #[test]
+    fn test_header_and_trailer_fields_and_validation() {
+        let with_serial = |line: &str, serial: &str| format!("{}{}", &line[..194], serial);
+        let second_carrier = with_serial(&CARRIER_LINE.replacen("XX", "YY", 1), "000005");
+        let second_flight = with_serial(&FLIGHT_LINE.replacen("XX", "YY", 1), "000006");
+        // Correct check reference, but 'E' although another carrier follows.
+        let first_trailer = TRAILER_LINE.replace("000011E000012", "000003E000004");
+        // Wrong check reference, and 'C' although no carrier follows.
+        let second_trailer = TRAILER_LINE
+            .replacen("XX", "YY", 1)
+            .replace("000011E000012", "000099C000007");
+        let (file_path, _temp_dir) = create_temp_ssim_file_from_lines(&[
+            HEADER_LINE,
+            CARRIER_LINE,
+            FLIGHT_LINE,
+            &first_trailer,
+            &second_carrier,
+            &second_flight,
+            &second_trailer,
+        ]);
+
+        // Trailer mismatches are warnings, even in strict mode.
+        let ((carriers, flights, _segments), diagnostics) =
+            ssim_to_dataframes_with_options(&file_path, &SsimOptions::default())
+                .expect("Trailer mismatches should not fail the read");
+
+        assert_eq!(flights.height(), 2);
+        assert_eq!(carriers.height(), 2);
+        let column = |name: &str| -> Vec<Option<String>> {
+            carriers
+                .column(name)
+                .unwrap()
+                .str()
+                .unwrap()
+                .iter()
+                .map(|v| v.map(str::to_string))
+                .collect()
+        };
+        assert_eq!(
+            column("title_of_contents"),
+            vec![Some("AIRLINE STANDARD SCHEDULE DATA SET".to_string()); 2]
+        );
+        assert_eq!(
+            column("data_set_serial_number"),
+            vec![Some("001".to_string()); 2]
+        );
+        assert_eq!(
+            column("serial_number_check_reference"),
+            vec![Some("000003".to_string()), Some("000099".to_string())]
+        );
+        assert_eq!(
+            column("continuation_end_code"),
+            vec![Some("E".to_string()), Some("C".to_string())]
+        );
+
+        assert_eq!(diagnostics.count(IssueKind::TrailerCheckMismatch), 1);
+        assert_eq!(diagnostics.count(IssueKind::TrailerEndCodeMismatch), 2);
+        let reported: Vec<(IssueKind, usize)> = diagnostics
+            .issues
+            .iter()
+            .map(|issue| (issue.kind, issue.location.line_number))
+            .collect();
+        assert_eq!(
+            reported,
+            vec![
+                (IssueKind::TrailerEndCodeMismatch, 4),
+                (IssueKind::TrailerCheckMismatch, 7),
+                (IssueKind::TrailerEndCodeMismatch, 7),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_serial_number_continuity_and_line_length() {
+        let with_serial = |line: &str, serial: &str| format!("{}{}", &line[..194], serial);
+        let long_flight = format!("{}   ", with_serial(FLIGHT_LINE, "000004"));
+        let (file_path, _temp_dir) = create_temp_ssim_file_from_lines(&[
+            HEADER_LINE,
+            CARRIER_LINE,
+            FLIGHT_LINE,
+            &long_flight,
+            &with_serial(FLIGHT_LINE, "000004"),
+            &with_serial(FLIGHT_LINE, "000007"),
+            &with_serial(FLIGHT_LINE, "000002"),
+            &TRAILER_LINE.replace("000011E000012", "000002E000003"),
+        ]);
+
+        let ((_, flights, _), diagnostics) =
+            ssim_to_dataframes_with_options(&file_path, &SsimOptions::default())
+                .expect("Serial number and length problems should not fail the read");
+
+        assert_eq!(flights.height(), 5);
+        let reported: Vec<(IssueKind, usize)> = diagnostics
+            .issues
+            .iter()
+            .map(|issue| (issue.kind, issue.location.line_number))
+            .collect();
+        assert_eq!(
+            reported,
+            vec![
+                (IssueKind::InvalidLineLength, 4),
+                (IssueKind::SerialNumberDuplicate, 5),
+                (IssueKind::SerialNumberGap, 6),
+                (IssueKind::SerialNumberReset, 7),
+            ]
+        );
+        assert_eq!(
+            diagnostics.issues[2]
+                .location
+                .record_serial_number
+                .as_deref(),
+            Some("000007")
+        );
+    }
+
+    #[test]
+    fn test_records_iterator_yields_typed_records() {
+        let trailer = TRAILER_LINE.replace("000011E000012", "000004E000005");
+        let input = [
+            HEADER_LINE,
+            CARRIER_LINE,
+            FLIGHT_LINE,
+            SEGMENT_LINE,
+            &trailer,
+        ]
+        .join("\n");
+
+        let mut reader = SsimReader::from_reader(input.as_bytes(), None);
+        let records: Vec<SsimRecord> = reader
+            .records()
+            .collect::<Result<_, _>>()
+            .expect("Valid input should iterate without errors");
+
+        let kinds: Vec<(char, usize)> = records
+            .iter()
+            .map(|r| (r.record_type(), r.line_number()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![('1', 1), ('2', 2), ('3', 3), ('4', 4), ('5', 5)]
+        );
+        match &records[2] {
+            SsimRecord::FlightLeg { record, .. } => {
+                assert_eq!(record.departure_station, "KEF");
+                assert_eq!(record.arrival_station, "AMS");
+            }
+            other => panic!("Expected a flight leg, got {:?}", other),
+        }
+        match &records[4] {
+            SsimRecord::Trailer { record, .. } => {
+                assert_eq!(record.serial_number_check_reference, "000004");
+            }
+            other => panic!("Expected a trailer, got {:?}", other),
+        }
+        assert!(reader.diagnostics().is_clean());
+        assert_eq!(reader.diagnostics().lines_read, 5);
+    }
+
+    #[test]
+    fn test_records_iterator_errors_and_lenient_mode() {
+        let input = [CARRIER_LINE, &FLIGHT_LINE[..120], FLIGHT_LINE].join("\n");
+
+        // Strict mode yields the error once, then stops.
+        let mut reader = SsimReader::from_reader(input.as_bytes(), None);
+        let mut records = reader.records();
+        assert!(matches!(
+            records.next(),
+            Some(Ok(SsimRecord::Carrier { .. }))
+        ));
+        assert!(matches!(
+            records.next(),
+            Some(Err(SsimError::TruncatedRecord { .. }))
+        ));
+        assert!(records.next().is_none());
+
+        let mut reader =
+            SsimReader::from_reader(input.as_bytes(), None).with_parse_mode(ParseMode::Lenient);
+        let flight_legs = reader
+            .records()
+            .filter_map(Result::ok)
+            .filter(|r| matches!(r, SsimRecord::FlightLeg { .. }))
+            .count();
+        assert_eq!(flight_legs, 1);
+        assert_eq!(reader.diagnostics().count(IssueKind::TruncatedRecord), 1);
+    }
+
+    #[test]
+    fn test_native_dtypes_option() {
+        let (file_path, _temp_dir) = create_temp_ssim_file_from_lines(&[
+            HEADER_LINE,
+            CARRIER_LINE,
+            FLIGHT_LINE,
+            SEGMENT_LINE,
+            TRAILER_LINE,
+        ]);
+        let options = SsimOptions {
+            native_dtypes: true,
+            ..Default::default()
+        };
+
+        let (df, _) = ssim_to_dataframe_with_options(&file_path, None, None, &options)
+            .expect("Failed to read with native dtypes");
+        let dtype = |name: &str| df.column(name).unwrap().dtype().clone();
+        assert_eq!(dtype("period_of_operation_from"), DataType::Date);
+        assert_eq!(dtype("creation_date"), DataType::Date);
+        assert_eq!(
+            dtype("scheduled_time_of_aircraft_departure"),
+            DataType::Duration(TimeUnit::Milliseconds)
+        );
+        assert_eq!(dtype("time_variation_arrival"), DataType::Int16);
+        assert_eq!(dtype("leg_sequence_number"), DataType::UInt8);
+        assert_eq!(dtype("days_of_operation"), DataType::UInt8);
+        assert!(matches!(
+            dtype("departure_station"),
+            DataType::Categorical(..)
+        ));
+        assert!(matches!(dtype("board_point"), DataType::Categorical(..)));
+        assert_eq!(dtype("flight_number"), DataType::String);
+
+        let row = df.head(Some(1));
+        let value = |name: &str| row.column(name).unwrap().get(0).unwrap().into_static();
+        assert_eq!(
+            row.column("period_of_operation_from")
+                .unwrap()
+                .cast(&DataType::String)
+                .unwrap()
+                .str()
+                .unwrap()
+                .get(0),
+            Some("2018-03-28")
+        );
+        assert_eq!(
+            value("scheduled_time_of_aircraft_departure"),
+            AnyValue::Duration(310 * 60_000, TimeUnit::Milliseconds)
+        );
+        assert_eq!(value("time_variation_arrival"), AnyValue::Int16(120));
+        assert_eq!(value("leg_sequence_number"), AnyValue::UInt8(2));
+        assert_eq!(value("days_of_operation"), AnyValue::UInt8(0b10));
+
+        let ((carriers, flights, segments), _) =
+            ssim_to_dataframes_with_options(&file_path, &options)
+                .expect("Failed to read split DataFrames with native dtypes");
+        assert_eq!(
+            carriers.column("release_date").unwrap().dtype(),
+            &DataType::Date
+        );
+        assert_eq!(
+            flights.column("period_of_operation_to").unwrap().dtype(),
+            &DataType::Date
+        );
+        assert!(matches!(
+            segments.column("off_point").unwrap().dtype(),
+            DataType::Categorical(..)
+        ));
+    }
+
+    #[test]
+    fn test_native_dtypes_rejects_impossible_values() {
+        let bad_time = FLIGHT_LINE.replacen("05100510", "25000510", 1);
+        let (file_path, _temp_dir) =
+            create_temp_ssim_file_from_lines(&[HEADER_LINE, CARRIER_LINE, &bad_time]);
+        let options = SsimOptions {
+            native_dtypes: true,
+            ..Default::default()
+        };
+
+        let result = ssim_to_dataframe_with_options(&file_path, None, None, &options);
+        let error = result.expect_err("An impossible time should fail the conversion");
+        assert!(
+            error
+                .to_string()
+                .contains("scheduled_time_of_passenger_departure"),
+            "Error should name the column: {}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_column_projection_matches_full_output() {
+        let (file_path, _temp_dir) = create_temp_ssim_file(10, 2, true);
+        let names = [
+            "departure_station",
+            "flight_number",
+            "board_point",
+            "data",
+            "release_date",
+        ];
+        let options = SsimOptions {
+            columns: ColumnSelection::new(names).unwrap(),
+            ..Default::default()
+        };
+
+        let (full, _) =
+            ssim_to_dataframe_with_options(&file_path, None, None, &SsimOptions::default())
+                .expect("Failed to parse SSIM file");
+        let (projected, _) = ssim_to_dataframe_with_options(&file_path, None, None, &options)
+            .expect("Failed to parse SSIM file with a column selection");
+        assert_eq!(projected.get_column_names(), names);
+        assert_dataframe_equal!(&projected, &full.select(names).unwrap());
+
+        let ((carriers, flights, segments), _) =
+            ssim_to_dataframes_with_options(&file_path, &options)
+                .expect("Failed to parse split SSIM file with a column selection");
+        assert_eq!(carriers.get_column_names(), ["release_date"]);
+        assert_eq!(
+            flights.get_column_names(),
+            ["departure_station", "flight_number"]
+        );
+        assert_eq!(
+            segments.get_column_names(),
+            ["flight_number", "board_point", "data"]
+        );
+
+        let condensed_options = SsimOptions {
+            columns: "flight_designator,segment_data".parse().unwrap(),
+            ..Default::default()
+        };
+        let (condensed, _) =
+            ssim_to_dataframe_with_options(&file_path, Some(true), None, &condensed_options)
+                .expect("Failed to parse condensed SSIM file with a column selection");
+        let (full_condensed, _) =
+            ssim_to_dataframe_with_options(&file_path, Some(true), None, &SsimOptions::default())
+                .expect("Failed to parse condensed SSIM file");
+        assert_dataframe_equal!(
+            &condensed,
+            &full_condensed
+                .select(["flight_designator", "segment_data"])
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_filter_matches_filtering_full_output() {
+        let (file_path, _temp_dir) = create_temp_multi_ssim_file(5, 2);
+        let (full, full_diagnostics) =
+            ssim_to_dataframe_with_options(&file_path, None, None, &SsimOptions::default())
+                .expect("Failed to parse SSIM file");
+
+        let options = SsimOptions {
+            filter: SsimFilter::new().with_airlines(["YY"]),
+            ..Default::default()
+        };
+        let (filtered, diagnostics) =
+            ssim_to_dataframe_with_options(&file_path, None, None, &options)
+                .expect("Failed to parse SSIM file with an airline filter");
+        let expected = full
+            .clone()
+            .lazy()
+            .filter(col("airline_designator").eq(lit("YY ")))
+            .collect()
+            .unwrap();
+        assert!(filtered.height() > 0);
+        assert_dataframe_equal!(&filtered, &expected);
+        // Skipped carrier blocks still take part in the serial number checks.
+        assert_eq!(diagnostics.counts, full_diagnostics.counts);
+        assert_eq!(diagnostics.lines_read, full_diagnostics.lines_read);
+
+        let mut reader = SsimReader::new(&file_path, None, None)
+            .unwrap()
+            .with_filter(SsimFilter::new().with_airlines(["YY"]));
+        let carriers = reader
+            .records()
+            .filter(|record| matches!(record, Ok(SsimRecord::Carrier { .. })))
+            .count();
+        assert_eq!(carriers, 2);
+
+        let (file_path, _temp_dir) = create_temp_ssim_file(10, 3, true);
+        let (full, _) =
+            ssim_to_dataframe_with_options(&file_path, None, None, &SsimOptions::default())
+                .expect("Failed to parse SSIM file");
+        // The legs are random, so filter on a station that is sure to be there.
+        let station = full
+            .column("departure_station")
+            .unwrap()
+            .str()
+            .unwrap()
+            .get(0)
+            .unwrap()
+            .to_string();
+        let options = SsimOptions {
+            filter: SsimFilter::new()
+                .with_departure_stations([&station])
+                .with_service_types(["J"])
+                .with_date_from(NaiveDate::from_ymd_opt(2018, 4, 1).unwrap()),
+            ..Default::default()
+        };
+        let (filtered, _) = ssim_to_dataframe_with_options(&file_path, None, None, &options)
+            .expect("Failed to parse SSIM file with a leg filter");
+        let expected = full
+            .lazy()
+            .filter(col("departure_station").eq(lit(station.as_str())))
+            .collect()
+            .unwrap();
+        assert_dataframe_equal!(&filtered, &expected);
+
+        let ((_, flights, segments), _) = ssim_to_dataframes_with_options(&file_path, &options)
+            .expect("Failed to parse split SSIM file with a leg filter");
+        let flight_column = flights.column("flight_designator").unwrap().str().unwrap();
+        let segment_column = segments.column("flight_designator").unwrap().str().unwrap();
+        let kept: Vec<_> = flight_column.iter().collect();
+        assert!(segment_column.iter().all(|flight| kept.contains(&flight)));
+
+        let options = SsimOptions {
+            filter: SsimFilter::new().with_date_from(NaiveDate::from_ymd_opt(2018, 4, 4).unwrap()),
+            ..Default::default()
+        };
+        let ((_, flights, segments), _) = ssim_to_dataframes_with_options(&file_path, &options)
+            .expect("Failed to parse SSIM file with a date window");
+        assert_eq!(flights.height(), 0);
+        assert_eq!(segments.height(), 0);
+    }
+
+    #[test]
+    fn test_chunked_reader_matches_sequential() {
+        let assert_same_output = |file_path: &str, options: &SsimOptions| {
+            let (expected, expected_diagnostics) =
+                ssim_to_dataframe_with_options(file_path, Some(false), None, options)
+                    .expect("Failed to parse SSIM file");
+            let (expected_condensed, _) =
+                ssim_to_dataframe_with_options(file_path, Some(true), None, options)
+                    .expect("Failed to parse condensed SSIM file");
+            let (expected_split, _) = ssim_to_dataframes_with_options(file_path, options)
+                .expect("Failed to parse split SSIM file");
+
+            // From one line per chunk up to a single chunk.
+            for chunk_size in [1, 700, 4096, 1 << 20] {
+                let chunked_options = SsimOptions {
+                    chunk_size: Some(chunk_size),
+                    ..options.clone()
+                };
+                let (df, diagnostics) =
+                    ssim_to_dataframe_with_options(file_path, Some(false), None, &chunked_options)
+                        .expect("Failed to parse SSIM file in chunks");
+                assert_dataframe_equal!(&df, &expected);
+                assert_eq!(
+                    diagnostics, expected_diagnostics,
+                    "chunk size {}",
+                    chunk_size
+                );
+
+                let (condensed, _) =
+                    ssim_to_dataframe_with_options(file_path, Some(true), None, &chunked_options)
+                        .expect("Failed to parse condensed SSIM file in chunks");
+                assert_dataframe_equal!(&condensed, &expected_condensed);
+
+                let (split, _) = ssim_to_dataframes_with_options(file_path, &chunked_options)
+                    .expect("Failed to parse split SSIM file in chunks");
+                assert_dataframe_equal!(&split.0, &expected_split.0);
+                assert_dataframe_equal!(&split.1, &expected_split.1);
+                assert_dataframe_equal!(&split.2, &expected_split.2);
+
+                // A processor without a batch layout gets the lines of each chunk.
+                let mut through_records = ThroughRecords(SplitDataFrameProcessor::new());
+                ChunkedSsimReader::open(file_path, &chunked_options)
+                    .and_then(|reader| reader.process(&mut through_records))
+                    .expect("Failed to parse SSIM file in chunks through records");
+                let split = through_records.0.into_result().unwrap();
+                assert_dataframe_equal!(&split.1, &expected_split.1);
+                assert_dataframe_equal!(&split.2, &expected_split.2);
+            }
+        };
+
+        let (file_path, _temp_dir) = create_temp_multi_ssim_file(30, 3);
+        assert_same_output(&file_path, &SsimOptions::default());
+        let filtered = SsimOptions {
+            filter: SsimFilter::new()
+                .with_airlines(["YY"])
+                .with_departure_stations(["AMS"]),
+            ..Default::default()
+        };
+        assert_same_output(&file_path, &filtered);
+
+        let (file_path, _temp_dir) = create_temp_ssim_file(30, 3, true);
+        assert_same_output(&file_path, &SsimOptions::default());
+
+        // Serial number, trailer and end code checks across chunk boundaries.
+        let with_serial = |line: &str, serial: &str| format!("{}{}", &line[..194], serial);
+        let (file_path, _temp_dir) = create_temp_ssim_file_from_lines(&[
+            HEADER_LINE,
+            &FLIGHT_LINE[..120],
+            CARRIER_LINE,
+            FLIGHT_LINE,
+            &with_serial(&FLIGHT_LINE.replacen("0001", "0002", 1), "000003"),
+            &with_serial(&FLIGHT_LINE.replacen("0001", "0003", 1), "000009"),
+            &TRAILER_LINE.replace("000011E000012", "000003E000004"),
+            &with_serial(&CARRIER_LINE.replacen("XX", "YY", 1), "000005"),
+            &with_serial(&FLIGHT_LINE.replacen("XX", "YY", 1), "000002"),
+            &TRAILER_LINE
+                .replacen("XX", "YY", 1)
+                .replace("000011E000012", "000099C000007"),
+        ]);
+        let lenient = SsimOptions {
+            parse_mode: ParseMode::Lenient,
+            ..Default::default()
+        };
+        assert_same_output(&file_path, &lenient);
+
+        // Lines that are not valid UTF-8 are skipped the same way in chunks.
+        let mut invalid_utf8 = FLIGHT_LINE.replacen("0001", "0002", 1).into_bytes();
+        invalid_utf8[150] = 0xff;
+        let content = [
+            HEADER_LINE.as_bytes(),
+            CARRIER_LINE.as_bytes(),
+            FLIGHT_LINE.as_bytes(),
+            &invalid_utf8,
+            FLIGHT_LINE.replacen("0001", "0003", 1).as_bytes(),
+            TRAILER_LINE.as_bytes(),
+        ]
+        .join(&b'\n');
+        let (invalid_utf8_path, _temp_dir2) = create_temp_ssim_file_from_lines(&[]);
+        fs::write(&invalid_utf8_path, content).expect("Failed to write SSIM file");
+        assert_same_output(&invalid_utf8_path, &lenient);
+
+        let chunked = SsimOptions {
+            chunk_size: Some(1),
+            ..Default::default()
+        };
+        assert!(matches!(
+            ssim_to_dataframes_with_options(&file_path, &chunked),
+            Err(SsimError::MissingCarrierContext { .. })
+        ));
+    }
+
+    /// Hands the processor parsed records instead of raw lines.
+    struct ThroughRecords<P>(P);
+
+    impl<P: BatchProcessor> BatchProcessor for ThroughRecords<P> {
+        fn process_batch(
+            &mut self,
+            flight_batch: Vec<FlightLegRecord<'_>>,
+            segment_batch: Vec<SegmentRecords<'_>>,
+            carrier: Option<&CarrierRecord>,
+        ) -> PolarsResult<()> {
+            self.0.process_batch(flight_batch, segment_batch, carrier)
+        }
+
+        fn on_header(&mut self, header: &HeaderRecord) -> PolarsResult<()> {
+            self.0.on_header(header)
+        }
+
+        fn on_trailer(
+            &mut self,
+            trailer: &TrailerRecord,
+            carrier: Option<&CarrierRecord>,
+        ) -> PolarsResult<()> {
+            self.0.on_trailer(trailer, carrier)
+        }
+
+        fn on_carrier_complete(&mut self, carrier: Option<&CarrierRecord>) -> PolarsResult<()> {
+            self.0.on_carrier_complete(carrier)
+        }
+
+        fn finalize(&mut self) -> PolarsResult<()> {
+            self.0.finalize()
+        }
+    }
+
+    #[test]
+    fn test_columnar_build_matches_records() {
+        let (multi_carrier, _temp_dir) = create_temp_multi_ssim_file(20, 3);
+        let (multi_leg, _temp_dir2) = create_temp_ssim_file(20, 3, true);
+        let projected: ColumnSelection =
+            "data,onward_flight,control_duplicate_indicator,record_type"
+                .parse()
+                .unwrap();
+
+        for file_path in [&multi_carrier, &multi_leg] {
+            for columns in [ColumnSelection::all(), projected.clone()] {
+                // A small batch size spreads each carrier over several batches.
+                let open = || {
+                    SsimReader::new(file_path, Some(7), None)
+                        .expect("Failed to open SSIM file")
+                        .with_columns(columns.clone())
+                };
+
+                let mut from_lines = SplitDataFrameProcessor::new().with_columns(columns.clone());
+                open()
+                    .process(&mut from_lines)
+                    .expect("Failed to parse lines");
+                let mut from_records =
+                    ThroughRecords(SplitDataFrameProcessor::new().with_columns(columns.clone()));
+                open()
+                    .process(&mut from_records)
+                    .expect("Failed to parse records");
+
+                let (carriers, flights, segments) = from_lines.into_result().unwrap();
+                let expected = from_records.0.into_result().unwrap();
+                assert!(flights.height() > 0 && segments.height() > 0);
+                assert_dataframe_equal!(&carriers, &expected.0);
+                assert_dataframe_equal!(&flights, &expected.1);
+                assert_dataframe_equal!(&segments, &expected.2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_combined_build_matches_join() {
+        let (multi_carrier, _temp_dir) = create_temp_multi_ssim_file(20, 3);
+        let (multi_leg, _temp_dir2) = create_temp_ssim_file(20, 3, true);
+        let projected: ColumnSelection =
+            "flight_designator,time_mode,segment_data,board_point,data"
+                .parse()
+                .unwrap();
+
+        for file_path in [&multi_carrier, &multi_leg] {
+            for condense_segments in [false, true] {
+                for columns in [ColumnSelection::all(), projected.clone()] {
+                    let open = || {
+                        SsimReader::new(file_path, Some(7), None)
+                            .expect("Failed to open SSIM file")
+                            .with_columns(columns.clone())
+                    };
+                    let processor = || {
+                        CombinedDataFrameProcessor::new(condense_segments, false)
+                            .with_columns(columns.clone())
+                    };
+
+                    let mut from_lines = processor();
+                    let diagnostics = open()
+                        .process(&mut from_lines)
+                        .expect("Failed to parse lines");
+                    assert_eq!(diagnostics.count(IssueKind::OrphanSegment), 0);
+                    let mut joined = ThroughRecords(processor());
+                    open()
+                        .process(&mut joined)
+                        .expect("Failed to parse records");
+
+                    let combined = from_lines.into_result();
+                    assert!(combined.height() > 0);
+                    assert_dataframe_equal!(&combined, &joined.0.into_result());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_orphan_segments_are_reported() {
+        let other_leg = FLIGHT_LINE.replacen("  120102P", "  120101P", 1);
+        let orphan = SEGMENT_LINE.replacen("  120102P", "  120103P", 1);
+        let content = [
+            HEADER_LINE,
+            CARRIER_LINE,
+            &other_leg,
+            SEGMENT_LINE,
+            &orphan,
+            TRAILER_LINE,
+        ]
+        .join("\n");
+
+        let mut processor = CombinedDataFrameProcessor::new(false, false);
+        let diagnostics = SsimReader::from_reader(content.as_bytes(), None)
+            .process(&mut processor)
+            .expect("Failed to parse SSIM data");
+
+        // Neither segment follows a leg with its leg sequence number.
+        assert_eq!(diagnostics.count(IssueKind::OrphanSegment), 2);
+        let df = processor.into_result();
+        assert_eq!(df.height(), 1);
+        assert_eq!(df.column("data").unwrap().null_count(), 1);
+    }
+
+    #[test]
+    fn test_ssim_from_reader_matches_file() {
+        let (file_path, _temp_dir) = create_temp_ssim_file(10, 2, true);
+        let bytes = fs::read(&file_path).expect("Failed to read SSIM file");
+        let options = SsimOptions::default();
+
+        let (from_file, file_diagnostics) =
+            ssim_to_dataframe_with_options(&file_path, Some(false), Some(false), &options)
+                .expect("Failed to parse SSIM file");
+        let (from_bytes, diagnostics) =
+            ssim_to_dataframe_from_reader(bytes.as_slice(), Some(false), Some(false), &options)
+                .expect("Failed to parse SSIM bytes");
+
+        assert_dataframe_equal!(&from_file, &from_bytes);
+        assert_eq!(diagnostics.counts, file_diagnostics.counts);
+
+        let (_, flights, segments) =
+            ssim_to_dataframes(&file_path, None, None).expect("Failed to parse SSIM file");
+        let ((_, reader_flights, reader_segments), _) =
+            ssim_to_dataframes_from_reader(std::io::Cursor::new(bytes), &options)
+                .expect("Failed to parse SSIM cursor");
+        assert_eq!(flights.height(), reader_flights.height());
+        assert_eq!(segments.height(), reader_segments.height());
+    }
+
+    #[test]
+    fn test_reader_errors_have_no_file_path() {
+        let content = [HEADER_LINE, CARRIER_LINE, &FLIGHT_LINE[..120]].join("\n");
+
+        match ssim_to_dataframes_from_reader(content.as_bytes(), &SsimOptions::default()) {
+            Err(SsimError::TruncatedRecord { location, .. }) => {
+                assert_eq!(location.line_number, 3);
+                assert_eq!(location.file_path, None);
+            }
+            other => panic!("Expected TruncatedRecord error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_compressed_inputs_match_plain() {
+        let (file_path, temp_dir) = create_temp_ssim_file(20, 2, true);
+        let content = fs::read(&file_path).expect("Failed to read SSIM file");
+        let expected = ssim_to_dataframe(&file_path, None, None, Some(false), Some(false))
+            .expect("Failed to parse plain SSIM file");
+
+        let mut gzip = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gzip.write_all(&content).unwrap();
+        let mut bzip2 = bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::default());
+        bzip2.write_all(&content).unwrap();
+
+        let compressed = [
+            ("test.ssim.gz", gzip.finish().unwrap()),
+            (
+                "test.ssim.zst",
+                zstd::encode_all(content.as_slice(), 3).unwrap(),
+            ),
+            ("test.ssim.bz2", bzip2.finish().unwrap()),
+        ];
+
+        for (name, bytes) in compressed {
+            let path = temp_dir.path().join(name);
+            fs::write(&path, &bytes).expect("Failed to write compressed file");
+
+            let from_file =
+                ssim_to_dataframe(path.to_str().unwrap(), None, None, Some(false), Some(false))
+                    .unwrap_or_else(|e| panic!("Failed to parse {}: {}", name, e));
+            assert_dataframe_equal!(&expected, &from_file);
+
+            let (from_reader, _) = ssim_to_dataframe_from_reader(
+                bytes.as_slice(),
+                Some(false),
+                Some(false),
+                &SsimOptions::default(),
+            )
+            .unwrap_or_else(|e| panic!("Failed to parse {} from a reader: {}", name, e));
+            assert_dataframe_equal!(&expected, &from_reader);
+        }
+    }
+
+    #[test]
+    fn test_zip_archive_reads_every_member() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let archive_path = temp_dir.path().join("schedules.zip");
+        let first = ssim_file_generator(10, 1);
+        let second = multi_carrier_ssim_file_generator(10, 1);
+
+        let mut archive = ::zip::ZipWriter::new(fs::File::create(&archive_path).unwrap());
+        let options = ::zip::write::SimpleFileOptions::default();
+        archive.add_directory("feeds/", options).unwrap();
+        archive.start_file("feeds/first.ssim", options).unwrap();
+        archive.write_all(first.as_bytes()).unwrap();
+        archive
+            .start_file("__MACOSX/feeds/._first.ssim", options)
+            .unwrap();
+        archive.write_all(b"resource fork").unwrap();
+        archive.start_file("feeds/second.ssim", options).unwrap();
+        archive.write_all(second.as_bytes()).unwrap();
+        archive.finish().unwrap();
+
+        let first_path = temp_dir.path().join("first.ssim");
+        let second_path = temp_dir.path().join("second.ssim");
+        fs::write(&first_path, &first).unwrap();
+        fs::write(&second_path, &second).unwrap();
+        let (_, first_flights, _) =
+            ssim_to_dataframes(first_path.to_str().unwrap(), None, None).unwrap();
+        let (_, second_flights, _) =
+            ssim_to_dataframes(second_path.to_str().unwrap(), None, None).unwrap();
+
+        let ((carriers, flights, _), diagnostics) = ssim_to_dataframes_with_options(
+            archive_path.to_str().unwrap(),
+            &SsimOptions::default(),
+        )
+        .expect("Failed to parse zip archive");
+
+        assert_eq!(
+            flights.height(),
+            first_flights.height() + second_flights.height()
+        );
+        assert!(
+            carriers.height() > 1,
+            "Carriers from both members should be present"
+        );
+        assert_eq!(
+            diagnostics.lines_read,
+            first.lines().count() + second.lines().count()
+        );
+    }
+
+    #[test]
+    fn test_zip_archive_records() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let archive_path = temp_dir.path().join("schedules.zip");
+        let first = ssim_file_generator(5, 1);
+        let second = multi_carrier_ssim_file_generator(5, 1);
+
+        let mut archive = ::zip::ZipWriter::new(fs::File::create(&archive_path).unwrap());
+        let options = ::zip::write::SimpleFileOptions::default();
+        archive.start_file("first.ssim", options).unwrap();
+        archive.write_all(first.as_bytes()).unwrap();
+        archive.start_file("second.ssim", options).unwrap();
+        archive.write_all(second.as_bytes()).unwrap();
+        archive.finish().unwrap();
+
+        let archive_path = archive_path.to_str().unwrap();
+        let mut reader =
+            SsimReader::new(archive_path, None, None).expect("Failed to open zip archive");
+        let records = reader
+            .records()
+            .collect::<Result<Vec<_>, _>>()
+            .expect("Failed to read zip archive records");
+
+        let header_lines: Vec<usize> = records
+            .iter()
+            .filter_map(|record| match record {
+                SsimRecord::Header { line_number, .. } => Some(*line_number),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            header_lines,
+            vec![1, 1],
+            "Line numbers restart in each member"
+        );
+
+        let flight_legs =
+            |content: &str| content.lines().filter(|line| line.starts_with('3')).count();
+        let read_legs = records
+            .iter()
+            .filter(|record| matches!(record, SsimRecord::FlightLeg { .. }))
+            .count();
+        assert_eq!(read_legs, flight_legs(&first) + flight_legs(&second));
+
+        let diagnostics = reader.diagnostics();
+        assert_eq!(
+            diagnostics.lines_read,
+            first.lines().count() + second.lines().count()
+        );
+
+        // Serial numbers and trailers are checked per member, as in separate files.
+        let mut expected_counts = std::collections::BTreeMap::new();
+        for content in [&first, &second] {
+            let mut reader = SsimReader::from_reader(content.as_bytes(), None);
+            reader.records().for_each(drop);
+            for (kind, count) in reader.diagnostics().counts {
+                *expected_counts.entry(kind).or_insert(0) += count;
+            }
+        }
+        assert_eq!(diagnostics.counts, expected_counts);
+    }
+
+    #[test]
+    fn test_zip_member_error_location() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let archive_path = temp_dir.path().join("schedules.zip");
+
+        let mut archive = ::zip::ZipWriter::new(fs::File::create(&archive_path).unwrap());
+        let options = ::zip::write::SimpleFileOptions::default();
+        archive.start_file("bad.ssim", options).unwrap();
+        archive
+            .write_all(
+                [HEADER_LINE, CARRIER_LINE, &FLIGHT_LINE[..120]]
+                    .join("\n")
+                    .as_bytes(),
+            )
+            .unwrap();
+        archive.finish().unwrap();
+
+        let archive_path = archive_path.to_str().unwrap();
+        match ssim_to_dataframes(archive_path, None, None) {
+            Err(SsimError::TruncatedRecord { location, .. }) => {
+                assert_eq!(location.line_number, 3);
+                assert_eq!(
+                    location.file_path,
+                    Some(format!("{}!bad.ssim", archive_path))
+                );
+            }
+            other => panic!("Expected TruncatedRecord error, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_different_batch_sizes() {
         let (file_path, _temp_dir) = create_temp_ssim_file(1000, 10, false);
@@ -493,7 +1538,7 @@ mod integration_tests {
                         .path()
                         .extension()
                         .and_then(|ext| ext.to_str())
-                        .map_or(false, |ext| ext == expected_ext)
+                        .is_some_and(|ext| ext == expected_ext)
                 })
                 .count();
 
@@ -509,6 +1554,558 @@ mod integration_tests {
         }
     }
 
+    #[test]
+    fn test_parquet_streams_row_groups() {
+        let (file_path, temp_dir) = create_temp_ssim_file(20, 3, true);
+        let output_path = temp_dir.path().join("parquet");
+        let options = SsimOptions {
+            batch_size: Some(7),
+            row_group_size: Some(10),
+            ..Default::default()
+        };
+
+        for condense_segments in [false, true] {
+            ssim_to_parquets_with_options(
+                &file_path,
+                output_path.to_str(),
+                None,
+                Some(condense_segments),
+                None,
+                &options,
+            )
+            .expect("Failed to write parquet");
+            let (expected, _) = ssim_to_dataframe_with_options(
+                &file_path,
+                Some(condense_segments),
+                Some(false),
+                &options,
+            )
+            .expect("Failed to parse SSIM file");
+
+            let file = fs::File::open(output_path.join("ssim_XX_.parquet"))
+                .expect("Failed to open parquet file");
+            let mut reader = ParquetReader::new(file);
+            let row_groups: Vec<usize> = reader
+                .get_metadata()
+                .expect("Failed to read parquet metadata")
+                .row_groups
+                .iter()
+                .map(|row_group| row_group.num_rows())
+                .collect();
+            let written = reader.finish().expect("Failed to read parquet file");
+
+            // Full row groups across batch boundaries, then the rest.
+            assert_eq!(row_groups.len(), expected.height().div_ceil(10));
+            assert!(
+                row_groups[..row_groups.len() - 1]
+                    .iter()
+                    .all(|&rows| rows == 10)
+            );
+            assert_dataframe_equal!(&written, &expected);
+        }
+    }
+
+    #[test]
+    fn test_parquet_layouts() {
+        let (file_path, temp_dir) = create_temp_multi_ssim_file(5, 2);
+        let (expected, _) = ssim_to_dataframe_with_options(
+            &file_path,
+            Some(false),
+            Some(false),
+            &SsimOptions::default(),
+        )
+        .expect("Failed to parse SSIM file");
+        let write = |parquet_layout: ParquetLayout, directory: &str| {
+            let output_path = temp_dir.path().join(directory);
+            let options = SsimOptions {
+                row_group_size: Some(4),
+                parquet_layout,
+                ..Default::default()
+            };
+            ssim_to_parquets_with_options(
+                &file_path,
+                output_path.to_str(),
+                None,
+                None,
+                None,
+                &options,
+            )
+            .expect("Failed to write parquet");
+            output_path
+        };
+        let read = |path: std::path::PathBuf| {
+            ParquetReader::new(fs::File::open(path).expect("Failed to open parquet file"))
+                .finish()
+                .expect("Failed to read parquet file")
+        };
+
+        let single = write(ParquetLayout::SingleFile, "single");
+        assert_eq!(fs::read_dir(&single).unwrap().count(), 1);
+        assert_dataframe_equal!(&read(single.join("ssim.parquet")), &expected);
+
+        let layout =
+            ParquetLayout::hive(["airline_designator", "period_of_operation_from:month"]).unwrap();
+        let hive = write(layout, "hive");
+        let mut airlines: Vec<String> = fs::read_dir(&hive)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        airlines.sort();
+        assert_eq!(
+            airlines,
+            [
+                "airline_designator=XX",
+                "airline_designator=YY",
+                "airline_designator=ZZ"
+            ]
+        );
+
+        // Both YY carrier blocks land in one partition, the second in a new file as
+        // the partition is closed when a block ends; the key column is in the path.
+        let yy_month = hive.join("airline_designator=YY/period_of_operation_from_month=2018-03");
+        let mut yy = read(yy_month.join("ssim.parquet"));
+        yy.vstack_mut(&read(yy_month.join("part-1.parquet")))
+            .unwrap();
+        let is_yy = expected
+            .column("airline_designator")
+            .unwrap()
+            .str()
+            .unwrap()
+            .equal("YY ");
+        let expected_yy = expected
+            .filter(&is_yy)
+            .unwrap()
+            .drop("airline_designator")
+            .unwrap();
+        assert_dataframe_equal!(&yy, &expected_yy);
+    }
+
+    /// The Parquet files under `directory`, recursively.
+    fn parquet_files(directory: &std::path::Path) -> Vec<std::path::PathBuf> {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(directory).expect("Failed to read output directory") {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                files.extend(parquet_files(&path));
+            } else {
+                files.push(path);
+            }
+        }
+        files
+    }
+
+    /// Parquet files under `directory` still being written, which have no footer yet.
+    fn unfinished_parquet_files(directory: &std::path::Path) -> usize {
+        parquet_files(directory)
+            .into_iter()
+            .filter(|path| {
+                let file = fs::File::open(path).expect("Failed to open parquet file");
+                ParquetReader::new(file).finish().is_err()
+            })
+            .count()
+    }
+
+    /// Checks the open files of a Hive-partitioned [`ParquetWriterProcessor`] as it writes.
+    struct OpenFilesCheck {
+        writer: ParquetWriterProcessor,
+        output_path: std::path::PathBuf,
+        max_open: usize,
+        /// Whether every file is closed when a carrier block ends.
+        closed_per_carrier: bool,
+        carriers: usize,
+    }
+
+    impl OpenFilesCheck {
+        fn check_open_files(&self) {
+            let open = unfinished_parquet_files(&self.output_path);
+            assert!(
+                open <= self.max_open,
+                "{} partition files open, expected at most {}",
+                open,
+                self.max_open
+            );
+        }
+    }
+
+    impl BatchProcessor for OpenFilesCheck {
+        fn process_batch(
+            &mut self,
+            flight_batch: Vec<FlightLegRecord<'_>>,
+            segment_batch: Vec<SegmentRecords<'_>>,
+            carrier: Option<&CarrierRecord>,
+        ) -> PolarsResult<()> {
+            self.writer
+                .process_batch(flight_batch, segment_batch, carrier)?;
+            self.check_open_files();
+            Ok(())
+        }
+
+        fn batch_layout(&self) -> Option<BatchLayout> {
+            self.writer.batch_layout()
+        }
+
+        fn process_frames(
+            &mut self,
+            frames: BatchFrames,
+            carrier: Option<&CarrierRecord>,
+        ) -> PolarsResult<()> {
+            self.writer.process_frames(frames, carrier)?;
+            self.check_open_files();
+            Ok(())
+        }
+
+        fn on_header(&mut self, header: &HeaderRecord) -> PolarsResult<()> {
+            self.writer.on_header(header)
+        }
+
+        fn on_carrier_complete(&mut self, carrier: Option<&CarrierRecord>) -> PolarsResult<()> {
+            self.writer.on_carrier_complete(carrier)?;
+            self.carriers += 1;
+            if self.closed_per_carrier {
+                assert_eq!(unfinished_parquet_files(&self.output_path), 0);
+            }
+            Ok(())
+        }
+
+        fn finalize(&mut self) -> PolarsResult<()> {
+            self.writer.finalize()
+        }
+    }
+
+    #[test]
+    fn test_hive_partition_files_are_closed() {
+        let (file_path, temp_dir) = create_temp_multi_ssim_file(12, 2);
+        let (expected, _) = ssim_to_dataframe_with_options(
+            &file_path,
+            Some(false),
+            Some(false),
+            &SsimOptions::default(),
+        )
+        .expect("Failed to parse SSIM file");
+
+        // 36 airline and flight number partitions, and 12 flight number partitions
+        // that every carrier block writes to again.
+        for (keys, closed_per_carrier) in [
+            (vec!["airline_designator", "flight_number"], true),
+            (vec!["flight_number"], false),
+        ] {
+            let output_path = temp_dir.path().join(keys.join("-"));
+            let writer =
+                ParquetWriterProcessor::new(output_path.to_str().unwrap(), None, false, false)
+                    .expect("Failed to create parquet writer")
+                    .with_layout(ParquetLayout::hive(&keys).unwrap())
+                    .with_max_open_partitions(3);
+            let mut check = OpenFilesCheck {
+                writer,
+                output_path: output_path.clone(),
+                max_open: 3,
+                closed_per_carrier,
+                carriers: 0,
+            };
+            SsimReader::new(&file_path, Some(5), None)
+                .expect("Failed to open SSIM file")
+                .process(&mut check)
+                .expect("Failed to write parquet");
+
+            assert_eq!(check.carriers, 4);
+            assert_eq!(unfinished_parquet_files(&output_path), 0);
+
+            let files = parquet_files(&output_path);
+            let rows: usize = files
+                .iter()
+                .map(|path| {
+                    ParquetReader::new(fs::File::open(path).unwrap())
+                        .finish()
+                        .unwrap()
+                        .height()
+                })
+                .sum();
+            assert_eq!(rows, expected.height());
+            assert!(
+                files
+                    .iter()
+                    .any(|path| path.ends_with("flight_number=1000/part-1.parquet")),
+                "A partition written to again after its file was closed gets a new part file"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parquet_file_metadata() {
+        let (file_path, temp_dir) = create_temp_ssim_file(5, 2, false);
+        let output_path = temp_dir.path().join("parquet");
+        ssim_to_parquets(
+            &file_path,
+            output_path.to_str(),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .expect("Failed to write parquet");
+        let (carriers_df, _, _) =
+            ssim_to_dataframes(&file_path, None, None).expect("Failed to parse SSIM file");
+
+        let metadata =
+            read_parquet_metadata(output_path.join("ssim_XX_.parquet").to_str().unwrap())
+                .expect("Failed to read parquet metadata")
+                .expect("Missing rusty-ssim metadata");
+        assert_eq!(metadata.rusty_ssim_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(metadata.schema_version, SCHEMA_VERSION);
+        assert_eq!(metadata.source_file.as_deref(), Some("test.ssim"));
+        assert_eq!(metadata.carrier["season"], "S18");
+        assert_eq!(metadata.carrier["schedule_status"], "P");
+        // Every field matches the column of the same name.
+        for (name, value) in metadata.header.iter().chain(&metadata.carrier) {
+            let column = carriers_df.column(name).unwrap().str().unwrap().get(0);
+            assert_eq!(column, Some(value.as_str()), "Field {}", name);
+        }
+
+        // Files mixing carriers only get the provenance and header.
+        let options = SsimOptions {
+            parquet_layout: ParquetLayout::SingleFile,
+            ..Default::default()
+        };
+        let single_path = temp_dir.path().join("single");
+        ssim_to_parquets_with_options(&file_path, single_path.to_str(), None, None, None, &options)
+            .expect("Failed to write parquet");
+        let metadata = read_parquet_metadata(single_path.join("ssim.parquet").to_str().unwrap())
+            .unwrap()
+            .unwrap();
+        assert!(!metadata.header.is_empty());
+        assert!(metadata.carrier.is_empty());
+
+        // Parquet files written elsewhere have none.
+        let mut df = df!("a" => [1, 2]).unwrap();
+        let other_path = temp_dir.path().join("other.parquet");
+        ParquetWriter::new(fs::File::create(&other_path).unwrap())
+            .finish(&mut df)
+            .unwrap();
+        assert_eq!(
+            read_parquet_metadata(other_path.to_str().unwrap()).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_ipc_and_ndjson_outputs() {
+        let (file_path, temp_dir) = create_temp_multi_ssim_file(5, 2);
+        // Small batches so the files are written in several appends.
+        let options = SsimOptions {
+            batch_size: Some(7),
+            ..Default::default()
+        };
+        for condense in [false, true] {
+            let (expected, _) = ssim_to_dataframe_with_options(
+                &file_path,
+                Some(condense),
+                Some(false),
+                &SsimOptions::default(),
+            )
+            .expect("Failed to parse SSIM file");
+
+            let ipc_path = temp_dir.path().join(format!("ssim_{}.arrow", condense));
+            let ipc_path = ipc_path.to_str().unwrap();
+            ssim_to_ipc_with_options(&file_path, ipc_path, Some("zstd"), Some(condense), &options)
+                .expect("Failed to write IPC");
+            let ipc = IpcReader::new(fs::File::open(ipc_path).unwrap())
+                .finish()
+                .expect("Failed to read IPC");
+            assert_dataframe_equal!(&ipc, &expected);
+
+            let ndjson_path = temp_dir.path().join(format!("ssim_{}.ndjson", condense));
+            let ndjson_path = ndjson_path.to_str().unwrap();
+            // A second run replaces the file instead of adding the rows again.
+            for _ in 0..2 {
+                ssim_to_ndjson_with_options(&file_path, ndjson_path, Some(condense), &options)
+                    .expect("Failed to write NDJSON");
+            }
+            let ndjson = JsonReader::new(fs::File::open(ndjson_path).unwrap())
+                .with_json_format(JsonFormat::JsonLines)
+                .with_schema(expected.schema().clone())
+                .finish()
+                .expect("Failed to read NDJSON");
+            assert_dataframe_equal!(&ndjson, &expected);
+        }
+
+        // Native dtypes keep their Arrow types, categoricals included.
+        let native = SsimOptions {
+            native_dtypes: true,
+            ..options
+        };
+        let ipc_path = temp_dir.path().join("ssim_native.arrow");
+        let ipc_path = ipc_path.to_str().unwrap();
+        ssim_to_ipc_with_options(&file_path, ipc_path, None, Some(true), &native)
+            .expect("Failed to write IPC");
+        let (expected, _) =
+            ssim_to_dataframe_with_options(&file_path, Some(true), Some(false), &native)
+                .expect("Failed to parse SSIM file");
+        let ipc = IpcReader::new(fs::File::open(ipc_path).unwrap())
+            .finish()
+            .expect("Failed to read IPC");
+        assert_eq!(ipc.schema(), expected.schema());
+        assert_eq!(ipc.height(), expected.height());
+
+        let ndjson_path = temp_dir.path().join("ssim_native.ndjson");
+        let ndjson_path = ndjson_path.to_str().unwrap();
+        ssim_to_ndjson_with_options(&file_path, ndjson_path, Some(true), &native)
+            .expect("Failed to write NDJSON");
+        let text = fs::read_to_string(ndjson_path).unwrap();
+        assert_eq!(text.lines().count(), expected.height());
+        // Days of operation are a bitmask number instead of SSIM text.
+        let days = text.split("\"days_of_operation\":").nth(1).unwrap();
+        assert!(days.starts_with(|c: char| c.is_ascii_digit()), "{}", days);
+    }
+
+    #[test]
+    fn test_csv_write_modes_and_formats() {
+        let (file_path, temp_dir) = create_temp_multi_ssim_file(5, 2);
+        let expected = ssim_to_dataframe(&file_path, None, None, None, None)
+            .expect("Failed to parse SSIM file");
+        let csv_path = temp_dir.path().join("ssim.csv");
+        let csv_path = csv_path.to_str().unwrap();
+        let with_csv = |csv: CsvOptions| SsimOptions {
+            batch_size: Some(7),
+            csv,
+            ..Default::default()
+        };
+        let write = |csv: CsvOptions| {
+            ssim_to_csv_with_options(&file_path, csv_path, Some(false), &with_csv(csv))
+        };
+        let lines = |text: &str| text.lines().count();
+
+        // Overwriting is the default, so a second run does not double the rows.
+        for _ in 0..2 {
+            write(CsvOptions::default()).expect("Failed to write CSV");
+            let text = fs::read_to_string(csv_path).unwrap();
+            assert_eq!(lines(&text), expected.height() + 1);
+        }
+        let err = write(CsvOptions {
+            write_mode: WriteMode::FailIfExists,
+            ..Default::default()
+        });
+        assert!(err.is_err(), "An existing file should not be replaced");
+        assert_eq!(
+            lines(&fs::read_to_string(csv_path).unwrap()),
+            expected.height() + 1
+        );
+
+        // Appending keeps the one header line.
+        let append = CsvOptions {
+            write_mode: WriteMode::Append,
+            ..Default::default()
+        };
+        write(append.clone()).expect("Failed to append to CSV");
+        let text = fs::read_to_string(csv_path).unwrap();
+        assert_eq!(lines(&text), 2 * expected.height() + 1);
+        let header = text.lines().next().unwrap();
+        assert_eq!(text.lines().filter(|line| *line == header).count(), 1);
+
+        // Other columns or another separator do not fit the existing header.
+        let columns = SsimOptions {
+            columns: "airline_designator,flight_designator".parse().unwrap(),
+            ..with_csv(append.clone())
+        };
+        assert!(ssim_to_csv_with_options(&file_path, csv_path, Some(false), &columns).is_err());
+        let semicolons = CsvOptions {
+            separator: b';',
+            ..append.clone()
+        };
+        assert!(write(semicolons).is_err());
+        assert_eq!(
+            lines(&fs::read_to_string(csv_path).unwrap()),
+            2 * expected.height() + 1
+        );
+
+        // Compressed output with its own format, appended as a second gzip member.
+        let gzip_path = temp_dir.path().join("ssim.csv.gz");
+        let gzip_path = gzip_path.to_str().unwrap();
+        let gzip = CsvOptions {
+            separator: b';',
+            null_value: "NULL".to_string(),
+            date_format: Some("%d/%m/%Y".to_string()),
+            compression: Compression::Gzip,
+            ..Default::default()
+        };
+        let native = |csv: CsvOptions| SsimOptions {
+            native_dtypes: true,
+            ..with_csv(csv)
+        };
+        for write_mode in [WriteMode::Overwrite, WriteMode::Append] {
+            let csv = CsvOptions {
+                write_mode,
+                ..gzip.clone()
+            };
+            ssim_to_csv_with_options(&file_path, gzip_path, Some(false), &native(csv))
+                .expect("Failed to write gzip CSV");
+        }
+        let mut text = String::new();
+        flate2::read::MultiGzDecoder::new(fs::File::open(gzip_path).unwrap())
+            .read_to_string(&mut text)
+            .unwrap();
+        assert_eq!(lines(&text), 2 * expected.height() + 1);
+        assert!(
+            text.lines()
+                .next()
+                .unwrap()
+                .contains(";airline_designator;")
+        );
+        assert!(text.contains(";05:10;"), "Times should be written as HH:MM");
+        assert!(
+            text.contains(";25/03/2018;"),
+            "Dates should use the date format"
+        );
+        assert!(text.contains(";NULL;"), "Nulls should use the null value");
+        let zstd = CsvOptions {
+            write_mode: WriteMode::Append,
+            compression: Compression::Zstd,
+            ..gzip
+        };
+        assert!(
+            ssim_to_csv_with_options(&file_path, gzip_path, Some(false), &native(zstd)).is_err(),
+            "zstd rows should not be appended to a gzip file"
+        );
+
+        // One file per carrier, named like the Parquet files.
+        let carrier_dir = temp_dir.path().join("carriers");
+        let per_carrier = CsvOptions {
+            compression: Compression::Zstd,
+            per_carrier: true,
+            ..Default::default()
+        };
+        ssim_to_csv_with_options(
+            &file_path,
+            carrier_dir.to_str().unwrap(),
+            Some(false),
+            &with_csv(per_carrier),
+        )
+        .expect("Failed to write per-carrier CSVs");
+        let mut files: Vec<String> = fs::read_dir(&carrier_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        files.sort();
+        assert_eq!(
+            files,
+            [
+                "ssim_XX_.csv.zst",
+                "ssim_YY_.csv.zst",
+                "ssim_YY_X.csv.zst",
+                "ssim_ZZ_.csv.zst"
+            ]
+        );
+        let rows: usize = files
+            .iter()
+            .map(|file| {
+                let bytes =
+                    zstd::decode_all(fs::File::open(carrier_dir.join(file)).unwrap()).unwrap();
+                lines(&String::from_utf8(bytes).unwrap()) - 1
+            })
+            .sum();
+        assert_eq!(rows, expected.height());
+    }
+
     #[test]
     fn test_condense_segments() {
         let (file_path, _temp_dir) = create_temp_ssim_file(10, 5, false);
@@ -553,6 +2150,582 @@ mod integration_tests {
             "There should be no legs with more than 2 segments."
         );
     }
+
+    fn dated_flight_line(period: &str, days: &str, rate: &str, variation: &str) -> String {
+        let mut line = FLIGHT_LINE.to_string();
+        line.replace_range(14..28, period);
+        line.replace_range(28..35, days);
+        line.replace_range(35..36, rate);
+        line.replace_range(192..194, variation);
+        line
+    }
+
+    fn date_strings(df: &DataFrame, column: &str) -> Vec<String> {
+        df.column(column)
+            .unwrap()
+            .cast(&DataType::String)
+            .unwrap()
+            .str()
+            .unwrap()
+            .iter()
+            .map(|date| date.unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_expand_to_dated_flights() {
+        // Mondays and Wednesdays every other week, arriving the next day.
+        let biweekly = dated_flight_line("25MAR1822APR18", "1 3    ", "2", "01");
+        let (file_path, _temp_dir) =
+            create_temp_ssim_file_from_lines(&[HEADER_LINE, CARRIER_LINE, &biweekly, TRAILER_LINE]);
+
+        let dated = ssim_to_dated_flights(&file_path, None, None, None, None).unwrap();
+        assert_eq!(
+            date_strings(&dated, "flight_date"),
+            ["2018-04-02", "2018-04-04", "2018-04-16", "2018-04-18"]
+        );
+        assert_eq!(
+            date_strings(&dated, "departure_date"),
+            date_strings(&dated, "flight_date")
+        );
+        assert_eq!(
+            date_strings(&dated, "arrival_date"),
+            ["2018-04-03", "2018-04-05", "2018-04-17", "2018-04-19"]
+        );
+        assert_eq!(
+            dated.column("flight_date").unwrap().dtype(),
+            &DataType::Date
+        );
+
+        // The window bounds flight dates; native dtypes expand the same way.
+        let options = SsimOptions {
+            native_dtypes: true,
+            ..Default::default()
+        };
+        let (windowed, _) = ssim_to_dated_flights_with_options(
+            &file_path,
+            NaiveDate::from_ymd_opt(2018, 4, 3),
+            NaiveDate::from_ymd_opt(2018, 4, 16),
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            date_strings(&windowed, "flight_date"),
+            ["2018-04-04", "2018-04-16"]
+        );
+
+        // The second leg of a flight departs the day after the flight date.
+        let (_, flights, _) = ssim_to_dataframes(&file_path, None, None).unwrap();
+        let flights = flights
+            .lazy()
+            .with_column(lit("11").alias("date_variation"))
+            .collect()
+            .unwrap();
+        let dated =
+            expand_to_dated_flights(&flights, None, NaiveDate::from_ymd_opt(2018, 4, 4)).unwrap();
+        assert_eq!(
+            date_strings(&dated, "flight_date"),
+            ["2018-04-02", "2018-04-04"]
+        );
+        assert_eq!(
+            date_strings(&dated, "departure_date"),
+            ["2018-04-03", "2018-04-05"]
+        );
+    }
+
+    #[test]
+    fn test_expand_open_ended_period_needs_window() {
+        let open_ended = dated_flight_line("25MAR1800XXX00", "1234567", " ", "  ");
+        let (file_path, _temp_dir) = create_temp_ssim_file_from_lines(&[
+            HEADER_LINE,
+            CARRIER_LINE,
+            &open_ended,
+            TRAILER_LINE,
+        ]);
+
+        let err = ssim_to_dated_flights(&file_path, None, None, None, None).unwrap_err();
+        assert!(
+            err.to_string().contains("period_of_operation_to"),
+            "unexpected error: {}",
+            err
+        );
+
+        let dated = ssim_to_dated_flights(
+            &file_path,
+            None,
+            NaiveDate::from_ymd_opt(2018, 3, 31),
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(dated.height(), 7);
+    }
+
+    fn duration_minutes(df: &DataFrame, column: &str) -> Vec<Option<i64>> {
+        df.column(column)
+            .unwrap()
+            .to_physical_repr()
+            .i64()
+            .unwrap()
+            .iter()
+            .map(|ms| ms.map(|ms| ms / 60_000))
+            .collect()
+    }
+
+    fn block_times(df: &DataFrame) -> Vec<Option<i64>> {
+        df.column("block_time_minutes")
+            .unwrap()
+            .i64()
+            .unwrap()
+            .iter()
+            .collect()
+    }
+
+    #[test]
+    fn test_add_leg_times() {
+        // KEF 0510 +0000 to AMS 0800 +0200; an overnight arrival at 0130 the next day; and
+        // the same arrival without its date variation, which lands before it departs.
+        let overnight = dated_flight_line("28MAR1803APR18", " 2     ", " ", "01")
+            .replacen("AMS08000800", "AMS01300130", 1)
+            .replacen("120102P", "130102P", 1);
+        let mut negative = overnight.replacen("130102P", "140102P", 1);
+        negative.replace_range(192..194, "  ");
+        let (file_path, _temp_dir) = create_temp_ssim_file_from_lines(&[
+            HEADER_LINE,
+            CARRIER_LINE,
+            FLIGHT_LINE,
+            &overnight,
+            &negative,
+            TRAILER_LINE,
+        ]);
+
+        // Flight leg patterns without a time_mode column are in local time.
+        let (_, flights, _) = ssim_to_dataframes(&file_path, None, None).unwrap();
+        let timed = add_leg_times(&flights).unwrap();
+        assert!(matches!(
+            timed.column("departure_local").unwrap().dtype(),
+            DataType::Duration(_)
+        ));
+        assert_eq!(
+            duration_minutes(&timed, "departure_utc"),
+            [Some(310), Some(310), Some(310)]
+        );
+        assert_eq!(
+            duration_minutes(&timed, "arrival_local"),
+            [Some(480), Some(1440 + 90), Some(90)]
+        );
+        assert_eq!(block_times(&timed), [Some(50), Some(1100), Some(-340)]);
+        let suspect: Vec<_> = timed
+            .column("block_time_suspect")
+            .unwrap()
+            .bool()
+            .unwrap()
+            .iter()
+            .collect();
+        assert_eq!(suspect, [Some(false), Some(false), Some(true)]);
+
+        // The combined DataFrame carries the carrier's time mode U: the times are UTC.
+        let options = SsimOptions {
+            native_dtypes: true,
+            ..Default::default()
+        };
+        let (legs, _) =
+            ssim_to_dataframe_with_options(&file_path, Some(true), None, &options).unwrap();
+        let dated = expand_to_dated_flights(&legs, None, None).unwrap();
+        let timed = add_leg_times(&dated)
+            .unwrap()
+            .sort(["flight_number"], Default::default())
+            .unwrap();
+        assert!(matches!(
+            timed.column("arrival_utc").unwrap().dtype(),
+            DataType::Datetime(_, None)
+        ));
+        assert_eq!(
+            date_strings(&timed, "departure_utc")[0],
+            "2018-04-03 05:10:00.000"
+        );
+        assert_eq!(
+            date_strings(&timed, "arrival_local")[0],
+            "2018-04-03 10:00:00.000"
+        );
+        assert_eq!(
+            date_strings(&timed, "arrival_utc")[1],
+            "2018-04-04 01:30:00.000"
+        );
+        assert_eq!(block_times(&timed), [Some(170), Some(1220), Some(-220)]);
+
+        // Dated flights read the time mode even if the column selection leaves it out.
+        let options = SsimOptions {
+            columns: ColumnSelection::new([
+                "flight_number",
+                "period_of_operation_from",
+                "period_of_operation_to",
+                "days_of_operation",
+                "scheduled_time_of_aircraft_departure",
+                "time_variation_departure",
+                "scheduled_time_of_aircraft_arrival",
+                "time_variation_arrival",
+                "date_variation",
+            ])
+            .unwrap(),
+            ..Default::default()
+        };
+        let (dated, _) =
+            ssim_to_dated_flights_with_options(&file_path, None, None, &options).unwrap();
+        assert!(dated.column("time_mode").is_ok());
+        assert!(dated.column("airline_designator").is_err());
+        let timed = add_leg_times(&dated)
+            .unwrap()
+            .sort(["flight_number"], Default::default())
+            .unwrap();
+        assert_eq!(block_times(&timed), [Some(170), Some(1220), Some(-220)]);
+    }
+
+    #[test]
+    fn test_decode_deis() {
+        let segment_line = |dei: &str, data: &str| {
+            let mut line = SEGMENT_LINE.to_string();
+            line.replace_range(30..33, dei);
+            line.replace_range(39..39 + 155, &format!("{data:<155}"));
+            line
+        };
+        let (file_path, _temp_dir) = create_temp_ssim_file_from_lines(&[
+            HEADER_LINE,
+            CARRIER_LINE,
+            FLIGHT_LINE,
+            SEGMENT_LINE,
+            &segment_line("010", "AF 1234/KQ5678A"),
+            &segment_line("127", "KLM DBA FLYFREE"),
+            &segment_line("501", "YBJM"),
+            &segment_line("010", "NOT A FLIGHT"),
+            &segment_line("999", "UNKNOWN"),
+            &segment_line("AB1", "NOT A DEI"),
+            TRAILER_LINE,
+        ]);
+
+        let options = SsimOptions {
+            decode_deis: true,
+            ..Default::default()
+        };
+        let ((_, _, segments), _) = ssim_to_dataframes_with_options(&file_path, &options).unwrap();
+        let names: Vec<_> = segments
+            .column("dei_name")
+            .unwrap()
+            .str()
+            .unwrap()
+            .iter()
+            .collect();
+        assert_eq!(
+            names,
+            [
+                Some("Duplicate Leg Cross Reference"),
+                Some("Code Share - Commercial Duplicate"),
+                Some("Operating Airline Disclosure"),
+                Some("Meal Service Note"),
+                Some("Code Share - Commercial Duplicate"),
+                None,
+                None,
+            ]
+        );
+        let levels: Vec<_> = segments
+            .column("dei_level")
+            .unwrap()
+            .str()
+            .unwrap()
+            .iter()
+            .collect();
+        assert_eq!(
+            levels,
+            [
+                Some("leg"),
+                Some("leg"),
+                Some("leg"),
+                Some("segment"),
+                Some("leg"),
+                None,
+                None
+            ]
+        );
+        let values: Vec<Option<Vec<String>>> = segments
+            .column("dei_values")
+            .unwrap()
+            .list()
+            .unwrap()
+            .amortized_iter()
+            .map(|items| {
+                items.map(|items| {
+                    items
+                        .as_ref()
+                        .str()
+                        .unwrap()
+                        .iter()
+                        .map(|item| item.unwrap().to_string())
+                        .collect()
+                })
+            })
+            .collect();
+        assert_eq!(
+            values,
+            [
+                Some(vec!["KL 2562".to_string()]),
+                Some(vec!["AF 1234".to_string(), "KQ 5678A".to_string()]),
+                Some(vec!["KLM DBA FLYFREE".to_string()]),
+                Some(vec!["Y:B".to_string(), "J:M".to_string()]),
+                None,
+                None,
+                None,
+            ]
+        );
+
+        // Decoding the flat combined DataFrame gives the same columns.
+        let combined = ssim_to_dataframe(&file_path, None, None, Some(false), Some(false)).unwrap();
+        let decoded = add_dei_columns(&combined).unwrap();
+        assert_eq!(
+            decoded.column("dei_name").unwrap().null_count(),
+            combined.height() - 5
+        );
+
+        assert!(decode_dei(505, "XX").is_err());
+        assert!(decode_dei(999, "UNKNOWN").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_codeshares() {
+        // XX 12 lists its marketing flights in DEI 10; KL 2562 is filed as a
+        // duplicate leg pointing back at it with DEI 50, AF 1234 is not filed.
+        let segment_line = |flight: &str, dei: &str, data: &str| {
+            let mut line = SEGMENT_LINE.to_string();
+            line.replace_range(2..9, flight);
+            line.replace_range(30..39, &format!("{dei}KEFAMS"));
+            line.replace_range(39..39 + 155, &format!("{data:<155}"));
+            line
+        };
+        let mut marketing_flight = FLIGHT_LINE.to_string();
+        marketing_flight.replace_range(2..9, "KL 2562");
+        let (file_path, _temp_dir) = create_temp_ssim_file_from_lines(&[
+            HEADER_LINE,
+            CARRIER_LINE,
+            FLIGHT_LINE,
+            &segment_line("XX   12", "010", "KL 2562/AF 1234"),
+            &marketing_flight,
+            &segment_line("KL 2562", "050", "XX   12"),
+            TRAILER_LINE,
+        ]);
+        let (_, flights, segments) = ssim_to_dataframes(&file_path, None, None).unwrap();
+
+        let table = codeshare_table(&segments).unwrap();
+        assert_eq!(date_strings(&table, "marketing_airline"), ["KL", "AF"]);
+        assert_eq!(
+            date_strings(&table, "marketing_flight_number"),
+            ["2562", "1234"]
+        );
+        assert_eq!(date_strings(&table, "operating_airline"), ["XX", "XX"]);
+        assert_eq!(date_strings(&table, "board_point"), ["KEF", "KEF"]);
+        assert_eq!(
+            date_strings(&table, "data_element_identifier"),
+            ["10", "10"]
+        );
+
+        let marked = add_operating_flights(&flights, &segments, false).unwrap();
+        let operating: Vec<_> = marked
+            .column("operating_flight")
+            .unwrap()
+            .str()
+            .unwrap()
+            .iter()
+            .collect();
+        assert_eq!(operating, [None, Some("XX 12")]);
+
+        let expanded = add_operating_flights(&flights, &segments, true).unwrap();
+        assert_eq!(expanded.height(), 3);
+        assert_eq!(
+            date_strings(&expanded, "airline_designator"),
+            ["XX ", "KL ", "AF "]
+        );
+        assert_eq!(date_strings(&expanded, "flight_number")[2], "1234");
+        assert!(date_strings(&expanded, "flight_designator")[2].starts_with("AF _ 1234"));
+        let operating = expanded.column("operating_flight").unwrap().str().unwrap();
+        assert_eq!(operating.get(2), Some("XX 12"));
+        assert_eq!(date_strings(&expanded, "departure_station")[2], "KEF");
+
+        let operating_legs = collapse_codeshares(&flights, &segments).unwrap();
+        assert_eq!(date_strings(&operating_legs, "airline_designator"), ["XX "]);
+        assert_eq!(operating_legs.width(), flights.width());
+
+        // On dated flights every date of the marketing duplicate is marked.
+        let daily = |line: &str| line.replacen(" 2     ", "1234567", 1);
+        let (file_path, _temp_dir) = create_temp_ssim_file_from_lines(&[
+            HEADER_LINE,
+            CARRIER_LINE,
+            &daily(FLIGHT_LINE),
+            &segment_line("XX   12", "010", "KL 2562/AF 1234"),
+            &daily(&marketing_flight),
+            &segment_line("KL 2562", "050", "XX   12"),
+            TRAILER_LINE,
+        ]);
+        let (_, flights, segments) = ssim_to_dataframes(&file_path, None, None).unwrap();
+        let dated = expand_to_dated_flights(&flights, None, None).unwrap();
+        assert_eq!(dated.height(), 14);
+
+        let marked = add_operating_flights(&dated, &segments, false).unwrap();
+        let airlines = date_strings(&marked, "airline_designator");
+        let operating = marked.column("operating_flight").unwrap().str().unwrap();
+        for (airline, operating) in airlines.iter().zip(operating.iter()) {
+            let expected = (airline == "KL ").then_some("XX 12");
+            assert_eq!(operating, expected);
+        }
+
+        let operating_legs = collapse_codeshares(&dated, &segments).unwrap();
+        assert_eq!(operating_legs.height(), 7);
+        assert!(
+            date_strings(&operating_legs, "airline_designator")
+                .iter()
+                .all(|airline| airline == "XX ")
+        );
+    }
+
+    #[test]
+    fn test_seat_columns() {
+        let flight_line = |number: &str, configuration: &str| {
+            let mut line = FLIGHT_LINE.to_string();
+            line.replace_range(5..9, number);
+            line.replace_range(172..192, &format!("{configuration:<20}"));
+            line
+        };
+        let (file_path, temp_dir) = create_temp_ssim_file_from_lines(&[
+            HEADER_LINE,
+            CARRIER_LINE,
+            FLIGHT_LINE,
+            &flight_line("  13", "F8J30W24Y250"),
+            &flight_line("  14", "VV32A"),
+            &flight_line("  15", "VV999"),
+            TRAILER_LINE,
+        ]);
+        let versions_path = temp_dir.path().join("versions.csv");
+        fs::write(
+            &versions_path,
+            "airline_designator,aircraft_version,aircraft_configuration\nXX,32A,C12Y150\n,32A,Y180\n",
+        )
+        .unwrap();
+        let versions = AircraftVersions::from_csv(versions_path.to_str().unwrap()).unwrap();
+        assert_eq!(versions.len(), 2);
+
+        let options = SsimOptions {
+            seat_columns: true,
+            aircraft_versions: Some(versions),
+            ..Default::default()
+        };
+        let ((_, flights, _), _) = ssim_to_dataframes_with_options(&file_path, &options).unwrap();
+        let seats = |name: &str| -> Vec<Option<u32>> {
+            flights
+                .column(name)
+                .unwrap()
+                .u32()
+                .unwrap()
+                .iter()
+                .collect()
+        };
+        assert_eq!(seats("seats_f"), [Some(0), Some(8), Some(0), None]);
+        assert_eq!(seats("seats_c"), [Some(0), Some(30), Some(12), None]);
+        assert_eq!(seats("seats_w"), [Some(0), Some(24), Some(0), None]);
+        assert_eq!(seats("seats_y"), [Some(189), Some(250), Some(150), None]);
+        assert_eq!(
+            seats("total_seats"),
+            [Some(189), Some(312), Some(162), None]
+        );
+
+        let cabin_seats = flights.column("cabin_seats").unwrap().list().unwrap();
+        assert!(cabin_seats.get_as_series(3).is_none());
+        let cabins = cabin_seats.get_as_series(1).unwrap();
+        let codes = cabins.struct_().unwrap().field_by_name("cabin").unwrap();
+        let codes: Vec<_> = codes.str().unwrap().iter().collect();
+        assert_eq!(codes, [Some("F"), Some("J"), Some("W"), Some("Y")]);
+
+        // The seat columns read the aircraft configuration even if it is not selected.
+        let seat_column_names = [
+            "flight_designator",
+            "cabin_seats",
+            "seats_f",
+            "seats_c",
+            "seats_w",
+            "seats_y",
+            "total_seats",
+        ];
+        for chunk_size in [None, Some(256)] {
+            let options = SsimOptions {
+                columns: "flight_designator".parse().unwrap(),
+                chunk_size,
+                ..options.clone()
+            };
+            let ((_, selected, _), _) =
+                ssim_to_dataframes_with_options(&file_path, &options).unwrap();
+            assert_eq!(selected.get_column_names(), seat_column_names);
+            assert_eq!(
+                selected.column("total_seats").unwrap(),
+                flights.column("total_seats").unwrap()
+            );
+            let (combined, _) =
+                ssim_to_dataframe_with_options(&file_path, None, None, &options).unwrap();
+            assert_eq!(combined.get_column_names(), seat_column_names);
+        }
+
+        // The file writers add the same seat columns; CSV only the per-cabin totals.
+        let options = SsimOptions {
+            columns: "flight_designator".parse().unwrap(),
+            parquet_layout: ParquetLayout::SingleFile,
+            ..options.clone()
+        };
+        let (expected, _) =
+            ssim_to_dataframe_with_options(&file_path, None, None, &options).unwrap();
+        let ipc_path = temp_dir.path().join("seats.arrow");
+        ssim_to_ipc_with_options(&file_path, ipc_path.to_str().unwrap(), None, None, &options)
+            .unwrap();
+        let ipc = IpcReader::new(fs::File::open(&ipc_path).unwrap())
+            .finish()
+            .unwrap();
+        assert_dataframe_equal!(&ipc, &expected);
+        let parquet_dir = temp_dir.path().join("seats");
+        ssim_to_parquets_with_options(&file_path, parquet_dir.to_str(), None, None, None, &options)
+            .unwrap();
+        let parquet = ParquetReader::new(fs::File::open(parquet_dir.join("ssim.parquet")).unwrap())
+            .finish()
+            .unwrap();
+        assert_dataframe_equal!(&parquet, &expected);
+        let csv_path = temp_dir.path().join("seats.csv");
+        ssim_to_csv_with_options(&file_path, csv_path.to_str().unwrap(), None, &options).unwrap();
+        let csv = fs::read_to_string(&csv_path).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("flight_designator,seats_f,seats_c,seats_w,seats_y,total_seats")
+        );
+        let totals: Vec<_> = lines.map(|line| line.rsplit(',').next().unwrap()).collect();
+        assert_eq!(totals, ["189", "312", "162", ""]);
+
+        let raw_flights = flights.drop_many([
+            "cabin_seats",
+            "seats_f",
+            "seats_c",
+            "seats_w",
+            "seats_y",
+            "total_seats",
+        ]);
+        let without_versions = add_seat_columns(&raw_flights, None).unwrap();
+        let totals: Vec<_> = without_versions
+            .column("total_seats")
+            .unwrap()
+            .u32()
+            .unwrap()
+            .iter()
+            .collect();
+        assert_eq!(totals, [Some(189), Some(312), None, None]);
+
+        let configuration = AircraftConfiguration::parse("Y189VV738H189").unwrap();
+        assert_eq!(configuration.cabins, [('Y', 189)]);
+        assert_eq!(configuration.version.as_deref(), Some("738H189"));
+        assert_eq!(configuration.seats(Cabin::Economy), 189);
+        assert!(AircraftConfiguration::parse("189Y").is_err());
+    }
 }
 
 #[cfg(test)]
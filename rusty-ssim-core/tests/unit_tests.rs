@@ -1,6 +1,8 @@
+use rusty_ssim_core::ColumnSelection;
 use rusty_ssim_core::records::carrier_record::CarrierRecord;
 use rusty_ssim_core::utils::ssim_parser::{
-    parse_carrier_record, parse_flight_record_legs, parse_segment_record,
+    RecordDefect, check_record, parse_carrier_record, parse_flight_record_legs,
+    parse_header_record, parse_segment_record, parse_trailer_record,
 };
 
 #[cfg(test)]
@@ -26,10 +28,40 @@ mod parser_tests {
         assert_eq!(record.record_serial_number, "000002");
     }
 
+    #[test]
+    fn test_parse_header_record_valid() {
+        let line = "1AIRLINE STANDARD SCHEDULE DATA SET     1                                                                                                                                                      001000001";
+
+        let record = parse_header_record(line).expect("Header record should parse");
+        assert_eq!(
+            record.title_of_contents,
+            "AIRLINE STANDARD SCHEDULE DATA SET"
+        );
+        assert_eq!(record.number_of_seasons, "1");
+        assert_eq!(record.data_set_serial_number, "001");
+        assert_eq!(record.record_type, '1');
+        assert_eq!(record.record_serial_number, "000001");
+    }
+
+    #[test]
+    fn test_parse_trailer_record_valid() {
+        let line = "5 XX 13OCT17                                                                                                                                                                               000011E000012";
+
+        let record = parse_trailer_record(line).expect("Trailer record should parse");
+        assert_eq!(record.airline_designator, "XX ");
+        assert_eq!(record.release_date, "13OCT17");
+        assert_eq!(record.serial_number_check_reference, "000011");
+        assert_eq!(record.continuation_end_code, "E");
+        assert_eq!(record.record_type, '5');
+        assert_eq!(record.record_serial_number, "000012");
+
+        assert!(parse_trailer_record(&line[..150]).is_none());
+    }
+
     #[test]
     fn test_parse_flight_record_valid() {
         let line = "3 XX   120102P28MAR1803APR18 2      KEF05100510+0000  AMS08000800+0200  73HY                                                             XY   13                            Y189VV738H189         000003";
-        let carriers = vec![CarrierRecord {
+        let carriers = [CarrierRecord {
             airline_designator: "XX ".to_string(),
             control_duplicate_indicator: " ".to_string(),
             time_mode: "U".to_string(),
@@ -48,7 +80,7 @@ mod parser_tests {
             record_serial_number: "000002".to_string(),
         }];
 
-        let result = parse_flight_record_legs(line, &carriers[0]);
+        let result = parse_flight_record_legs(line, &carriers[0], &ColumnSelection::all());
         assert!(result.is_some());
 
         let record = result.unwrap();
@@ -103,7 +135,7 @@ mod parser_tests {
     #[test]
     fn test_parse_segment_record_valid() {
         let line = "4 XX   130101J              AB050AMSGRQKL 2562                                                                                                                                                    000006";
-        let carriers = vec![CarrierRecord {
+        let carriers = [CarrierRecord {
             airline_designator: "XX ".to_string(),
             control_duplicate_indicator: " ".to_string(),
             time_mode: "A".to_string(),
@@ -122,7 +154,7 @@ mod parser_tests {
             record_serial_number: "000002".to_string(),
         }];
 
-        let result = parse_segment_record(line, &carriers[0]);
+        let result = parse_segment_record(line, &carriers[0], &ColumnSelection::all());
         assert!(result.is_some());
 
         let record = result.unwrap();
@@ -143,4 +175,189 @@ mod parser_tests {
         assert_eq!(record.record_type, '4');
         assert_eq!(record.record_serial_number, "000006");
     }
+
+    #[test]
+    fn test_parse_truncated_records_return_none() {
+        let line = "3 XX   120102P28MAR1803APR18 2      KEF05100510+0000  AMS0800";
+        let carrier = parse_carrier_record("2UXX  0008S18 25MAR1827OCT1813OCT17                                    P                                                                                                                      1301000002").unwrap();
+
+        assert!(parse_flight_record_legs(line, &carrier, &ColumnSelection::all()).is_none());
+        assert!(parse_segment_record(line, &carrier, &ColumnSelection::all()).is_none());
+        assert!(parse_carrier_record(&line.replacen('3', "2", 1)).is_none());
+        assert_eq!(
+            check_record(line),
+            Err(RecordDefect::Truncated { length: line.len() })
+        );
+    }
+
+    #[test]
+    fn test_parse_projected_records() {
+        let carrier = parse_carrier_record("2UXX  0008S18 25MAR1827OCT1813OCT17                                    P                                                                                                                      1301000002").unwrap();
+        let flight = "3 XX   120102P28MAR1803APR18 2      KEF05100510+0000  AMS08000800+0200  73HY                                                             XY   13                            Y189VV738H189         000003";
+        let segment = "4 XX   130101J              AB050AMSGRQKL 2562                                                                                                                                                    000006";
+        let columns: ColumnSelection = "departure_station,board_point".parse().unwrap();
+
+        let record = parse_flight_record_legs(flight, &carrier, &columns).unwrap();
+        assert_eq!(record.departure_station, "KEF");
+        assert_eq!(record.aircraft_type, "");
+        assert_eq!(record.aircraft_owner, "");
+        // Join keys are always parsed.
+        assert_eq!(record.flight_designator, "XX _   12  01  ");
+        assert_eq!(record.leg_sequence_number, "02");
+
+        let duplicate = CarrierRecord {
+            control_duplicate_indicator: "X".to_string(),
+            ..carrier.clone()
+        };
+        let record = parse_flight_record_legs(flight, &duplicate, &columns).unwrap();
+        assert_eq!(record.flight_designator, "XX _X  12  01  ");
+        assert_eq!(record.control_duplicate_indicator, "X");
+
+        let record = parse_segment_record(segment, &carrier, &columns).unwrap();
+        assert_eq!(record.board_point, "AMS");
+        assert_eq!(record.off_point, "");
+        assert_eq!(record.data, "");
+
+        // Selecting the condensed column keeps the segment fields nested in it.
+        let columns = ColumnSelection::new(["flight_designator", "segment_data"]).unwrap();
+        let record = parse_segment_record(segment, &carrier, &columns).unwrap();
+        assert_eq!(record.data, "KL 2562");
+        assert_eq!(record.service_type, "");
+    }
+
+    #[test]
+    fn test_column_selection_rejects_unknown_columns() {
+        assert_eq!(
+            "flight_number, no_such_column".parse::<ColumnSelection>(),
+            Err("unknown column 'no_such_column'".to_string())
+        );
+        assert!(ColumnSelection::new(Vec::<String>::new()).is_err());
+        assert!(ColumnSelection::default().is_all());
+
+        let columns =
+            ColumnSelection::new(["arrival_station", "flight_number", "arrival_station"]).unwrap();
+        assert_eq!(
+            columns.names(),
+            Some(&["arrival_station".to_string(), "flight_number".to_string()][..])
+        );
+    }
+
+    #[test]
+    fn test_check_record_invalid_utf8_boundary() {
+        // 'É' is two bytes and straddles the boundary between the flight number (6-9) and the IVI (10-11).
+        let line = format!("3 XX   1É0102P{}", " ".repeat(186));
+        assert_eq!(
+            check_record(&line),
+            Err(RecordDefect::InvalidUtf8Boundary { column: 10 })
+        );
+
+        // Multi-byte characters fully inside a free-text field are fine.
+        let line = format!("4 XX   130101J              AB050AMSGRQKLÉ{}000006", " ".repeat(152));
+        assert_eq!(check_record(&line), Ok(()));
+    }
+}
+
+#[cfg(test)]
+mod typed_field_tests {
+    use super::*;
+    use chrono::{NaiveDate, Weekday};
+    use rusty_ssim_core::TypedFlightLeg;
+    use rusty_ssim_core::utils::ssim_fields::{
+        DaysOfWeek, parse_date, parse_time, parse_utc_offset,
+    };
+
+    const CARRIER_LINE: &str = "2UXX  0008S18 25MAR1827OCT1813OCT17                                    P                                                                                                                      1301000002";
+    const FLIGHT_LINE: &str = "3 XX   120102P28MAR1803APR18 2      KEF05100510+0000  AMS08000800+0200  73HY                                                             XY   13                            Y189VV738H189         000003";
+
+    #[test]
+    fn test_typed_flight_leg_from_record() {
+        let carrier = parse_carrier_record(CARRIER_LINE).unwrap();
+        let record =
+            parse_flight_record_legs(FLIGHT_LINE, &carrier, &ColumnSelection::all()).unwrap();
+
+        let typed = TypedFlightLeg::try_from(&record).expect("Valid flight leg should convert");
+        assert_eq!(typed.airline_designator, "XX");
+        assert_eq!(typed.flight_number, 12);
+        assert_eq!(typed.itinerary_variation_identifier, 1);
+        assert_eq!(typed.leg_sequence_number, 2);
+        assert_eq!(
+            typed.period_of_operation_from,
+            NaiveDate::from_ymd_opt(2018, 3, 28).unwrap()
+        );
+        assert_eq!(
+            typed.period_of_operation_to,
+            NaiveDate::from_ymd_opt(2018, 4, 3)
+        );
+        assert_eq!(
+            typed.days_of_operation.iter().collect::<Vec<_>>(),
+            vec![Weekday::Tue]
+        );
+        assert_eq!(typed.frequency_rate, None);
+        assert_eq!(typed.scheduled_time_of_aircraft_departure, 5 * 60 + 10);
+        assert_eq!(typed.time_variation_departure, 0);
+        assert_eq!(typed.scheduled_time_of_aircraft_arrival, 8 * 60);
+        assert_eq!(typed.time_variation_arrival, 120);
+        assert_eq!(typed.passenger_terminal_departure, None);
+        assert_eq!(
+            typed.aircraft_configuration.as_deref(),
+            Some("Y189VV738H189")
+        );
+        assert_eq!(typed.date_variation_arrival, 0);
+        assert_eq!(typed.record_serial_number, 3);
+    }
+
+    #[test]
+    fn test_typed_flight_leg_rejects_impossible_values() {
+        let carrier = parse_carrier_record(CARRIER_LINE).unwrap();
+
+        let bad_date = FLIGHT_LINE.replacen("28MAR18", "32MAR18", 1);
+        let record =
+            parse_flight_record_legs(&bad_date, &carrier, &ColumnSelection::all()).unwrap();
+        let err = TypedFlightLeg::try_from(&record).unwrap_err();
+        assert_eq!(err.field, "period_of_operation_from");
+        assert_eq!(err.value, "32MAR18");
+
+        let bad_time = FLIGHT_LINE.replacen("08000800", "24600800", 1);
+        let record =
+            parse_flight_record_legs(&bad_time, &carrier, &ColumnSelection::all()).unwrap();
+        let err = TypedFlightLeg::try_from(&record).unwrap_err();
+        assert_eq!(err.field, "scheduled_time_of_aircraft_arrival");
+        assert_eq!(
+            err.to_string(),
+            "invalid scheduled_time_of_aircraft_arrival \"2460\": time out of range"
+        );
+    }
+
+    #[test]
+    fn test_field_parsers() {
+        assert_eq!(
+            parse_date("date", "29FEB24"),
+            Ok(NaiveDate::from_ymd_opt(2024, 2, 29))
+        );
+        assert_eq!(parse_date("date", "00XXX00"), Ok(None));
+        assert!(parse_date("date", "29FEB25").is_err());
+        assert!(parse_date("date", "01FOO25").is_err());
+
+        assert_eq!(parse_time("time", "0000"), Ok(0));
+        assert_eq!(parse_time("time", "2400"), Ok(1440));
+        assert!(parse_time("time", "2401").is_err());
+        assert!(parse_time("time", "12 5").is_err());
+
+        assert_eq!(parse_utc_offset("offset", "-0530"), Ok(-330));
+        assert_eq!(parse_utc_offset("offset", "+1245"), Ok(765));
+        assert!(parse_utc_offset("offset", "+1500").is_err());
+        assert!(parse_utc_offset("offset", "0100").is_err());
+    }
+
+    #[test]
+    fn test_days_of_week() {
+        let days = DaysOfWeek::parse("days", "1 3 5 7").unwrap();
+        assert_eq!(days.len(), 4);
+        assert!(days.contains(Weekday::Wed));
+        assert!(!days.contains(Weekday::Tue));
+        assert_eq!(days.to_string(), "1 3 5 7");
+        assert_eq!(DaysOfWeek::parse("days", "1234567"), Ok(DaysOfWeek::ALL));
+        assert!(DaysOfWeek::parse("days", "3      ").is_err());
+        assert!(DaysOfWeek::parse("days", "1 3").is_err());
+    }
 }